@@ -14,5 +14,6 @@ criterion_main! {
     benchmarks::egcd::benches,
     benchmarks::factorial::benches,
     benchmarks::bigint::benches,
+    benchmarks::kernels::benches,
     benchmarks::roots::benches,
 }
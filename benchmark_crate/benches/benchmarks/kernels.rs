@@ -0,0 +1,69 @@
+use criterion::Criterion;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{rngs::StdRng, SeedableRng};
+
+// Operations that spend nearly all their time in the digit kernels. Run
+// these with and without `--features asm` to compare the assembly kernels
+// against the portable ones.
+
+fn get_rng() -> StdRng {
+    let mut seed = [0; 32];
+    for i in 1..32 {
+        seed[usize::from(i)] = i;
+    }
+    SeedableRng::from_seed(seed)
+}
+
+fn kernel_add(c: &mut Criterion) {
+    let mut rng = get_rng();
+    let x = rng.gen_biguint(1 << 16);
+    let y = rng.gen_biguint(1 << 16);
+
+    c.bench_function("kernel_add", move |b| b.iter(|| &x + &y));
+}
+
+fn kernel_sub(c: &mut Criterion) {
+    let mut rng = get_rng();
+    let x = rng.gen_biguint(1 << 16) + (BigUint::from(1u32) << (1 << 16));
+    let y = rng.gen_biguint(1 << 16);
+
+    c.bench_function("kernel_sub", move |b| b.iter(|| &x - &y));
+}
+
+// Small enough for schoolbook multiplication, so each row is one `mac_n`.
+fn kernel_mul_1k(c: &mut Criterion) {
+    let mut rng = get_rng();
+    let x = rng.gen_biguint(1 << 10);
+    let y = rng.gen_biguint(1 << 10);
+
+    c.bench_function("kernel_mul_1k", move |b| b.iter(|| &x * &y));
+}
+
+fn kernel_mul_64k(c: &mut Criterion) {
+    let mut rng = get_rng();
+    let x = rng.gen_biguint(1 << 16);
+    let y = rng.gen_biguint(1 << 16);
+
+    c.bench_function("kernel_mul_64k", move |b| b.iter(|| &x * &y));
+}
+
+// The Montgomery inner loop is also `mac_n`.
+fn kernel_modpow(c: &mut Criterion) {
+    let mut rng = get_rng();
+    let base = rng.gen_biguint(2048);
+    let e = rng.gen_biguint(2048);
+    let m = rng.gen_biguint(2048) | BigUint::from(1u32);
+
+    c.bench_function("kernel_modpow", move |b| b.iter(|| base.modpow(&e, &m)));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets =
+        kernel_add,
+        kernel_sub,
+        kernel_mul_1k,
+        kernel_mul_64k,
+        kernel_modpow,
+}
@@ -2,5 +2,6 @@ pub mod bigint;
 pub mod egcd;
 pub mod factorial;
 pub mod gcd;
+pub mod kernels;
 pub mod prime_benches;
 pub mod roots;
@@ -1,3 +1,4 @@
+use crate::algorithms::kernels::add_n;
 use crate::big_digit::{BigDigit, DoubleBigDigit, BITS};
 
 // Add with carry:
@@ -15,23 +16,21 @@ pub fn adc(a: BigDigit, b: BigDigit, acc: &mut DoubleBigDigit) -> BigDigit {
 pub fn __add2(a: &mut [BigDigit], b: &[BigDigit]) -> BigDigit {
     debug_assert!(a.len() >= b.len());
 
-    let mut carry = 0;
     let (a_lo, a_hi) = a.split_at_mut(b.len());
+    let mut carry = add_n(a_lo, b);
 
-    for (a, b) in a_lo.iter_mut().zip(b) {
-        *a = adc(*a, *b, &mut carry);
-    }
-
-    if carry != 0 {
+    if carry {
         for a in a_hi {
-            *a = adc(*a, 0, &mut carry);
-            if carry == 0 {
+            let (sum, c) = a.overflowing_add(1);
+            *a = sum;
+            carry = c;
+            if !carry {
                 break;
             }
         }
     }
 
-    carry as BigDigit
+    BigDigit::from(carry)
 }
 
 /// /Two argument addition of raw slices:
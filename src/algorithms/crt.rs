@@ -0,0 +1,115 @@
+use num_traits::{One, Zero};
+
+use crate::traits::ModInverse;
+use crate::BigUint;
+use integer::Integer;
+
+/// Solves the system of congruences `x = a (mod m)` given as `(a, m)` pairs, where the
+/// moduli need not be coprime.
+///
+/// Returns `(x, l)` with `l` the least common multiple of the moduli and `x < l` the unique
+/// solution modulo `l`, or `None` if the congruences contradict each other. Two congruences
+/// `x = a1 (mod m1)` and `x = a2 (mod m2)` are consistent exactly when `gcd(m1, m2)` divides
+/// `a2 - a1`. An empty system is solved by `(0, 1)`.
+///
+/// Panics if a modulus is zero.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::algorithms::crt_general;
+/// use num_bigint_dig::BigUint;
+///
+/// let big = |v: u32| BigUint::from(v);
+/// assert_eq!(
+///     crt_general(&[(big(2), big(6)), (big(8), big(10))]),
+///     Some((big(8), big(30)))
+/// );
+/// assert_eq!(crt_general(&[(big(1), big(4)), (big(2), big(6))]), None);
+/// ```
+pub fn crt_general(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    let mut x = BigUint::zero();
+    let mut l = BigUint::one();
+    for (a, m) in congruences {
+        assert!(!m.is_zero(), "divide by zero!");
+
+        // x + l * t = a (mod m), so l / g * t = (a - x) / g (mod m / g).
+        let g = l.gcd(m);
+        let a = a % m;
+        let d = (&a + m - &x % m) % m;
+        if !d.is_multiple_of(&g) {
+            return None;
+        }
+        let n = m / &g;
+        let t = if n.is_one() {
+            BigUint::zero()
+        } else {
+            let (_, inv) = (&l / &g).mod_inverse(&n).unwrap().into_parts();
+            d / &g * inv % &n
+        };
+        x += &l * t;
+        l *= n;
+    }
+    Some((x, l))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_crt_general() {
+        let moduli = [4u32, 6, 9, 10, 15, 1, 7];
+        // Every combination of residues, checked against a search over one period.
+        for i in 0..moduli.len() {
+            for j in 0..moduli.len() {
+                let (m1, m2) = (moduli[i], moduli[j]);
+                let l = m1 / m1.gcd(&m2) * m2;
+                for a1 in 0..m1 {
+                    for a2 in 0..m2 {
+                        let expected = (0..l).find(|x| x % m1 == a1 && x % m2 == a2);
+                        let system = [
+                            (BigUint::from(a1 + m1), BigUint::from(m1)),
+                            (BigUint::from(a2), BigUint::from(m2)),
+                        ];
+                        let found = crt_general(&system);
+                        assert_eq!(
+                            found,
+                            expected.map(|x| (BigUint::from(x), BigUint::from(l))),
+                            "x = {} mod {}, x = {} mod {}",
+                            a1,
+                            m1,
+                            a2,
+                            m2
+                        );
+                    }
+                }
+            }
+        }
+
+        assert_eq!(crt_general(&[]), Some((BigUint::zero(), BigUint::one())));
+    }
+
+    #[test]
+    fn test_crt_general_large() {
+        let p = BigUint::from(1_000_000_007u32);
+        let q = BigUint::from(998_244_353u32);
+        let x = BigUint::from(123_456_789_012_345_678u64);
+        let moduli: Vec<BigUint> = vec![&p * &p, &p * &q, &q * 12u32, BigUint::from(8u32)];
+        let system: Vec<(BigUint, BigUint)> = moduli.iter().map(|m| (&x % m, m.clone())).collect();
+        let (y, l) = crt_general(&system).unwrap();
+        assert_eq!(l, &p * &p * &q * 24u32);
+        assert_eq!(y, &x % &l);
+
+        let mut system = system;
+        system[3].0 += 1u32;
+        assert_eq!(crt_general(&system), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero!")]
+    fn test_crt_general_zero_modulus() {
+        crt_general(&[(BigUint::one(), BigUint::zero())]);
+    }
+}
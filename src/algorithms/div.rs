@@ -1,11 +1,42 @@
-use num_traits::{One, Zero};
-use smallvec::SmallVec;
 use core::cmp::Ordering;
+use core::mem;
+
+use num_traits::{One, Zero};
 
+use crate::algorithms::trace::{trace, Algorithm};
 use crate::algorithms::{add2, cmp_slice, sub2};
 use crate::big_digit::{self, BigDigit, DoubleBigDigit};
+use crate::budget::{Budget, BudgetExceeded};
+use crate::stats;
 use crate::BigUint;
 
+/// A division algorithm, for
+/// [`BigUint::div_rem_with_strategy`](../struct.BigUint.html#method.div_rem_with_strategy).
+///
+/// Every strategy gives the same results. Forcing one is useful for comparing
+/// their performance, or for checking one against the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DivStrategy {
+    /// Picks the algorithm from the operands, as `/` and `%` do: division by
+    /// a single digit for single digit divisors, and Knuth's algorithm
+    /// otherwise.
+    Auto,
+    /// Knuth's long division, algorithm D, even for single digit divisors.
+    Knuth,
+    /// Montgomery reduction for the remainder, followed by exact division for
+    /// the quotient.
+    Montgomery,
+    /// Shifting and subtracting, one bit of the quotient at a time. This is
+    /// far slower than the others, but simple enough to check by hand.
+    Binary,
+}
+
+impl Default for DivStrategy {
+    fn default() -> DivStrategy {
+        DivStrategy::Auto
+    }
+}
+
 pub fn div_rem_digit(mut a: BigUint, b: BigDigit) -> (BigUint, BigDigit) {
     let mut rem = 0;
 
@@ -18,6 +49,79 @@ pub fn div_rem_digit(mut a: BigUint, b: BigDigit) -> (BigUint, BigDigit) {
     (a.normalized(), rem)
 }
 
+/// Returns the remainder of the number with little-endian digits `a` divided by `b`, without
+/// computing the quotient.
+pub fn rem_digit(a: &[BigDigit], b: BigDigit) -> BigDigit {
+    let mut rem = 0;
+
+    for &d in a.iter().rev() {
+        let (_, r) = div_wide(rem, d, b);
+        rem = r;
+    }
+
+    rem
+}
+
+/// Returns whether the number with little-endian digits `a` is a multiple of the nonzero
+/// digit `d`, without dividing.
+///
+/// Powers of two in `d` are checked against the low digit. The odd part is then checked with
+/// a sum of the digits when it divides `BigDigit::MAX`, since the base is `1` modulo such a
+/// divisor, and with an exact division by its inverse modulo the base otherwise.
+pub fn divisible_by_digit(a: &[BigDigit], d: BigDigit) -> bool {
+    debug_assert!(d != 0);
+
+    let low = match a.first() {
+        Some(&low) => low,
+        None => return true,
+    };
+    let tz = d.trailing_zeros();
+    if low.trailing_zeros() < tz {
+        return false;
+    }
+
+    let d = d >> tz;
+    if d == 1 {
+        true
+    } else if BigDigit::MAX % d == 0 {
+        // Summing with an end-around carry keeps the sum congruent modulo `BigDigit::MAX`.
+        let mut sum: BigDigit = 0;
+        for &x in a {
+            let (s, carry) = sum.overflowing_add(x);
+            sum = s + BigDigit::from(carry);
+        }
+        sum % d == 0
+    } else {
+        divisible_by_odd_digit(a, d)
+    }
+}
+
+/// Returns whether `a` is a multiple of the odd digit `d`.
+///
+/// This runs an exact division from the low digit up, with each quotient digit found by a
+/// multiplication by `d^-1` modulo the base. The final borrow is zero exactly when the
+/// division leaves no remainder.
+fn divisible_by_odd_digit(a: &[BigDigit], d: BigDigit) -> bool {
+    debug_assert!(d & 1 == 1);
+
+    // Newton's iteration doubles the number of correct low bits, starting from 3.
+    let mut inv = d;
+    let mut bits = 3;
+    while bits < big_digit::BITS {
+        inv = inv.wrapping_mul((2 as BigDigit).wrapping_sub(d.wrapping_mul(inv)));
+        bits *= 2;
+    }
+
+    let mut borrow: BigDigit = 0;
+    for &x in a {
+        let (t, b) = x.overflowing_sub(borrow);
+        let q = t.wrapping_mul(inv);
+        let (hi, _) = big_digit::from_doublebigdigit(q as DoubleBigDigit * d as DoubleBigDigit);
+        borrow = hi + BigDigit::from(b);
+    }
+    borrow == 0
+}
+
 /// Divide a two digit numerator by a one digit divisor, returns quotient and remainder:
 ///
 /// Note: the caller must ensure that both the quotient and remainder will fit into a single digit.
@@ -28,9 +132,8 @@ pub fn div_rem_digit(mut a: BigUint, b: BigDigit) -> (BigUint, BigDigit) {
 pub fn div_wide(hi: BigDigit, lo: BigDigit, divisor: BigDigit) -> (BigDigit, BigDigit) {
     debug_assert!(hi < divisor);
 
-    let lhs = big_digit::to_doublebigdigit(hi, lo);
-    let rhs = divisor as DoubleBigDigit;
-    ((lhs / rhs) as BigDigit, (lhs % rhs) as BigDigit)
+    stats::record_div();
+    big_digit::div_wide(hi, lo, divisor)
 }
 
 pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
@@ -45,6 +148,7 @@ pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
             return (u.clone(), Zero::zero());
         }
 
+        trace(Algorithm::DivDigit, u.data.len(), 1);
         let (div, rem) = div_rem_digit(u.clone(), d.data[0]);
         return (div, rem.into());
     }
@@ -56,12 +160,20 @@ pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
         Ordering::Greater => {} // Do nothing
     }
 
+    div_rem_knuth(u, d)
+}
+
+/// Divides `u` by `d` with Knuth's algorithm D, whatever the size of `d`.
+///
+/// `d` must be nonzero and `u` greater than `d`.
+pub(crate) fn div_rem_knuth(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
     // This algorithm is from Knuth, TAOCP vol 2 section 4.3, algorithm D:
     //
     // First, normalize the arguments so the highest bit in the highest digit of the divisor is
     // set: the main loop uses the highest digit of the divisor for generating guesses, so we
     // want it to be the largest number we can efficiently divide by.
     //
+    trace(Algorithm::Knuth, u.data.len(), d.data.len());
     let shift = d.data.last().unwrap().leading_zeros() as usize;
     let mut a = u << shift;
     let b = d << shift;
@@ -80,19 +192,73 @@ pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
     // until we have a guess such that q0 * b <= a.
     //
 
+    let mut q = BigUint::zero();
+    div_rem_core(
+        &mut a,
+        &b,
+        &mut q,
+        &mut BigUint::zero(),
+        &mut BigUint::zero(),
+    );
+
+    (q, a >> shift)
+}
+
+/// Divides `u` by `d` by shifting and subtracting, finding one bit of the
+/// quotient at a time.
+///
+/// `d` must be nonzero.
+pub(crate) fn div_rem_binary(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
+    let bits = big_digit::BITS;
+    let mut q = BigUint::zero();
+    q.data.resize(u.data.len(), 0);
+    let mut r = BigUint::zero();
+    for i in (0..u.bits()).rev() {
+        r <<= 1;
+        if (u.data[i / bits] >> (i % bits)) & 1 == 1 {
+            r += 1u32;
+        }
+        if r >= *d {
+            r -= d;
+            q.data[i / bits] |= 1 << (i % bits);
+        }
+    }
+    (q.normalized(), r)
+}
+
+/// The main loop of Knuth's algorithm D, writing into caller provided storage.
+///
+/// `b` must be normalized so its top bit is set, and `a` must be at least
+/// `b`. On return `q` holds the quotient and `a` the remainder. `a0` and
+/// `prod` are scratch space, kept around so their allocations are reused.
+pub(crate) fn div_rem_core(
+    a: &mut BigUint,
+    b: &BigUint,
+    q: &mut BigUint,
+    a0: &mut BigUint,
+    prod: &mut BigUint,
+) {
+    // An unlimited budget never runs out.
+    div_rem_core_with_budget(a, b, q, a0, prod, &mut Budget::unlimited()).unwrap();
+}
+
+/// Like `div_rem_core`, but charges `budget` for each quotient digit and
+/// stops as soon as it runs out, leaving `a` and `q` unspecified.
+pub(crate) fn div_rem_core_with_budget(
+    a: &mut BigUint,
+    b: &BigUint,
+    q: &mut BigUint,
+    a0: &mut BigUint,
+    prod: &mut BigUint,
+    budget: &mut Budget,
+) -> Result<(), BudgetExceeded> {
     let bn = *b.data.last().unwrap();
     let q_len = a.data.len() - b.data.len() + 1;
-    let mut q = BigUint {
-        data: smallvec![0; q_len],
-    };
-
-    // We reuse the same temporary to avoid hitting the allocator in our inner loop - this is
-    // sized to hold a0 (in the common case; if a particular digit of the quotient is zero a0
-    // can be bigger).
-    //
-    let mut tmp = BigUint {
-        data: SmallVec::with_capacity(2),
-    };
+    if q_len > q.data.capacity() {
+        stats::record_alloc(q_len);
+    }
+    q.data.clear();
+    q.data.resize(q_len, 0);
 
     for j in (0..q_len).rev() {
         /*
@@ -105,11 +271,11 @@ pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
         if offset >= a.data.len() {
             continue;
         }
+        budget.charge(b.data.len() as u64)?;
 
         /* just avoiding a heap allocation: */
-        let mut a0 = tmp;
-        a0.data.truncate(0);
-        a0.data.extend(a.data[offset..].iter().cloned());
+        a0.data.clear();
+        a0.data.extend_from_slice(&a.data[offset..]);
 
         /*
          * q0 << j * big_digit::BITS is our actual quotient estimate - we do the shifts
@@ -117,23 +283,62 @@ pub fn div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
          * save the cost of the shifts, the rest of the arithmetic gets to work with
          * smaller numbers.
          */
-        let (mut q0, _) = div_rem_digit(a0, bn);
-        let mut prod = &b * &q0;
+        let (q0, _) = div_rem_digit(mem::take(a0), bn);
+        *a0 = q0;
+        prod.mul_assign_reuse(b, a0);
 
         while cmp_slice(&prod.data[..], &a.data[j..]) == Ordering::Greater {
-            let one: BigUint = One::one();
-            q0 = q0 - one;
-            prod = prod - &b;
+            *a0 -= 1u32;
+            *prod -= b;
         }
 
-        add2(&mut q.data[j..], &q0.data[..]);
+        add2(&mut q.data[j..], &a0.data[..]);
         sub2(&mut a.data[j..], &prod.data[..]);
         a.normalize();
-
-        tmp = q0;
     }
 
-    debug_assert!(a < b);
+    q.normalize();
+    debug_assert!(*a < *b);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    (q.normalized(), a >> shift)
+    #[test]
+    fn test_div_wide() {
+        let max = BigDigit::max_value();
+        let mut cases = vec![
+            (0, 0, 1),
+            (0, max, 1),
+            (0, max, max),
+            (max - 1, max, max),
+            (max - 1, 0, max),
+            (1, 0, 2),
+            (0, 5, 3),
+            (1 << (big_digit::BITS - 2), 0, 1 << (big_digit::BITS - 1)),
+        ];
+
+        // A xorshift generator, with a variety of divisor sizes.
+        let mut x: BigDigit = 0x2545_f491;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+        for i in 0..10_000 {
+            let d = (next() >> (i % big_digit::BITS)).max(1);
+            let hi = next() % d;
+            cases.push((hi, next(), d));
+        }
+
+        for (hi, lo, d) in cases {
+            let n = big_digit::to_doublebigdigit(hi, lo);
+            let d2 = DoubleBigDigit::from(d);
+            let expected = ((n / d2) as BigDigit, (n % d2) as BigDigit);
+            assert_eq!(div_wide(hi, lo, d), expected, "{}:{} / {}", hi, lo, d);
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::algorithms::tree::tree_reduce;
 use crate::big_digit::{BigDigit, DoubleBigDigit, BITS};
 use crate::bigint::Sign::*;
 use crate::bigint::{BigInt, ToBigInt};
@@ -543,6 +544,34 @@ fn euclid_udpate(
     }
 }
 
+/// Returns the greatest common divisor of all of `values`, or zero if there are none.
+///
+/// Neighbouring values are combined pairwise, then neighbouring results, and so on, so the
+/// operands stay about the size of the inputs instead of one accumulator being combined with
+/// each value in turn. Stops as soon as any partial result is one.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::algorithms::{gcd_of, lcm_of};
+/// use num_bigint_dig::BigUint;
+///
+/// let values: Vec<BigUint> = [12u32, 18, 30].iter().map(|&v| BigUint::from(v)).collect();
+/// assert_eq!(gcd_of(&values), BigUint::from(6u32));
+/// assert_eq!(lcm_of(&values), BigUint::from(180u32));
+/// ```
+pub fn gcd_of(values: &[BigUint]) -> BigUint {
+    tree_reduce(values.to_vec(), 0, BigUint::is_one, |a, b| a.gcd(&b)).unwrap_or_else(BigUint::zero)
+}
+
+/// Returns the least common multiple of all of `values`, or one if there are none.
+///
+/// Values are combined pairwise like in [`gcd_of`], stopping as soon as any partial result
+/// is zero.
+pub fn lcm_of(values: &[BigUint]) -> BigUint {
+    tree_reduce(values.to_vec(), 0, BigUint::is_zero, |a, b| a.lcm(&b)).unwrap_or_else(BigUint::one)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,99 @@
+//! Kernels for aarch64.
+//!
+//! The widening multiply lowers to a MUL/UMULH pair. Working on two digits per
+//! iteration lets the compiler pair the loads and stores (LDP/STP) and overlap
+//! the multiplies of neighbouring digits, while the carry stays in the ADCS
+//! chain for addition and subtraction.
+
+pub(super) fn add_n(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    let mut a_pairs = a.chunks_exact_mut(2);
+    let mut b_pairs = b.chunks_exact(2);
+    for (a, b) in (&mut a_pairs).zip(&mut b_pairs) {
+        let (s0, c0) = a[0].overflowing_add(b[0]);
+        let (s0, c1) = s0.overflowing_add(u64::from(carry));
+        let (s1, c2) = a[1].overflowing_add(b[1]);
+        let (s1, c3) = s1.overflowing_add(u64::from(c0 | c1));
+        a[0] = s0;
+        a[1] = s1;
+        carry = c2 | c3;
+    }
+    for (a, &b) in a_pairs.into_remainder().iter_mut().zip(b_pairs.remainder()) {
+        let (s, c0) = a.overflowing_add(b);
+        let (s, c1) = s.overflowing_add(u64::from(carry));
+        *a = s;
+        carry = c0 | c1;
+    }
+    carry
+}
+
+pub(super) fn sub_n(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    let mut a_pairs = a.chunks_exact_mut(2);
+    let mut b_pairs = b.chunks_exact(2);
+    for (a, b) in (&mut a_pairs).zip(&mut b_pairs) {
+        let (d0, c0) = a[0].overflowing_sub(b[0]);
+        let (d0, c1) = d0.overflowing_sub(u64::from(borrow));
+        let (d1, c2) = a[1].overflowing_sub(b[1]);
+        let (d1, c3) = d1.overflowing_sub(u64::from(c0 | c1));
+        a[0] = d0;
+        a[1] = d1;
+        borrow = c2 | c3;
+    }
+    for (a, &b) in a_pairs.into_remainder().iter_mut().zip(b_pairs.remainder()) {
+        let (d, c0) = a.overflowing_sub(b);
+        let (d, c1) = d.overflowing_sub(u64::from(borrow));
+        *a = d;
+        borrow = c0 | c1;
+    }
+    borrow
+}
+
+pub(super) fn sub_n_rev(a: &[u64], b: &mut [u64]) -> bool {
+    let mut borrow = false;
+    let mut a_pairs = a.chunks_exact(2);
+    let mut b_pairs = b.chunks_exact_mut(2);
+    for (a, b) in (&mut a_pairs).zip(&mut b_pairs) {
+        let (d0, c0) = a[0].overflowing_sub(b[0]);
+        let (d0, c1) = d0.overflowing_sub(u64::from(borrow));
+        let (d1, c2) = a[1].overflowing_sub(b[1]);
+        let (d1, c3) = d1.overflowing_sub(u64::from(c0 | c1));
+        b[0] = d0;
+        b[1] = d1;
+        borrow = c2 | c3;
+    }
+    for (&a, b) in a_pairs.remainder().iter().zip(b_pairs.into_remainder()) {
+        let (d, c0) = a.overflowing_sub(*b);
+        let (d, c1) = d.overflowing_sub(u64::from(borrow));
+        *b = d;
+        borrow = c0 | c1;
+    }
+    borrow
+}
+
+pub(super) fn mac_n(acc: &mut [u64], b: &[u64], c: u64) -> u64 {
+    let c = u128::from(c);
+    let mut carry = 0;
+    let mut acc_pairs = acc.chunks_exact_mut(2);
+    let mut b_pairs = b.chunks_exact(2);
+    for (a, b) in (&mut acc_pairs).zip(&mut b_pairs) {
+        // Both products are independent, only the additions are chained.
+        let p0 = u128::from(b[0]) * c;
+        let p1 = u128::from(b[1]) * c;
+        let t0 = p0 + u128::from(a[0]) + u128::from(carry);
+        let t1 = p1 + u128::from(a[1]) + (t0 >> 64);
+        a[0] = t0 as u64;
+        a[1] = t1 as u64;
+        carry = (t1 >> 64) as u64;
+    }
+    for (a, &b) in acc_pairs
+        .into_remainder()
+        .iter_mut()
+        .zip(b_pairs.remainder())
+    {
+        let t = u128::from(b) * c + u128::from(*a) + u128::from(carry);
+        *a = t as u64;
+        carry = (t >> 64) as u64;
+    }
+    carry
+}
@@ -0,0 +1,137 @@
+//! Digit-slice kernels at the core of addition, subtraction and multiplication.
+//!
+//! Every kernel has a portable implementation. With the `asm` feature, an
+//! architecture specific version is used instead: on x86_64 it is picked at
+//! runtime when the CPU supports ADX and BMI2, on aarch64 it is always used.
+
+use crate::big_digit::BigDigit;
+use crate::stats;
+
+#[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "aarch64"))]
+mod aarch64;
+#[cfg(not(all(feature = "asm", feature = "u64_digit", target_arch = "aarch64")))]
+mod portable;
+#[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "x86_64"))]
+mod x86_64;
+
+// The kernels used when no runtime-selected version applies.
+#[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "aarch64"))]
+use self::aarch64 as base;
+#[cfg(not(all(feature = "asm", feature = "u64_digit", target_arch = "aarch64")))]
+use self::portable as base;
+
+/// `a += b`, returning the carry out. The slices must have the same length.
+#[inline]
+pub(crate) fn add_n(a: &mut [BigDigit], b: &[BigDigit]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+
+    #[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "x86_64"))]
+    {
+        if x86_64::has_adx() {
+            return x86_64::add_n(a, b);
+        }
+    }
+
+    base::add_n(a, b)
+}
+
+/// `a -= b`, returning the borrow out. The slices must have the same length.
+#[inline]
+pub(crate) fn sub_n(a: &mut [BigDigit], b: &[BigDigit]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+
+    #[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "x86_64"))]
+    {
+        if x86_64::has_adx() {
+            return x86_64::sub_n(a, b);
+        }
+    }
+
+    base::sub_n(a, b)
+}
+
+/// `b = a - b`, returning the borrow out. The slices must have the same length.
+#[inline]
+pub(crate) fn sub_n_rev(a: &[BigDigit], b: &mut [BigDigit]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+
+    #[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "x86_64"))]
+    {
+        if x86_64::has_adx() {
+            return x86_64::sub_n_rev(a, b);
+        }
+    }
+
+    base::sub_n_rev(a, b)
+}
+
+/// `acc += b * c`, returning the carry digit. The slices must have the same length.
+#[inline]
+pub(crate) fn mac_n(acc: &mut [BigDigit], b: &[BigDigit], c: BigDigit) -> BigDigit {
+    debug_assert_eq!(acc.len(), b.len());
+    stats::record_muls(b.len());
+
+    #[cfg(all(feature = "asm", feature = "u64_digit", target_arch = "x86_64"))]
+    {
+        if x86_64::has_adx() {
+            return unsafe { x86_64::mac_n(acc, b, c) };
+        }
+    }
+
+    base::mac_n(acc, b, c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_digit::{borrowing_sub, carrying_add, carrying_mul_add};
+
+    const MAX: BigDigit = BigDigit::max_value();
+
+    fn inputs() -> [[BigDigit; 5]; 4] {
+        [
+            [0, 0, 0, 0, 0],
+            [1, 2, 3, 4, 5],
+            [MAX, MAX, MAX, MAX, MAX],
+            [MAX, 0, MAX - 1, 1, 0x5555_5555],
+        ]
+    }
+
+    // Compare against plain double-width arithmetic, one digit at a time.
+    #[test]
+    fn test_kernels() {
+        for a in &inputs() {
+            for b in &inputs() {
+                let mut sum = *a;
+                let carry = add_n(&mut sum, b);
+                let mut diff = *a;
+                let borrow = sub_n(&mut diff, b);
+                let mut rev = *b;
+                let rev_borrow = sub_n_rev(a, &mut rev);
+                assert_eq!((diff, borrow), (rev, rev_borrow));
+
+                let (mut c, mut d) = (false, false);
+                for i in 0..a.len() {
+                    let (s, c1) = carrying_add(a[i], b[i], c);
+                    let (t, d1) = borrowing_sub(a[i], b[i], d);
+                    assert_eq!((sum[i], diff[i]), (s, t));
+                    c = c1;
+                    d = d1;
+                }
+                assert_eq!((carry, borrow), (c, d));
+
+                for &m in &[0, 1, MAX, a[3]] {
+                    let mut acc = *a;
+                    let hi = mac_n(&mut acc, b, m);
+                    let mut carry = 0;
+                    for i in 0..a.len() {
+                        let (lo, h) = carrying_mul_add(b[i], m, a[i], carry);
+                        assert_eq!(acc[i], lo);
+                        carry = h;
+                    }
+                    assert_eq!(hi, carry);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Portable kernels, written in terms of the `big_digit` carry helpers.
+
+use crate::big_digit::{borrowing_sub, carrying_add, carrying_mul_add, BigDigit};
+
+pub(super) fn add_n(a: &mut [BigDigit], b: &[BigDigit]) -> bool {
+    let mut carry = false;
+    for (a, b) in a.iter_mut().zip(b) {
+        let (sum, c) = carrying_add(*a, *b, carry);
+        *a = sum;
+        carry = c;
+    }
+    carry
+}
+
+pub(super) fn sub_n(a: &mut [BigDigit], b: &[BigDigit]) -> bool {
+    let mut borrow = false;
+    for (a, b) in a.iter_mut().zip(b) {
+        let (diff, c) = borrowing_sub(*a, *b, borrow);
+        *a = diff;
+        borrow = c;
+    }
+    borrow
+}
+
+pub(super) fn sub_n_rev(a: &[BigDigit], b: &mut [BigDigit]) -> bool {
+    let mut borrow = false;
+    for (a, b) in a.iter().zip(b) {
+        let (diff, c) = borrowing_sub(*a, *b, borrow);
+        *b = diff;
+        borrow = c;
+    }
+    borrow
+}
+
+pub(super) fn mac_n(acc: &mut [BigDigit], b: &[BigDigit], c: BigDigit) -> BigDigit {
+    let mut carry = 0;
+    for (a, &b) in acc.iter_mut().zip(b) {
+        let (lo, hi) = carrying_mul_add(b, c, *a, carry);
+        *a = lo;
+        carry = hi;
+    }
+    carry
+}
@@ -0,0 +1,167 @@
+//! Assembly kernels for x86_64.
+//!
+//! `mac_n` multiplies with MULX, which leaves the flags alone, and keeps its
+//! two carry chains in separate flags: ADCX folds the high half of the
+//! previous product into the low half of this one through CF, while ADOX
+//! adds the result into the accumulator through OF. The addition and
+//! subtraction loops keep their carry in CF with ADC and SBB.
+//!
+//! The loops count an index up from `-n` to zero and step it with LEA and
+//! JRCXZ, which do not touch the flags, so no carry is saved between
+//! iterations. `mac_n` handles two digits per iteration, so the multiply of
+//! one digit overlaps the additions of the other.
+//!
+//! The `kernel_*` benchmarks in `benchmark_crate` compare these against the
+//! portable kernels when run with and without its `asm` feature.
+
+use core::arch::asm;
+
+/// Returns whether the CPU supports ADX and BMI2. The answer is cached by std.
+#[inline]
+pub(super) fn has_adx() -> bool {
+    std::is_x86_feature_detected!("adx") && std::is_x86_feature_detected!("bmi2")
+}
+
+pub(super) fn add_n(a: &mut [u64], b: &[u64]) -> bool {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return false;
+    }
+    let carry: u8;
+    // Safety: both pointers are read or written at indices below `n` only.
+    unsafe {
+        asm!(
+            "clc",
+            "2:",
+            "mov {t}, qword ptr [{a} + 8*rcx]",
+            "adc {t}, qword ptr [{b} + 8*rcx]",
+            "mov qword ptr [{a} + 8*rcx], {t}",
+            "lea rcx, [rcx + 1]",
+            "jrcxz 3f",
+            "jmp 2b",
+            "3:",
+            "setc {c}",
+            a = in(reg) a.as_mut_ptr().add(n),
+            b = in(reg) b.as_ptr().add(n),
+            inout("rcx") n.wrapping_neg() => _,
+            t = out(reg) _,
+            c = out(reg_byte) carry,
+            options(nostack),
+        );
+    }
+    carry != 0
+}
+
+pub(super) fn sub_n(a: &mut [u64], b: &[u64]) -> bool {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return false;
+    }
+    let borrow: u8;
+    // Safety: both pointers are read or written at indices below `n` only.
+    unsafe {
+        asm!(
+            "clc",
+            "2:",
+            "mov {t}, qword ptr [{a} + 8*rcx]",
+            "sbb {t}, qword ptr [{b} + 8*rcx]",
+            "mov qword ptr [{a} + 8*rcx], {t}",
+            "lea rcx, [rcx + 1]",
+            "jrcxz 3f",
+            "jmp 2b",
+            "3:",
+            "setc {c}",
+            a = in(reg) a.as_mut_ptr().add(n),
+            b = in(reg) b.as_ptr().add(n),
+            inout("rcx") n.wrapping_neg() => _,
+            t = out(reg) _,
+            c = out(reg_byte) borrow,
+            options(nostack),
+        );
+    }
+    borrow != 0
+}
+
+pub(super) fn sub_n_rev(a: &[u64], b: &mut [u64]) -> bool {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return false;
+    }
+    let borrow: u8;
+    // Safety: both pointers are read or written at indices below `n` only.
+    unsafe {
+        asm!(
+            "clc",
+            "2:",
+            "mov {t}, qword ptr [{a} + 8*rcx]",
+            "sbb {t}, qword ptr [{b} + 8*rcx]",
+            "mov qword ptr [{b} + 8*rcx], {t}",
+            "lea rcx, [rcx + 1]",
+            "jrcxz 3f",
+            "jmp 2b",
+            "3:",
+            "setc {c}",
+            a = in(reg) a.as_ptr().add(n),
+            b = in(reg) b.as_mut_ptr().add(n),
+            inout("rcx") n.wrapping_neg() => _,
+            t = out(reg) _,
+            c = out(reg_byte) borrow,
+            options(nostack),
+        );
+    }
+    borrow != 0
+}
+
+/// # Safety
+///
+/// The CPU must support ADX and BMI2, see `has_adx`.
+pub(super) unsafe fn mac_n(acc: &mut [u64], b: &[u64], c: u64) -> u64 {
+    let n = acc.len().min(b.len());
+    if n == 0 {
+        return 0;
+    }
+    let hi: u64;
+    // Safety: both pointers are read or written at indices below `n` only,
+    // and the caller has checked for ADX and BMI2.
+    asm!(
+        // Clears CF and OF, and the high half carried into the first digit.
+        "xor {hi:e}, {hi:e}",
+        // Takes one digit first if `n` is odd, then two per iteration.
+        "test {n:e}, 1",
+        "jz 2f",
+        "mulx {next}, {lo}, qword ptr [{b} + 8*rcx]",
+        "adcx {lo}, {hi}",
+        "adox {lo}, qword ptr [{acc} + 8*rcx]",
+        "mov qword ptr [{acc} + 8*rcx], {lo}",
+        "mov {hi}, {next}",
+        "lea rcx, [rcx + 1]",
+        "jrcxz 3f",
+        "2:",
+        "mulx {next}, {lo}, qword ptr [{b} + 8*rcx]",
+        "adcx {lo}, {hi}",
+        "adox {lo}, qword ptr [{acc} + 8*rcx]",
+        "mov qword ptr [{acc} + 8*rcx], {lo}",
+        "mulx {hi}, {lo}, qword ptr [{b} + 8*rcx + 8]",
+        "adcx {lo}, {next}",
+        "adox {lo}, qword ptr [{acc} + 8*rcx + 8]",
+        "mov qword ptr [{acc} + 8*rcx + 8], {lo}",
+        "lea rcx, [rcx + 2]",
+        "jrcxz 3f",
+        "jmp 2b",
+        "3:",
+        // The carry out of a row is at most `c`, so this cannot overflow.
+        "mov {lo:e}, 0",
+        "adcx {hi}, {lo}",
+        "adox {hi}, {lo}",
+        acc = in(reg) acc.as_mut_ptr().add(n),
+        b = in(reg) b.as_ptr().add(n),
+        n = in(reg) n,
+        inout("rcx") n.wrapping_neg() => _,
+        in("rdx") c,
+        hi = out(reg) hi,
+        lo = out(reg) _,
+        next = out(reg) _,
+        options(nostack),
+    );
+    hi
+}
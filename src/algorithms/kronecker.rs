@@ -0,0 +1,135 @@
+use alloc::vec::Vec;
+use core::cmp;
+
+use num_traits::Zero;
+
+use crate::big_digit::{self, BigDigit};
+use crate::BigUint;
+
+/// Packs polynomial coefficients into one integer, coefficient `i` taking bits
+/// `i * slot_bits .. (i + 1) * slot_bits`, so that the polynomial is evaluated at
+/// x = 2^`slot_bits` (Kronecker substitution).
+///
+/// Multiplying two packed polynomials multiplies the polynomials, as long as every
+/// coefficient of the product fits in a slot: for n coefficients below 2^a in one factor
+/// and below 2^b in the other, `slot_bits >= a + b + log2(n)` is enough. The product is
+/// then recovered with [`unpack_coefficients`].
+///
+/// Panics if `slot_bits` is zero or a coefficient doesn't fit in its slot.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::algorithms::{pack_coefficients, unpack_coefficients};
+/// use num_bigint_dig::BigUint;
+///
+/// // (1 + 2x)(3 + 4x) = 3 + 10x + 8x^2
+/// let a = pack_coefficients(&[BigUint::from(1u32), BigUint::from(2u32)], 8);
+/// let b = pack_coefficients(&[BigUint::from(3u32), BigUint::from(4u32)], 8);
+/// let product: Vec<BigUint> = [3u32, 10, 8].iter().map(|&c| BigUint::from(c)).collect();
+/// assert_eq!(unpack_coefficients(&(a * b), 8), product);
+/// ```
+pub fn pack_coefficients(coefficients: &[BigUint], slot_bits: usize) -> BigUint {
+    assert!(slot_bits > 0, "slot_bits must be positive");
+
+    let total_bits = coefficients.len() * slot_bits;
+    let mut data: Vec<BigDigit> = vec![0; (total_bits + big_digit::BITS - 1) / big_digit::BITS];
+    for (i, c) in coefficients.iter().enumerate() {
+        assert!(
+            c.bits() <= slot_bits,
+            "coefficient does not fit in its slot"
+        );
+
+        let offset = i * slot_bits;
+        let (start, shift) = (offset / big_digit::BITS, offset % big_digit::BITS);
+        for (j, &d) in c.data.iter().enumerate() {
+            data[start + j] |= d << shift;
+            if shift > 0 && d >> (big_digit::BITS - shift) != 0 {
+                data[start + j + 1] |= d >> (big_digit::BITS - shift);
+            }
+        }
+    }
+    BigUint::new_native(data.into())
+}
+
+/// Splits `packed` into coefficients of `slot_bits` bits each, the inverse of
+/// [`pack_coefficients`].
+///
+/// Returns one coefficient per slot up to the highest non-zero one, so trailing zero
+/// coefficients are dropped and zero gives an empty vector.
+///
+/// Panics if `slot_bits` is zero.
+pub fn unpack_coefficients(packed: &BigUint, slot_bits: usize) -> Vec<BigUint> {
+    assert!(slot_bits > 0, "slot_bits must be positive");
+
+    let count = (packed.bits() + slot_bits - 1) / slot_bits;
+    let mut coefficients = Vec::with_capacity(count);
+    for i in 0..count {
+        let offset = i * slot_bits;
+        let start = offset / big_digit::BITS;
+        let end = cmp::min(
+            packed.data.len(),
+            (offset + slot_bits + big_digit::BITS - 1) / big_digit::BITS,
+        );
+        let mut c = BigUint::from_slice_native(&packed.data[start..end]);
+        c >>= offset % big_digit::BITS;
+        c.keep_low_bits_assign(slot_bits);
+        coefficients.push(c);
+    }
+    debug_assert!(coefficients.last().map_or(true, |c| !c.is_zero()));
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_traits::Pow;
+
+    #[test]
+    fn test_kronecker_round_trip() {
+        for &slot_bits in &[1, 7, 32, 64, 65, 100] {
+            let coefficients: Vec<BigUint> = (1..20u32)
+                .map(|i| Pow::pow(&BigUint::from(i), 13u32).truncate_to_bits(slot_bits))
+                .collect();
+            let packed = pack_coefficients(&coefficients, slot_bits);
+            let mut expected = BigUint::zero();
+            for c in coefficients.iter().rev() {
+                expected = (expected << slot_bits) + c;
+            }
+            assert_eq!(packed, expected);
+
+            let mut trimmed = coefficients.clone();
+            while trimmed.last().map_or(false, Zero::is_zero) {
+                trimmed.pop();
+            }
+            assert_eq!(unpack_coefficients(&packed, slot_bits), trimmed);
+        }
+        assert!(unpack_coefficients(&BigUint::zero(), 8).is_empty());
+    }
+
+    #[test]
+    fn test_kronecker_multiply() {
+        // (x^3 + 2^40 x + 5)(3x^2 + 2^50)
+        let a = [5u64, 1 << 40, 0, 1];
+        let b = [1u64 << 50, 0, 3];
+        let mut product = vec![BigUint::zero(); a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                product[i + j] += BigUint::from(x) * y;
+            }
+        }
+
+        let pack = |p: &[u64]| {
+            let p: Vec<BigUint> = p.iter().map(|&c| BigUint::from(c)).collect();
+            pack_coefficients(&p, 100)
+        };
+        assert_eq!(unpack_coefficients(&(pack(&a) * pack(&b)), 100), product);
+    }
+
+    #[test]
+    #[should_panic(expected = "coefficient does not fit in its slot")]
+    fn test_pack_overflow() {
+        pack_coefficients(&[BigUint::from(256u32)], 8);
+    }
+}
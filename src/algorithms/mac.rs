@@ -1,10 +1,15 @@
 use core::cmp;
 use core::iter::repeat;
 
-use crate::algorithms::{adc, add2, sub2, sub_sign};
+use crate::algorithms::kernels::mac_n;
+use crate::algorithms::parallel::join;
+use crate::algorithms::trace::{trace, Algorithm};
+use crate::algorithms::{add2, sub2, sub_sign};
 use crate::big_digit::{BigDigit, DoubleBigDigit, BITS};
 use crate::bigint::Sign::{Minus, NoSign, Plus};
 use crate::biguint::IntDigits;
+use crate::budget::{Budget, BudgetExceeded};
+use crate::stats;
 use crate::{BigInt, BigUint};
 
 #[inline]
@@ -23,17 +28,20 @@ pub fn mac_digit(acc: &mut [BigDigit], b: &[BigDigit], c: BigDigit) {
         return;
     }
 
-    let mut carry = 0;
     let (a_lo, a_hi) = acc.split_at_mut(b.len());
+    let carry = mac_n(a_lo, b, c);
 
-    for (a, &b) in a_lo.iter_mut().zip(b) {
-        *a = mac_with_carry(*a, b, c, &mut carry);
-    }
-
-    let mut a = a_hi.iter_mut();
-    while carry != 0 {
-        let a = a.next().expect("carry overflow during multiplication!");
-        *a = adc(*a, 0, &mut carry);
+    if carry != 0 {
+        let mut a_hi = a_hi.iter_mut();
+        let a = a_hi.next().expect("carry overflow during multiplication!");
+        let (sum, mut overflow) = a.overflowing_add(carry);
+        *a = sum;
+        while overflow {
+            let a = a_hi.next().expect("carry overflow during multiplication!");
+            let (sum, o) = a.overflowing_add(1);
+            *a = sum;
+            overflow = o;
+        }
     }
 }
 
@@ -54,10 +62,13 @@ pub fn mac3(acc: &mut [BigDigit], b: &[BigDigit], c: &[BigDigit]) {
     // of `cargo bench --bench bigint multiply`.
 
     if x.len() <= 32 {
+        trace(Algorithm::Schoolbook, x.len(), y.len());
         long(acc, x, y)
     } else if x.len() <= 256 {
+        trace(Algorithm::Karatsuba, x.len(), y.len());
         karatsuba(acc, x, y)
     } else {
+        trace(Algorithm::Toom3, x.len(), y.len());
         toom3(acc, x, y)
     }
 }
@@ -139,6 +150,7 @@ fn karatsuba(acc: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
      * appropriately here: x1.len() >= x0.len and y1.len() >= y0.len():
      */
     let len = x1.len() + y1.len() + 1;
+    stats::record_alloc(len);
     let mut p = BigUint {
         data: smallvec![0; len],
     };
@@ -252,20 +264,37 @@ fn toom3(acc: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
     // y2 - y1 + y0, avoiding temporaries
     let q2 = &q - &y1;
 
-    // w(0)
-    let r0 = &x0 * &y0;
-
-    // w(inf)
-    let r4 = &x2 * &y2;
-
-    // w(1)
-    let r1 = (p + x1) * (q + y1);
-
-    // w(-1)
-    let r2 = &p2 * &q2;
-
-    // w(-2)
-    let r3 = ((p2 + x2) * 2 - x0) * ((q2 + y2) * 2 - y0);
+    // The five point evaluations are independent, so large inputs can
+    // compute them in parallel.
+    let n = x.len();
+    let ((r0, r4), (r1, (r2, r3))) = join(
+        n,
+        || {
+            join(
+                n,
+                // w(0)
+                || &x0 * &y0,
+                // w(inf)
+                || &x2 * &y2,
+            )
+        },
+        || {
+            join(
+                n,
+                // w(1)
+                || (&p + &x1) * (&q + &y1),
+                || {
+                    join(
+                        n,
+                        // w(-1)
+                        || &p2 * &q2,
+                        // w(-2)
+                        || ((&p2 + &x2) * 2 - &x0) * ((&q2 + &y2) * 2 - &y0),
+                    )
+                },
+            )
+        },
+    );
 
     // Evaluating these points gives us the following system of linear equations.
     //
@@ -303,6 +332,73 @@ fn toom3(acc: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) {
     add2(acc, (r4 << (BITS * 4 * i)).digits());
 }
 
+/// The largest operands `mac3_with_budget` multiplies without checking its
+/// budget again.
+const BUDGET_BLOCK: usize = 1024;
+
+/// Like `mac3`, but charges `budget` before each block of at most
+/// `BUDGET_BLOCK` by `BUDGET_BLOCK` digits and stops as soon as it runs out,
+/// leaving `acc` unspecified.
+///
+/// Larger operands are split as in `karatsuba`, so that a long product is
+/// checked all the way through instead of only once up front.
+pub(crate) fn mac3_with_budget(
+    acc: &mut [BigDigit],
+    b: &[BigDigit],
+    c: &[BigDigit],
+    budget: &mut Budget,
+) -> Result<(), BudgetExceeded> {
+    let (x, y) = if b.len() < c.len() { (b, c) } else { (c, b) };
+
+    if x.len() <= BUDGET_BLOCK {
+        for (i, block) in y.chunks(BUDGET_BLOCK).enumerate() {
+            budget.charge_mul(x.len(), block.len())?;
+            mac3(&mut acc[i * BUDGET_BLOCK..], x, block);
+        }
+        return Ok(());
+    }
+
+    let b = x.len() / 2;
+    let (x0, x1) = x.split_at(b);
+    let (y0, y1) = y.split_at(b);
+
+    let len = x1.len() + y1.len() + 1;
+    stats::record_alloc(len);
+    let mut p = BigUint {
+        data: smallvec![0; len],
+    };
+
+    // p2 = x1 * y1
+    mac3_with_budget(&mut p.data[..], x1, y1, budget)?;
+    p.normalize();
+    add2(&mut acc[b..], &p.data[..]);
+    add2(&mut acc[b * 2..], &p.data[..]);
+
+    // p0 = x0 * y0
+    p.data.clear();
+    p.data.resize(len, 0);
+    mac3_with_budget(&mut p.data[..], x0, y0, budget)?;
+    p.normalize();
+    add2(&mut acc[..], &p.data[..]);
+    add2(&mut acc[b..], &p.data[..]);
+
+    // p1 = (x1 - x0) * (y1 - y0), last so that acc never goes negative.
+    let (j0_sign, j0) = sub_sign(x1, x0);
+    let (j1_sign, j1) = sub_sign(y1, y0);
+    match j0_sign * j1_sign {
+        Plus => {
+            p.data.clear();
+            p.data.resize(len, 0);
+            mac3_with_budget(&mut p.data[..], &j0.data[..], &j1.data[..], budget)?;
+            p.normalize();
+            sub2(&mut acc[b..], &p.data[..]);
+        }
+        Minus => mac3_with_budget(&mut acc[b..], &j0.data[..], &j1.data[..], budget)?,
+        NoSign => (),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,25 +3,39 @@
 mod add;
 mod bits;
 mod cmp;
+mod crt;
 mod div;
 mod gcd;
 mod jacobi;
+pub(crate) mod kernels;
+mod kronecker;
 mod mac;
 mod mod_inverse;
 mod mul;
+pub(crate) mod parallel;
+mod rational;
 mod shl;
 mod shr;
 mod sub;
+pub(crate) mod trace;
+pub(crate) mod tree;
 
 pub use self::add::*;
 pub use self::bits::*;
 pub use self::cmp::*;
+pub use self::crt::*;
 pub use self::div::*;
 pub use self::gcd::*;
 pub use self::jacobi::*;
+pub use self::kronecker::*;
 pub use self::mac::*;
 pub use self::mod_inverse::*;
 pub use self::mul::*;
+#[cfg(feature = "rayon")]
+pub use self::parallel::{is_parallel, set_parallel};
+pub use self::rational::*;
 pub use self::shl::*;
 pub use self::shr::*;
 pub use self::sub::*;
+#[cfg(feature = "trace")]
+pub use self::trace::{set_trace_hook, Algorithm, AlgorithmChoice};
@@ -1,5 +1,12 @@
-use crate::algorithms::mac3;
-use crate::big_digit::{BigDigit, DoubleBigDigit, BITS};
+use core::cmp;
+
+use num_traits::One;
+
+use crate::algorithms::kernels::mac_n;
+use crate::algorithms::tree::tree_reduce;
+use crate::algorithms::{mac3, mac_digit};
+use crate::big_digit::{carrying_mul_add, BigDigit, DoubleBigDigit, BITS};
+use crate::stats;
 use crate::BigUint;
 
 #[inline]
@@ -12,6 +19,7 @@ pub fn mul_with_carry(a: BigDigit, b: BigDigit, acc: &mut DoubleBigDigit) -> Big
 
 pub fn mul3(x: &[BigDigit], y: &[BigDigit]) -> BigUint {
     let len = x.len() + y.len() + 1;
+    stats::record_alloc(len);
     let mut prod = BigUint {
         data: smallvec![0; len],
     };
@@ -20,10 +28,88 @@ pub fn mul3(x: &[BigDigit], y: &[BigDigit]) -> BigUint {
     prod.normalized()
 }
 
+/// Returns the partial products of `x * y` at digit position `t` and above,
+/// shifted down by `t` digits: the sum of `x[i] * y[j] * B^(i + j - t)` over
+/// all `i + j >= t`.
+///
+/// The partial products left out add up to less than `t * B^(t + 1)`, so the
+/// result falls short of `(x * y) >> (t * BITS)` by less than `t * B`.
+pub fn mul_hi(x: &[BigDigit], y: &[BigDigit], t: usize) -> BigUint {
+    let len = (x.len() + y.len() + 1).saturating_sub(t);
+    stats::record_alloc(len);
+    let mut prod = BigUint {
+        data: smallvec![0; len],
+    };
+
+    for (i, &xi) in x.iter().enumerate() {
+        let j = t.saturating_sub(i);
+        if j < y.len() {
+            mac_digit(&mut prod.data[i + j - t..], &y[j..], xi);
+        }
+    }
+    prod.normalized()
+}
+
+/// Returns the low `t` digits of `x * y`, that is `x * y mod B^t`.
+///
+/// Only the partial products below digit position `t` are computed, and the
+/// carries out of the top digit are dropped.
+pub fn mul_lo(x: &[BigDigit], y: &[BigDigit], t: usize) -> BigUint {
+    let len = cmp::min(x.len() + y.len(), t);
+    stats::record_alloc(len);
+    let mut prod = BigUint {
+        data: smallvec![0; len],
+    };
+
+    for (i, &xi) in x.iter().enumerate().take(len) {
+        let acc = &mut prod.data[i..];
+        let b = &y[..cmp::min(y.len(), acc.len())];
+        if xi == 0 || b.is_empty() {
+            continue;
+        }
+
+        let (lo, hi) = acc.split_at_mut(b.len());
+        let mut carry = mac_n(lo, b, xi);
+        for a in hi {
+            if carry == 0 {
+                break;
+            }
+            let (sum, overflow) = a.overflowing_add(carry);
+            *a = sum;
+            carry = overflow as BigDigit;
+        }
+    }
+    prod.normalized()
+}
+
 pub fn scalar_mul(a: &mut [BigDigit], b: BigDigit) -> BigDigit {
     let mut carry = 0;
     for a in a.iter_mut() {
-        *a = mul_with_carry(*a, b, &mut carry);
+        let (lo, hi) = carrying_mul_add(*a, b, carry, 0);
+        *a = lo;
+        carry = hi;
     }
-    carry as BigDigit
+    carry
+}
+
+/// Returns the product of all of `values`, or one if there are none.
+///
+/// The two halves of the slice are multiplied recursively and then together, so the
+/// operands of each multiplication are about the same size and the fast multiplication
+/// algorithms apply, where multiplying into one accumulator in turn takes time quadratic
+/// in the size of the result. With the `rayon` feature, the halves of large products are
+/// computed in parallel.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::algorithms::product_of;
+/// use num_bigint_dig::BigUint;
+///
+/// let values: Vec<BigUint> = (1..=20u32).map(BigUint::from).collect();
+/// assert_eq!(product_of(&values), BigUint::from(2_432_902_008_176_640_000u64));
+/// ```
+pub fn product_of(values: &[BigUint]) -> BigUint {
+    let digits = values.iter().map(|v| v.data.len()).sum();
+    tree_reduce(values.to_vec(), digits, |_| false, |a, b| a * b).unwrap_or_else(BigUint::one)
 }
@@ -0,0 +1,69 @@
+//! Optional parallelism for the largest multiplications and radix conversions,
+//! behind the `rayon` feature.
+
+#[cfg(feature = "rayon")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Inputs with fewer digits than this are always handled on one thread.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 1024;
+
+#[cfg(feature = "rayon")]
+static PARALLEL: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables running large multiplications and radix conversions on
+/// the rayon thread pool.
+///
+/// Parallelism is enabled by default. Latency sensitive callers, or callers
+/// that already saturate the pool, can turn it off globally.
+#[cfg(feature = "rayon")]
+pub fn set_parallel(enabled: bool) {
+    PARALLEL.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether large multiplications and radix conversions may run on the
+/// rayon thread pool.
+#[cfg(feature = "rayon")]
+pub fn is_parallel() -> bool {
+    PARALLEL.load(Ordering::Relaxed)
+}
+
+/// Returns whether work on `len` digits is large enough to split across threads.
+#[cfg(feature = "rayon")]
+#[inline]
+pub(crate) fn use_parallel(len: usize) -> bool {
+    len >= PARALLEL_THRESHOLD && is_parallel()
+}
+
+/// Runs `a` and `b`, in parallel if `len` digits is large enough to be worth it.
+#[cfg(feature = "rayon")]
+#[inline]
+pub(crate) fn join<A, B, RA, RB>(len: usize, a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    if use_parallel(len) {
+        rayon::join(a, b)
+    } else {
+        (a(), b())
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+#[inline]
+pub(crate) fn use_parallel(_len: usize) -> bool {
+    false
+}
+
+#[cfg(not(feature = "rayon"))]
+#[inline]
+pub(crate) fn join<A, B, RA, RB>(_len: usize, a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA,
+    B: FnOnce() -> RB,
+{
+    (a(), b())
+}
@@ -0,0 +1,103 @@
+use crate::integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+use crate::{BigInt, BigUint};
+
+/// Finds the fraction n / d with |n| <= `num_bound` and 0 < d <= `den_bound` that is
+/// congruent to `x` modulo `m`, that is with n ≡ x * d (mod m) and gcd(n, d) = 1.
+///
+/// Runs the extended Euclidean algorithm on `m` and `x`, stopping at the first remainder
+/// not above `num_bound`. If `2 * num_bound * den_bound < m`, there is at most one such
+/// fraction, and this finds it whenever it exists. Returns `None` if there is none.
+///
+/// See von zur Gathen and Gerhard, Modern Computer Algebra, 3rd ed., 2013, section 5.10.
+///
+/// Panics if `m` is zero.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::algorithms::rational_reconstruct;
+/// use num_bigint_dig::{BigInt, BigUint};
+///
+/// // -22 / 7 modulo 2^64
+/// let m = BigUint::from(1u32) << 64;
+/// let x = BigUint::from(10540996613548315206u64);
+/// let bound = BigUint::from(1u32) << 31;
+/// assert_eq!(
+///     rational_reconstruct(&x, &m, &bound, &bound),
+///     Some((BigInt::from(-22), BigUint::from(7u32)))
+/// );
+/// ```
+pub fn rational_reconstruct(
+    x: &BigUint,
+    m: &BigUint,
+    num_bound: &BigUint,
+    den_bound: &BigUint,
+) -> Option<(BigInt, BigUint)> {
+    assert!(!m.is_zero(), "divide by zero!");
+
+    // Invariant: r0 ≡ t0 * x and r1 ≡ t1 * x (mod m).
+    let mut r0 = m.clone();
+    let mut r1 = x % m;
+    let mut t0 = BigInt::zero();
+    let mut t1 = BigInt::one();
+    while &r1 > num_bound {
+        let (q, r) = r0.div_rem(&r1);
+        r0 = r1;
+        r1 = r;
+        let t = t0 - BigInt::from(q) * &t1;
+        t0 = t1;
+        t1 = t;
+    }
+
+    let negative = t1.is_negative();
+    let d = t1.data;
+    if d.is_zero() || &d > den_bound || !r1.gcd(&d).is_one() {
+        return None;
+    }
+    let n = BigInt::from(r1);
+    Some((if negative { -n } else { n }, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_traits::ToPrimitive;
+
+    use crate::traits::ModInverse;
+
+    #[test]
+    fn test_rational_reconstruct() {
+        let m = BigUint::from(1009u32);
+        let bound = BigUint::from(22u32);
+
+        // Every fraction within the bounds is recovered from its residue.
+        let mut representable = vec![false; 1009];
+        for n in -22i32..=22 {
+            for d in 1u32..=22 {
+                if n.unsigned_abs().gcd(&d) != 1 {
+                    continue;
+                }
+                let d_inv = BigUint::from(d).mod_inverse(&m).unwrap();
+                let x = BigInt::from(n) * d_inv;
+                let x = x.mod_floor(&BigInt::from(m.clone())).data;
+                representable[x.to_usize().unwrap()] = true;
+                assert_eq!(
+                    rational_reconstruct(&x, &m, &bound, &bound),
+                    Some((BigInt::from(n), BigUint::from(d))),
+                    "{} / {}",
+                    n,
+                    d
+                );
+            }
+        }
+
+        // Since 2 * 22 * 22 < 1009, the other residues have no such fraction.
+        for x in 0..1009u32 {
+            let found = rational_reconstruct(&BigUint::from(x), &m, &bound, &bound);
+            assert_eq!(found.is_some(), representable[x as usize], "{}", x);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use core::iter::repeat;
 use smallvec::SmallVec;
 
 use crate::big_digit::BITS;
+use crate::stats;
 use crate::BigUint;
 
 #[inline]
@@ -13,6 +14,7 @@ pub fn biguint_shl(n: Cow<BigUint>, bits: usize) -> BigUint {
         0 => n.into_owned().data,
         _ => {
             let len = n_unit + n.data.len() + 1;
+            stats::record_alloc(len);
             let mut data = SmallVec::with_capacity(len);
             data.extend(repeat(0).take(n_unit));
             data.extend(n.data.iter().cloned());
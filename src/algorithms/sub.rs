@@ -5,6 +5,7 @@ use num_traits::Zero;
 use smallvec::SmallVec;
 
 use crate::algorithms::cmp_slice;
+use crate::algorithms::kernels::{sub_n, sub_n_rev};
 use crate::big_digit::{BigDigit, SignedDoubleBigDigit, BITS};
 use crate::bigint::Sign::{self, *};
 use crate::{BigUint, VEC_SIZE};
@@ -20,20 +21,18 @@ pub fn sbb(a: BigDigit, b: BigDigit, acc: &mut SignedDoubleBigDigit) -> BigDigit
 }
 
 pub fn sub2(a: &mut [BigDigit], b: &[BigDigit]) {
-    let mut borrow = 0;
-
     let len = cmp::min(a.len(), b.len());
     let (a_lo, a_hi) = a.split_at_mut(len);
     let (b_lo, b_hi) = b.split_at(len);
 
-    for (a, b) in a_lo.iter_mut().zip(b_lo) {
-        *a = sbb(*a, *b, &mut borrow);
-    }
+    let mut borrow = sub_n(a_lo, b_lo);
 
-    if borrow != 0 {
+    if borrow {
         for a in a_hi {
-            *a = sbb(*a, 0, &mut borrow);
-            if borrow == 0 {
+            let (diff, c) = a.overflowing_sub(1);
+            *a = diff;
+            borrow = c;
+            if !borrow {
                 break;
             }
         }
@@ -41,7 +40,7 @@ pub fn sub2(a: &mut [BigDigit], b: &[BigDigit]) {
 
     // note: we're _required_ to fail on underflow
     assert!(
-        borrow == 0 && b_hi.iter().all(|x| *x == 0),
+        !borrow && b_hi.iter().all(|x| *x == 0),
         "Cannot subtract b from a because b is larger than a."
     );
 }
@@ -51,13 +50,7 @@ pub fn sub2(a: &mut [BigDigit], b: &[BigDigit]) {
 pub fn __sub2rev(a: &[BigDigit], b: &mut [BigDigit]) -> BigDigit {
     debug_assert!(b.len() == a.len());
 
-    let mut borrow = 0;
-
-    for (ai, bi) in a.iter().zip(b) {
-        *bi = sbb(*ai, *bi, &mut borrow);
-    }
-
-    borrow as BigDigit
+    BigDigit::from(sub_n_rev(a, b))
 }
 
 pub fn sub2rev(a: &[BigDigit], b: &mut [BigDigit]) {
@@ -0,0 +1,84 @@
+//! Optional reporting of which algorithm each operation picks, behind the
+//! `trace` feature.
+
+#[cfg(feature = "trace")]
+use core::mem;
+#[cfg(feature = "trace")]
+use core::ptr;
+#[cfg(feature = "trace")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// An algorithm chosen for an operation, as reported to the trace hook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// Schoolbook multiplication, used for the smallest operands.
+    Schoolbook,
+    /// Karatsuba multiplication.
+    Karatsuba,
+    /// Toom-3 multiplication, used for the largest operands.
+    Toom3,
+    /// Division by a single digit divisor.
+    DivDigit,
+    /// Knuth's long division, algorithm D.
+    Knuth,
+    /// Modular exponentiation in Montgomery form, used for odd moduli.
+    Montgomery,
+    /// Modular exponentiation by repeated squaring and division, used for
+    /// even moduli.
+    PlainModpow,
+}
+
+/// An algorithm choice, with the sizes of the operands it was made for.
+///
+/// The sizes are in digits. For multiplication they are the two factors,
+/// for division the dividend and divisor, and for modular exponentiation the
+/// exponent and modulus.
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AlgorithmChoice {
+    pub algorithm: Algorithm,
+    pub lhs_len: usize,
+    pub rhs_len: usize,
+}
+
+/// The hook as a `fn(&AlgorithmChoice)` cast to a raw pointer, or null if
+/// there is none.
+#[cfg(feature = "trace")]
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `hook` to be called every time an algorithm is chosen, or
+/// removes the current hook if `hook` is `None`.
+///
+/// Multiplications that recurse report each of their smaller
+/// multiplications too. The hook is global and may be called from several
+/// threads at once, so it should be cheap, for example incrementing a
+/// counter or emitting a log event.
+#[cfg(feature = "trace")]
+pub fn set_trace_hook(hook: Option<fn(&AlgorithmChoice)>) {
+    let hook = hook.map_or(ptr::null_mut(), |f| f as *mut ());
+    HOOK.store(hook, Ordering::Relaxed);
+}
+
+/// Reports `algorithm` being chosen for operands of `lhs_len` and `rhs_len`
+/// digits to the trace hook, if there is one.
+#[cfg(feature = "trace")]
+#[inline]
+pub(crate) fn trace(algorithm: Algorithm, lhs_len: usize, rhs_len: usize) {
+    let hook = HOOK.load(Ordering::Relaxed);
+    if !hook.is_null() {
+        // Function pointers can only be cast to raw pointers, not back, so
+        // this has to be a transmute. Only `set_trace_hook` stores non-null
+        // values, and each is a `fn(&AlgorithmChoice)` cast with `as`.
+        let hook = unsafe { mem::transmute::<*mut (), fn(&AlgorithmChoice)>(hook) };
+        hook(&AlgorithmChoice {
+            algorithm,
+            lhs_len,
+            rhs_len,
+        });
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline]
+pub(crate) fn trace(_algorithm: Algorithm, _lhs_len: usize, _rhs_len: usize) {}
@@ -0,0 +1,59 @@
+//! Balanced reduction of many values with one binary operation.
+
+use alloc::vec::Vec;
+
+use super::parallel::{join, use_parallel};
+
+/// Combines `values` with `op` in a balanced tree, or returns `None` if there are none.
+///
+/// The two halves of `values` are reduced recursively and then combined, so the operands of
+/// each `op` stay about the same size instead of one accumulator growing at every step.
+/// `digits` is the total size of the values. When it is large enough to split across
+/// threads, the halves are reduced in parallel. Otherwise they are reduced in order, and a
+/// partial result for which `absorbing` holds is returned without reducing the rest.
+pub(crate) fn tree_reduce<T, A, F>(values: Vec<T>, digits: usize, absorbing: A, op: F) -> Option<T>
+where
+    T: Send,
+    A: Fn(&T) -> bool + Sync,
+    F: Fn(T, T) -> T + Sync,
+{
+    if values.is_empty() {
+        None
+    } else {
+        Some(reduce(values, digits, &absorbing, &op))
+    }
+}
+
+fn reduce<T, A, F>(mut values: Vec<T>, digits: usize, absorbing: &A, op: &F) -> T
+where
+    T: Send,
+    A: Fn(&T) -> bool + Sync,
+    F: Fn(T, T) -> T + Sync,
+{
+    if values.len() == 1 {
+        return values.pop().unwrap();
+    }
+    let hi = values.split_off(values.len() / 2);
+    let half = digits / 2;
+
+    let (a, b) = if use_parallel(digits) {
+        join(
+            digits,
+            move || reduce(values, half, absorbing, op),
+            move || reduce(hi, half, absorbing, op),
+        )
+    } else {
+        let a = reduce(values, half, absorbing, op);
+        if absorbing(&a) {
+            return a;
+        }
+        (a, reduce(hi, half, absorbing, op))
+    };
+    if absorbing(&a) {
+        return a;
+    }
+    if absorbing(&b) {
+        return b;
+    }
+    op(a, b)
+}
@@ -0,0 +1,121 @@
+//! Barrett reduction for repeated remainders by one modulus.
+
+use core::cmp::min;
+use core::ops::{Rem, RemAssign};
+
+use num_traits::{One, Zero};
+
+use crate::algorithms::kernels::sub_n;
+use crate::algorithms::mul3;
+use crate::big_digit;
+use crate::BigUint;
+
+/// Precomputed state for reducing many values modulo the same `m`.
+///
+/// Barrett reduction replaces the long division in `x % m` by two
+/// multiplications with a precomputed reciprocal of `m`. Unlike the
+/// Montgomery machinery behind [`ModulusParams`](struct.ModulusParams.html),
+/// it works for any non-zero modulus, odd or even, and values stay in their
+/// ordinary representation.
+///
+/// A reducer can be used directly through [`rem`](#method.rem), or as the
+/// right hand side of `%` and `%=`.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BarrettReducer, BigUint};
+///
+/// let m = BigUint::from(1u32) << 100;
+/// let reducer = BarrettReducer::new(&(&m - 6u32));
+///
+/// let x = &m * &m + 123u32;
+/// assert_eq!(reducer.rem(&x), &x % reducer.modulus());
+/// assert_eq!(&x % &reducer, &x % reducer.modulus());
+/// ```
+#[derive(Clone, Debug)]
+pub struct BarrettReducer {
+    m: BigUint,
+    // floor(B^(2k) / m), where B is the digit base and k = len(m)
+    mu: BigUint,
+}
+
+impl BarrettReducer {
+    /// Precomputes the reciprocal of `m`.
+    ///
+    /// Panics if `m` is zero.
+    pub fn new(m: &BigUint) -> BarrettReducer {
+        assert!(!m.is_zero(), "divide by zero!");
+
+        let k = m.data.len();
+        let mu = (BigUint::one() << (2 * k * big_digit::BITS)) / m;
+        BarrettReducer { m: m.clone(), mu }
+    }
+
+    /// Returns the modulus.
+    #[inline]
+    pub fn modulus(&self) -> &BigUint {
+        &self.m
+    }
+
+    /// Returns `x % m`.
+    ///
+    /// Values of up to twice the length of the modulus are reduced with
+    /// Barrett's algorithm; longer ones fall back to long division.
+    pub fn rem(&self, x: &BigUint) -> BigUint {
+        let k = self.m.data.len();
+        if x.data.len() > 2 * k {
+            return x % &self.m;
+        }
+        if *x < self.m {
+            return x.clone();
+        }
+
+        // q = floor(floor(x / B^(k-1)) * mu / B^(k+1)), which is at most
+        // two less than floor(x / m).
+        let q2 = mul3(&x.data[k - 1..], &self.mu.data);
+        let q3 = q2.data.get(k + 1..).unwrap_or(&[]);
+
+        // r = (x - q * m) mod B^(k+1)
+        let low = &x.data[..min(x.data.len(), k + 1)];
+        let mut r = BigUint::zero();
+        r.data.extend_from_slice(low);
+        r.data.resize(k + 1, 0);
+        if !q3.is_empty() {
+            let mut qm = mul3(q3, &self.m.data);
+            qm.data.resize(k + 1, 0);
+            sub_n(&mut r.data, &qm.data[..k + 1]);
+        }
+        r.normalize();
+
+        while r >= self.m {
+            r -= &self.m;
+        }
+        r
+    }
+}
+
+impl<'a, 'b> Rem<&'b BarrettReducer> for &'a BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn rem(self, reducer: &BarrettReducer) -> BigUint {
+        reducer.rem(self)
+    }
+}
+
+impl<'a> Rem<&'a BarrettReducer> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn rem(self, reducer: &BarrettReducer) -> BigUint {
+        reducer.rem(&self)
+    }
+}
+
+impl<'a> RemAssign<&'a BarrettReducer> for BigUint {
+    #[inline]
+    fn rem_assign(&mut self, reducer: &BarrettReducer) {
+        *self = reducer.rem(self);
+    }
+}
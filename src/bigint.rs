@@ -34,11 +34,12 @@ use super::ParseBigIntError;
 use super::VEC_SIZE;
 use crate::big_digit::{self, BigDigit, DoubleBigDigit};
 use crate::biguint;
-use crate::biguint::to_str_radix_reversed;
+use crate::biguint::to_str_radix_be;
 use crate::biguint::{BigUint, IntDigits};
 use smallvec::SmallVec;
 
 use crate::IsizePromotion;
+use crate::TryFromBigIntError;
 use crate::UsizePromotion;
 
 use crate::algorithms::{extended_gcd, mod_inverse};
@@ -161,8 +162,22 @@ impl Eq for BigInt {}
 
 impl Hash for BigInt {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.sign.hash(state);
-        self.data.hash(state);
+        // Non-negative values hash like the equal `BigUint`, and so like the
+        // equal unsigned primitive. Negative values that fit hash like `i64`
+        // or `i128`.
+        if self.sign != Minus {
+            return self.data.hash(state);
+        }
+        match self.to_i128() {
+            Some(v) => match i64::try_from(v) {
+                Ok(v) => v.hash(state),
+                Err(_) => v.hash(state),
+            },
+            None => {
+                self.sign.hash(state);
+                self.data.hash(state);
+            }
+        }
     }
 }
 
@@ -701,18 +716,9 @@ impl Num for BigInt {
 
     /// Creates and initializes a BigInt.
     #[inline]
-    fn from_str_radix(mut s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
-        let sign = if s.starts_with('-') {
-            let tail = &s[1..];
-            if !tail.starts_with('+') {
-                s = tail
-            }
-            Minus
-        } else {
-            Plus
-        };
-        let bu = BigUint::from_str_radix(s, radix)?;
-        Ok(BigInt::from_biguint(sign, bu))
+    fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        let max_bits = biguint::parse_limit().unwrap_or(usize::MAX);
+        BigInt::from_str_radix_bounded(s, radix, max_bits)
     }
 }
 
@@ -891,6 +897,25 @@ pow_impl!(usize);
 #[cfg(has_i128)]
 pow_impl!(u128);
 
+impl<'a, 'b> Pow<&'b BigUint> for &'a BigInt {
+    type Output = BigInt;
+
+    /// Panics if the result would need more than `POW_MAX_BITS` bits.
+    #[inline]
+    fn pow(self, rhs: &BigUint) -> BigInt {
+        BigInt::from_biguint(powsign(self.sign, rhs), (&self.data).pow(rhs))
+    }
+}
+
+impl<'a> Pow<BigUint> for &'a BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn pow(self, rhs: BigUint) -> BigInt {
+        self.pow(&rhs)
+    }
+}
+
 // A convenience method for getting the absolute value of an i32 in a u32.
 #[inline]
 fn i32_abs_as_u32(a: i32) -> u32 {
@@ -1050,6 +1075,33 @@ impl<'a> Add<&'a BigUint> for BigInt {
     }
 }
 
+impl<'a, 'b> Add<&'b BigUint> for &'a BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn add(self, other: &BigUint) -> BigInt {
+        self.clone() + other
+    }
+}
+
+impl<'a> Add<BigInt> for &'a BigUint {
+    type Output = BigInt;
+
+    #[inline]
+    fn add(self, other: BigInt) -> BigInt {
+        other + self
+    }
+}
+
+impl<'a, 'b> Add<&'b BigInt> for &'a BigUint {
+    type Output = BigInt;
+
+    #[inline]
+    fn add(self, other: &BigInt) -> BigInt {
+        other.clone() + self
+    }
+}
+
 impl Add<BigInt> for BigInt {
     type Output = BigInt;
 
@@ -1068,8 +1120,12 @@ impl<'a> AddAssign<&'a BigInt> for BigInt {
 }
 forward_val_assign!(impl AddAssign for BigInt, add_assign);
 
+forward_mixed_val_binop!(impl Add, add);
+forward_mixed_assign!(impl AddAssign, add_assign);
+
 promote_all_scalars!(impl Add for BigInt, add);
 promote_all_scalars_assign!(impl AddAssign for BigInt, add_assign);
+forward_all_scalars_ref_assign!(impl AddAssign for BigInt, add_assign);
 forward_all_scalar_binop_to_val_val_commutative!(impl Add<u32> for BigInt, add);
 forward_all_scalar_binop_to_val_val_commutative!(impl Add<u64> for BigInt, add);
 #[cfg(has_i128)]
@@ -1299,8 +1355,13 @@ impl<'a> SubAssign<&'a BigInt> for BigInt {
 }
 forward_val_assign!(impl SubAssign for BigInt, sub_assign);
 
+forward_mixed_val_binop!(impl Sub, sub);
+forward_mixed_ref_binop!(impl Sub, sub);
+forward_mixed_assign!(impl SubAssign, sub_assign);
+
 promote_all_scalars!(impl Sub for BigInt, sub);
 promote_all_scalars_assign!(impl SubAssign for BigInt, sub_assign);
+forward_all_scalars_ref_assign!(impl SubAssign for BigInt, sub_assign);
 forward_all_scalar_binop_to_val_val!(impl Sub<u32> for BigInt, sub);
 forward_all_scalar_binop_to_val_val!(impl Sub<u64> for BigInt, sub);
 #[cfg(has_i128)]
@@ -1542,8 +1603,12 @@ impl<'a> MulAssign<&'a BigInt> for BigInt {
 
 forward_val_assign!(impl MulAssign for BigInt, mul_assign);
 
+forward_mixed_val_binop!(impl Mul, mul);
+forward_mixed_assign!(impl MulAssign, mul_assign);
+
 promote_all_scalars!(impl Mul for BigInt, mul);
 promote_all_scalars_assign!(impl MulAssign for BigInt, mul_assign);
+forward_all_scalars_ref_assign!(impl MulAssign for BigInt, mul_assign);
 forward_all_scalar_binop_to_val_val_commutative!(impl Mul<u32> for BigInt, mul);
 forward_all_scalar_binop_to_val_val_commutative!(impl Mul<u64> for BigInt, mul);
 #[cfg(has_i128)]
@@ -1706,8 +1771,13 @@ impl<'a> DivAssign<&'a BigInt> for BigInt {
 }
 forward_val_assign!(impl DivAssign for BigInt, div_assign);
 
+forward_mixed_val_binop!(impl Div, div);
+forward_mixed_ref_binop!(impl Div, div);
+forward_mixed_assign!(impl DivAssign, div_assign);
+
 promote_all_scalars!(impl Div for BigInt, div);
 promote_all_scalars_assign!(impl DivAssign for BigInt, div_assign);
+forward_all_scalars_ref_assign!(impl DivAssign for BigInt, div_assign);
 forward_all_scalar_binop_to_val_val!(impl Div<u32> for BigInt, div);
 forward_all_scalar_binop_to_val_val!(impl Div<u64> for BigInt, div);
 #[cfg(has_i128)]
@@ -1942,19 +2012,24 @@ impl<'a> RemAssign<&'a BigInt> for BigInt {
 }
 forward_val_assign!(impl RemAssign for BigInt, rem_assign);
 
+forward_mixed_val_binop!(impl Rem, rem);
+forward_mixed_ref_binop!(impl Rem, rem);
+forward_mixed_assign!(impl RemAssign, rem_assign);
+
 promote_all_scalars!(impl Rem for BigInt, rem);
 promote_all_scalars_assign!(impl RemAssign for BigInt, rem_assign);
-forward_all_scalar_binop_to_val_val!(impl Rem<u32> for BigInt, rem);
-forward_all_scalar_binop_to_val_val!(impl Rem<u64> for BigInt, rem);
+forward_all_scalars_ref_assign!(impl RemAssign for BigInt, rem_assign);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u32> for BigInt, rem);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u64> for BigInt, rem);
 #[cfg(has_i128)]
-forward_all_scalar_binop_to_val_val!(impl Rem<u128> for BigInt, rem);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u128> for BigInt, rem);
 
-impl Rem<u32> for BigInt {
+impl<'a> Rem<u32> for &'a BigInt {
     type Output = BigInt;
 
     #[inline]
     fn rem(self, other: u32) -> BigInt {
-        BigInt::from_biguint(self.sign, self.data % other)
+        BigInt::from_biguint(self.sign, &self.data % other)
     }
 }
 
@@ -1977,12 +2052,12 @@ impl Rem<BigInt> for u32 {
     }
 }
 
-impl Rem<u64> for BigInt {
+impl<'a> Rem<u64> for &'a BigInt {
     type Output = BigInt;
 
     #[inline]
     fn rem(self, other: u64) -> BigInt {
-        BigInt::from_biguint(self.sign, self.data % other)
+        BigInt::from_biguint(self.sign, &self.data % other)
     }
 }
 
@@ -2006,12 +2081,12 @@ impl Rem<BigInt> for u64 {
 }
 
 #[cfg(has_i128)]
-impl Rem<u128> for BigInt {
+impl<'a> Rem<u128> for &'a BigInt {
     type Output = BigInt;
 
     #[inline]
     fn rem(self, other: u128) -> BigInt {
-        BigInt::from_biguint(self.sign, self.data % other)
+        BigInt::from_biguint(self.sign, &self.data % other)
     }
 }
 
@@ -2084,7 +2159,7 @@ impl Rem<i64> for BigInt {
     #[inline]
     fn rem(self, other: i64) -> BigInt {
         if other >= 0 {
-            self % other as i64
+            self % other as u64
         } else {
             self % i64_abs_as_u64(other)
         }
@@ -2271,6 +2346,25 @@ impl Integer for BigInt {
         BigInt::from_biguint(Plus, self.data.lcm(&other.data))
     }
 
+    /// Calculates the GCD of the number and `other` together with Bézout
+    /// coefficients `x` and `y` such that `gcd = self * x + other * y`.
+    ///
+    /// This uses the same algorithm as [`ExtendedGcd`](trait.ExtendedGcd.html),
+    /// extended to negative operands. The GCD is never negative.
+    fn extended_gcd(&self, other: &BigInt) -> crate::integer::ExtendedGcd<BigInt> {
+        let (gcd, x, y) = extended_gcd(Cow::Borrowed(&self.data), Cow::Borrowed(&other.data), true);
+        // The coefficients are for the magnitudes, so a negative operand
+        // flips the sign of its coefficient.
+        let (mut x, mut y) = (x.unwrap(), y.unwrap());
+        if self.is_negative() {
+            x = -x;
+        }
+        if other.is_negative() {
+            y = -y;
+        }
+        crate::integer::ExtendedGcd { gcd, x, y }
+    }
+
     /// Deprecated, use `is_multiple_of` instead.
     #[inline]
     fn divides(&self, other: &BigInt) -> bool {
@@ -2730,6 +2824,16 @@ impl BigInt {
         BigInt { sign, data }
     }
 
+    /// Creates a `BigInt` from a sign and a magnitude, the inverse of
+    /// [`into_parts`](BigInt::into_parts).
+    ///
+    /// This is the same as [`from_biguint`](BigInt::from_biguint): a zero
+    /// magnitude gives `NoSign`, and `NoSign` gives zero.
+    #[inline]
+    pub fn from_parts(sign: Sign, magnitude: BigUint) -> BigInt {
+        BigInt::from_biguint(sign, magnitude)
+    }
+
     /// Creates and initializes a `BigInt`.
     #[inline]
     pub fn from_slice(sign: Sign, slice: &[u32]) -> BigInt {
@@ -2846,6 +2950,30 @@ impl BigInt {
         }
     }
 
+    /// Creates a `BigInt` from a big-endian two's complement field that must
+    /// be exactly `width` bytes long, such as an EVM `int256`.
+    ///
+    /// Returns an error if `digits` has any other length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let field = [0xff; 32];
+    /// assert_eq!(BigInt::from_signed_bytes_be_sized(&field, 32), Ok(BigInt::from(-1)));
+    /// assert!(BigInt::from_signed_bytes_be_sized(&field[1..], 32).is_err());
+    /// ```
+    pub fn from_signed_bytes_be_sized(
+        digits: &[u8],
+        width: usize,
+    ) -> Result<BigInt, TryFromBigIntError<()>> {
+        if digits.len() != width {
+            return Err(TryFromBigIntError { original: () });
+        }
+        Ok(BigInt::from_signed_bytes_be(digits))
+    }
+
     /// Creates and initializes a `BigInt`.
     ///
     /// # Examples
@@ -2864,6 +2992,28 @@ impl BigInt {
             .and_then(|s| BigInt::from_str_radix(s, radix).ok())
     }
 
+    /// Parses `s` like `from_str_radix`, but fails if the magnitude would
+    /// need more than `max_bits` bits.
+    ///
+    /// See [`BigUint::from_str_radix_bounded`].
+    pub fn from_str_radix_bounded(
+        mut s: &str,
+        radix: u32,
+        max_bits: usize,
+    ) -> Result<BigInt, ParseBigIntError> {
+        let sign = if s.starts_with('-') {
+            let tail = &s[1..];
+            if !tail.starts_with('+') {
+                s = tail
+            }
+            Minus
+        } else {
+            Plus
+        };
+        let bu = BigUint::from_str_radix_bounded(s, radix, max_bits)?;
+        Ok(BigInt::from_biguint(sign, bu))
+    }
+
     /// Creates and initializes a `BigInt`. Each u8 of the input slice is
     /// interpreted as one digit of the number
     /// and must therefore be less than `radix`.
@@ -2990,6 +3140,38 @@ impl BigInt {
         bytes
     }
 
+    /// Returns the big-endian two's complement representation of the
+    /// `BigInt`, sign-extended to exactly `width` bytes.
+    ///
+    /// Returns an error if the value doesn't fit in `width` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let i = BigInt::from(-1125);
+    /// assert_eq!(i.to_signed_bytes_be_sized(4), Ok(vec![255, 255, 251, 155]));
+    /// assert!(i.to_signed_bytes_be_sized(1).is_err());
+    /// ```
+    pub fn to_signed_bytes_be_sized(
+        &self,
+        width: usize,
+    ) -> Result<Vec<u8>, TryFromBigIntError<()>> {
+        if self.is_zero() {
+            return Ok(vec![0; width]);
+        }
+
+        let bytes = self.to_signed_bytes_be();
+        if bytes.len() > width {
+            return Err(TryFromBigIntError { original: () });
+        }
+        let fill = if self.sign == Sign::Minus { 0xff } else { 0 };
+        let mut sized = vec![fill; width - bytes.len()];
+        sized.extend_from_slice(&bytes);
+        Ok(sized)
+    }
+
     /// Returns the integer formatted as a string in the given radix.
     /// `radix` must be in the range `2...36`.
     ///
@@ -3003,13 +3185,12 @@ impl BigInt {
     /// ```
     #[inline]
     pub fn to_str_radix(&self, radix: u32) -> String {
-        let mut v = to_str_radix_reversed(&self.data, radix);
+        let mut v = to_str_radix_be(&self.data, radix);
 
         if self.is_negative() {
-            v.push(b'-');
+            v.insert(0, b'-');
         }
 
-        v.reverse();
         unsafe { String::from_utf8_unchecked(v) }
     }
 
@@ -3067,6 +3248,37 @@ impl BigInt {
         self.sign
     }
 
+    /// Returns a reference to the magnitude of the `BigInt`, without cloning it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigInt, BigUint};
+    ///
+    /// assert_eq!(BigInt::from(-1234).magnitude(), &BigUint::from(1234u32));
+    /// ```
+    #[inline]
+    pub fn magnitude(&self) -> &BigUint {
+        &self.data
+    }
+
+    /// Splits the `BigInt` into its sign and magnitude, without cloning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigInt, BigUint, Sign};
+    ///
+    /// let (sign, magnitude) = BigInt::from(-1234).into_parts();
+    /// assert_eq!(sign, Sign::Minus);
+    /// assert_eq!(magnitude, BigUint::from(1234u32));
+    /// assert_eq!(BigInt::from_parts(sign, magnitude), BigInt::from(-1234));
+    /// ```
+    #[inline]
+    pub fn into_parts(self) -> (Sign, BigUint) {
+        (self.sign, self.data)
+    }
+
     /// Determines the fewest bits necessary to express the `BigInt`,
     /// not including the sign.
     #[inline]
@@ -3074,6 +3286,45 @@ impl BigInt {
         self.data.bits()
     }
 
+    /// Compares `self` with an `i64`, without converting it to a `BigInt`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cmp::Ordering;
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let n = BigInt::from(-300);
+    /// assert_eq!(n.cmp_i64(-255), Ordering::Less);
+    /// assert_eq!(n.cmp_i64(-300), Ordering::Equal);
+    /// assert_eq!(n.cmp_i64(0), Ordering::Less);
+    /// ```
+    pub fn cmp_i64(&self, other: i64) -> Ordering {
+        match (self.sign, other < 0) {
+            (Minus, false) => Less,
+            (Minus, true) => self.data.cmp_u64(other.unsigned_abs()).reverse(),
+            (_, true) => Greater,
+            (_, false) => self.data.cmp_u64(other as u64),
+        }
+    }
+
+    /// Returns `true` if `self` equals the `i64` value `other`.
+    #[inline]
+    pub fn eq_i64(&self, other: i64) -> bool {
+        self.cmp_i64(other) == Equal
+    }
+
+    /// Compares `self` with an `i128`, without converting it to a `BigInt`.
+    #[cfg(has_i128)]
+    pub fn cmp_i128(&self, other: i128) -> Ordering {
+        match (self.sign, other < 0) {
+            (Minus, false) => Less,
+            (Minus, true) => self.data.cmp_u128(other.unsigned_abs()).reverse(),
+            (_, true) => Greater,
+            (_, false) => self.data.cmp_u128(other as u128),
+        }
+    }
+
     /// Converts this `BigInt` into a `BigUint`, if it's not negative.
     #[inline]
     pub fn to_biguint(&self) -> Option<BigUint> {
@@ -3115,21 +3366,34 @@ impl BigInt {
     /// The result will be in the interval `[0, modulus)` for `modulus > 0`,
     /// or in the interval `(modulus, 0]` for `modulus < 0`
     ///
-    /// Panics if the exponent is negative or the modulus is zero.
+    /// A negative exponent raises the modular inverse of `self` instead, like
+    /// Python's `pow(b, -e, m)`.
+    ///
+    /// Panics if the modulus is zero, or if the exponent is negative and
+    /// `self` is not invertible modulo `modulus`.
     pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Self {
-        assert!(
-            !exponent.is_negative(),
-            "negative exponentiation is not supported!"
-        );
         assert!(!modulus.is_zero(), "divide by zero!");
 
-        let result = self.data.modpow(&exponent.data, &modulus.data);
+        let (negative, result) = if exponent.is_negative() {
+            let mut base = &self.data % &modulus.data;
+            if self.is_negative() && !base.is_zero() {
+                base = &modulus.data - base;
+            }
+            let inverse = mod_inverse(Cow::Owned(base), Cow::Borrowed(&modulus.data))
+                .expect("base is not invertible for the given modulus");
+            (false, inverse.data.modpow(&exponent.data, &modulus.data))
+        } else {
+            (
+                self.is_negative(),
+                self.data.modpow(&exponent.data, &modulus.data),
+            )
+        };
         if result.is_zero() {
             return BigInt::zero();
         }
 
         // The sign of the result follows the modulus, like `mod_floor`.
-        let (sign, mag) = match (self.is_negative(), modulus.is_negative()) {
+        let (sign, mag) = match (negative, modulus.is_negative()) {
             (false, false) => (Plus, result),
             (true, false) => (Plus, &modulus.data - result),
             (false, true) => (Minus, &modulus.data - result),
@@ -3138,6 +3402,33 @@ impl BigInt {
         BigInt::from_biguint(sign, mag)
     }
 
+    /// Returns `self ^ exponent`, or `None` if the magnitude of the result
+    /// would need more than `max_bits` bits.
+    pub fn checked_pow_bits(&self, exponent: &BigUint, max_bits: usize) -> Option<Self> {
+        let mag = self.data.checked_pow_bits(exponent, max_bits)?;
+        Some(BigInt::from_biguint(powsign(self.sign, exponent), mag))
+    }
+
+    /// Returns `self << bits`, or `None` if the magnitude of the result would
+    /// need more than `max_bits` bits.
+    ///
+    /// The size is checked before anything is allocated.
+    pub fn checked_shl(&self, bits: usize, max_bits: usize) -> Option<Self> {
+        let mag = self.data.checked_shl(bits, max_bits)?;
+        Some(BigInt::from_biguint(self.sign, mag))
+    }
+
+    /// Returns `self >> bits`, rounding towards negative infinity like `>>`,
+    /// or `None` if the magnitude of the result would need more than
+    /// `max_bits` bits.
+    pub fn checked_shr(&self, bits: usize, max_bits: usize) -> Option<Self> {
+        let shifted = self >> bits;
+        if shifted.bits() > max_bits {
+            return None;
+        }
+        Some(shifted)
+    }
+
     /// Returns the truncated principal square root of `self` --
     /// see [Roots::sqrt](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#method.sqrt).
     pub fn sqrt(&self) -> Self {
@@ -3163,6 +3454,65 @@ impl BigInt {
     pub fn trailing_zeros(&self) -> Option<usize> {
         biguint::trailing_zeros(&self.data)
     }
+
+    /// Returns the midpoint `(self + other) / 2`, rounded towards negative infinity.
+    ///
+    /// This is computed as `(self & other) + ((self ^ other) >> 1)` using the
+    /// two's complement bit operations, so the full sum is never materialized.
+    pub fn midpoint(&self, other: &Self) -> Self {
+        (self & other) + ((self ^ other) >> 1)
+    }
+
+    /// Returns the low 64 bits of the two's complement of `self`, like an
+    /// `as u64` cast of a primitive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// assert_eq!(BigInt::from(-1).to_u64_wrapping(), u64::MAX);
+    /// ```
+    pub fn to_u64_wrapping(&self) -> u64 {
+        let low = self.data.to_u64_wrapping();
+        if self.sign == Minus {
+            low.wrapping_neg()
+        } else {
+            low
+        }
+    }
+
+    /// Returns the low 128 bits of the two's complement of `self`, like an
+    /// `as u128` cast of a primitive.
+    #[cfg(has_i128)]
+    pub fn to_u128_wrapping(&self) -> u128 {
+        let low = self.data.to_u128_wrapping();
+        if self.sign == Minus {
+            low.wrapping_neg()
+        } else {
+            low
+        }
+    }
+
+    /// Returns the low `bits` bits of the two's complement of `self`, that is
+    /// `self mod 2^bits` rounded like `mod_floor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigInt, BigUint};
+    ///
+    /// assert_eq!(BigInt::from(-1).truncate_to_bits(12), BigUint::from(0xfffu32));
+    /// assert_eq!(BigInt::from(-4096).truncate_to_bits(12), BigUint::from(0u32));
+    /// ```
+    pub fn truncate_to_bits(&self, bits: usize) -> BigUint {
+        let low = self.data.truncate_to_bits(bits);
+        if self.sign == Minus && !low.is_zero() {
+            (BigUint::one() << bits) - low
+        } else {
+            low
+        }
+    }
 }
 
 impl_sum_iter_type!(BigInt);
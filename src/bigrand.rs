@@ -2,6 +2,8 @@
 
 use rand::distributions::uniform::{SampleBorrow, SampleUniform, UniformSampler};
 use rand::prelude::*;
+#[cfg(feature = "getrandom")]
+use rand::rngs::OsRng;
 use rand::Rng;
 
 use crate::BigInt;
@@ -11,14 +13,15 @@ use crate::Sign::*;
 use crate::big_digit::BigDigit;
 use crate::bigint::{into_magnitude, magnitude};
 use crate::integer::Integer;
+use crate::traits::ModInverse;
 #[cfg(feature = "prime")]
 use num_iter::range_step;
-use num_traits::Zero;
 #[cfg(feature = "prime")]
 use num_traits::{FromPrimitive, ToPrimitive};
+use num_traits::{One, Zero};
 
 #[cfg(feature = "prime")]
-use crate::prime::probably_prime;
+use crate::prime::{probably_prime, TRIAL_DIVISION_LIMIT};
 
 pub trait RandBigInt {
     /// Generate a random `BigUint` of the given bit size.
@@ -37,9 +40,18 @@ pub trait RandBigInt {
     fn gen_biguint_range(&mut self, lbound: &BigUint, ubound: &BigUint) -> BigUint;
 
     /// Generate a random `BigInt` within the given range. The lower
-    /// bound is inclusive; the upper bound is exclusive. Fails when
-    /// the upper bound is not greater than the lower bound.
+    /// bound is inclusive; the upper bound is exclusive. Either bound may
+    /// be negative. Fails when the upper bound is not greater than the
+    /// lower bound.
     fn gen_bigint_range(&mut self, lbound: &BigInt, ubound: &BigInt) -> BigInt;
+
+    /// Generate a random `BigInt` of the given bit size, with a sign that
+    /// is chosen independently of the magnitude.
+    ///
+    /// Unlike [`gen_bigint`](#tymethod.gen_bigint), this never retries, so
+    /// it uses a fixed amount of randomness, but zero is twice as likely
+    /// as any other value.
+    fn gen_bigint_nonuniform_sign(&mut self, bit_size: usize) -> BigInt;
 }
 
 impl<R: Rng + ?Sized> RandBigInt for R {
@@ -48,10 +60,13 @@ impl<R: Rng + ?Sized> RandBigInt for R {
         let (digits, rem) = bit_size.div_rem(&BITS);
         let mut data = smallvec![BigDigit::default(); digits + (rem > 0) as usize];
 
-        // `fill` is faster than many `gen::<u32>` calls
-        // Internally this calls `SeedableRng` where implementors are responsible for adjusting endianness for reproducable values.
+        // `fill` makes a single `fill_bytes` call over the whole digit buffer, then only
+        // fixes up the endianness of each digit, so values from a seeded RNG are the same on
+        // every platform.
         self.fill(data.as_mut_slice());
 
+        // Drop the excess bits of the top digit. This keeps its high bits rather than
+        // masking to the low ones, which the seeded value stability tests depend on.
         if rem > 0 {
             data[digits] >>= BITS - rem;
         }
@@ -113,6 +128,100 @@ impl<R: Rng + ?Sized> RandBigInt for R {
             lbound + BigInt::from(self.gen_biguint_below(magnitude(&delta)))
         }
     }
+
+    fn gen_bigint_nonuniform_sign(&mut self, bit_size: usize) -> BigInt {
+        let biguint = self.gen_biguint(bit_size);
+        let sign = if self.gen() { Plus } else { Minus };
+        BigInt::from_biguint(sign, biguint)
+    }
+}
+
+impl BigUint {
+    /// Returns `(self ^ exponent) % modulus`, with random blinding applied to
+    /// reduce timing leakage of the secret inputs.
+    ///
+    /// This is a mitigation for side channels, not a constant-time
+    /// implementation: the underlying `modpow` still branches and sizes its
+    /// buffers on the data it is given, and blinding only decorrelates that
+    /// timing from the secret. Each countermeasure is enabled by passing its
+    /// input:
+    ///
+    /// - `public_exponent`: base blinding. For an `e` with
+    ///   `x^(e * exponent) = x (mod modulus)`, as with an RSA key pair, the
+    ///   base is replaced by `self * r^e` for a random unit `r`, and the
+    ///   result is multiplied by `r^-1`.
+    /// - `order`: exponent blinding. For a multiple `k` of the order of the
+    ///   group, such as `λ(modulus)` or `φ(modulus)`, the exponent is replaced
+    ///   by `exponent + s * k` for a random 64-bit `s`.
+    ///
+    /// With neither, this is the same as [`modpow`](#method.modpow).
+    ///
+    /// Panics if the modulus is zero.
+    pub fn modpow_blinded<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        exponent: &BigUint,
+        modulus: &BigUint,
+        public_exponent: Option<&BigUint>,
+        order: Option<&BigUint>,
+    ) -> BigUint {
+        assert!(!modulus.is_zero(), "divide by zero!");
+
+        let blinded_exponent;
+        let exponent = match order {
+            Some(order) => {
+                blinded_exponent = exponent + order * rng.gen::<u64>();
+                &blinded_exponent
+            }
+            None => exponent,
+        };
+
+        let e = match public_exponent {
+            Some(e) if !modulus.is_one() => e,
+            _ => return self.modpow(exponent, modulus),
+        };
+
+        // Find a random unit r and its inverse.
+        let (r, r_inv) = loop {
+            let r = rng.gen_biguint_range(&BigUint::one(), modulus);
+            if let Some(r_inv) = (&r).mod_inverse(modulus) {
+                break (r, into_magnitude(r_inv));
+            }
+        };
+
+        let base = self * r.modpow(e, modulus) % modulus;
+        base.modpow(exponent, modulus) * r_inv % modulus
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl BigUint {
+    /// Generates a random `BigUint` of the given bit size, using the operating system's
+    /// random number generator.
+    ///
+    /// This is `OsRng.gen_biguint(bits)`, for code that doesn't otherwise need an `Rng`.
+    ///
+    /// Panics if the operating system fails to provide random data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let nonce = BigUint::random(128);
+    /// assert!(nonce.bits() <= 128);
+    /// ```
+    pub fn random(bits: usize) -> BigUint {
+        OsRng.gen_biguint(bits)
+    }
+
+    /// Generates a random `BigUint` less than `bound`, using the operating system's
+    /// random number generator.
+    ///
+    /// Panics if `bound` is zero, or if the operating system fails to provide random data.
+    pub fn random_below(bound: &BigUint) -> BigUint {
+        OsRng.gen_biguint_below(bound)
+    }
 }
 
 /// The back-end implementing rand's `UniformSampler` for `BigUint`.
@@ -284,6 +393,16 @@ impl Distribution<BigInt> for RandomBits {
 pub trait RandPrime {
     /// Generate a random prime number with as many bits as given.
     fn gen_prime(&mut self, bits: usize) -> BigUint;
+
+    /// Generate a random odd number with as many bits as given and its top two bits set,
+    /// as a starting point for a prime search.
+    ///
+    /// With `sieve`, the number is also moved up to the next value with no factor in the
+    /// small primes up to 53, so candidates that are certainly composite are skipped
+    /// without any primality test. This is the candidate `gen_prime` tests.
+    ///
+    /// Panics if `bits` is less than 2.
+    fn gen_prime_candidate(&mut self, bits: usize, sieve: bool) -> BigUint;
 }
 
 /// A list of small, prime numbers that allows us to rapidly
@@ -306,6 +425,15 @@ lazy_static! {
 #[cfg(feature = "prime")]
 impl<R: Rng + ?Sized> RandPrime for R {
     fn gen_prime(&mut self, bit_size: usize) -> BigUint {
+        loop {
+            let p = self.gen_prime_candidate(bit_size, true);
+            if p.trial_divide(u64::from(TRIAL_DIVISION_LIMIT)).is_none() && probably_prime(&p, 20) {
+                return p;
+            }
+        }
+    }
+
+    fn gen_prime_candidate(&mut self, bit_size: usize, sieve: bool) -> BigUint {
         if bit_size < 2 {
             panic!("prime size must be at least 2-bit");
         }
@@ -341,6 +469,10 @@ impl<R: Rng + ?Sized> RandPrime for R {
             bytes[bytes_len - 1] |= 1u8;
 
             let mut p = BigUint::from_bytes_be(&bytes);
+            if !sieve {
+                return p;
+            }
+
             // must always be a u64, as the SMALL_PRIMES_PRODUCT is a u64
             let rem = (&p % &*SMALL_PRIMES_PRODUCT).to_u64().unwrap();
 
@@ -362,7 +494,7 @@ impl<R: Rng + ?Sized> RandPrime for R {
 
             // There is a tiny possibility that, by adding delta, we caused
             // the number to be one bit too long. Thus we check bit length here.
-            if p.bits() == bit_size && probably_prime(&p, 20) {
+            if p.bits() == bit_size {
                 return p;
             }
         }
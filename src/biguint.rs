@@ -11,6 +11,7 @@ use core::ops::{
     Mul, MulAssign, Neg, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
 };
 use core::str::{self, FromStr};
+use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use core::{cmp, fmt, mem};
 use core::{f32, f64};
 use core::{u32, u64, u8};
@@ -32,15 +33,25 @@ fn sqrt(a: f64) -> f64 {
 }
 
 #[cfg(feature = "std")]
-fn ln(a: f64) -> f64 {
+pub(crate) fn ln(a: f64) -> f64 {
     a.ln()
 }
 
 #[cfg(not(feature = "std"))]
-fn ln(a: f64) -> f64 {
+pub(crate) fn ln(a: f64) -> f64 {
     libm::log(a)
 }
 
+#[cfg(feature = "std")]
+fn log2(a: f64) -> f64 {
+    a.log2()
+}
+
+#[cfg(not(feature = "std"))]
+fn log2(a: f64) -> f64 {
+    libm::log2(a)
+}
+
 #[cfg(feature = "std")]
 fn cbrt(a: f64) -> f64 {
     a.cbrt()
@@ -64,32 +75,65 @@ fn exp(a: f64) -> f64 {
 use crate::integer::{Integer, Roots};
 use num_traits::float::FloatCore;
 use num_traits::{
-    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, Num, One, Pow, ToPrimitive,
-    Unsigned, Zero,
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, MulAdd, MulAddAssign, Num, One,
+    Pow, ToPrimitive, Unsigned, Zero,
 };
 
 use crate::BigInt;
+use crate::BigUintRef;
+
+#[cfg(feature = "known-primes")]
+use crate::known_primes::KnownPrime;
 
 use crate::big_digit::{self, BigDigit};
 
 use smallvec::SmallVec;
 
 #[path = "monty.rs"]
-mod monty;
+pub(crate) mod monty;
 
-use self::monty::monty_modpow;
+use self::monty::monty_div_rem;
+use self::monty::monty_modpow_with_budget;
+use self::monty::{monty_modpow, monty_modpow_with_options, monty_modpow_with_params};
+use self::monty::{ModPowOptions, ModulusParams};
 use super::VEC_SIZE;
+use crate::algorithms::div_rem_core_with_budget;
+use crate::algorithms::mac3_with_budget;
+#[cfg(feature = "rayon")]
+use crate::algorithms::parallel::{self, use_parallel};
+use crate::algorithms::trace::{trace, Algorithm};
 use crate::algorithms::{__add2, __sub2rev, add2, sub2, sub2rev};
 use crate::algorithms::{biguint_shl, biguint_shr};
 use crate::algorithms::{cmp_slice, fls, idiv_ceil, ilog2};
-use crate::algorithms::{div_rem, div_rem_digit, mac_with_carry, mul3, scalar_mul};
+use crate::algorithms::{div_rem, div_rem_digit, div_wide, mac3, mac_with_carry, mul3, scalar_mul};
+use crate::algorithms::{div_rem_binary, div_rem_knuth, DivStrategy};
+use crate::algorithms::{divisible_by_digit, rem_digit};
 use crate::algorithms::{extended_gcd, mod_inverse};
+use crate::algorithms::{mul_hi, mul_lo, product_of};
 use crate::traits::{ExtendedGcd, ModInverse};
 
 use crate::ParseBigIntError;
 use crate::UsizePromotion;
+use crate::Workspace;
+use crate::POW_MAX_BITS;
+use crate::{Budget, BudgetExceeded};
 
 /// A big unsigned integer type.
+///
+/// # Reusing storage
+///
+/// Binary operators that take an operand by value write their result into
+/// that operand's storage rather than allocating a new value:
+///
+/// - `+`, `|` and `^` reuse whichever owned operand has the larger capacity,
+///   so `a + b + c` allocates only when a sum outgrows both buffers.
+/// - `-` reuses the larger owned buffer as well, and `&` reuses an owned
+///   operand, the left one if both are.
+/// - `*` reuses the owned operand when the other one is a single digit.
+///   Longer products are written into fresh storage, since the product
+///   cannot overlap either input.
+///
+/// Only operators with both operands by reference always allocate.
 #[derive(Clone, Debug)]
 pub struct BigUint {
     pub(crate) data: SmallVec<[BigDigit; VEC_SIZE]>,
@@ -108,7 +152,27 @@ impl Eq for BigUint {}
 
 impl Hash for BigUint {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.data.hash(state);
+        hash_digits(&self.data, state);
+    }
+}
+
+/// Hashes normalized digits.
+///
+/// Values that fit in a `u64` or `u128` hash exactly like that primitive, so
+/// maps mixing primitive and big keys stay consistent. Anything larger hashes
+/// the digit slice, which integer slices feed to the hasher in one write.
+pub(crate) fn hash_digits<H: Hasher>(data: &[BigDigit], state: &mut H) {
+    if data.len() * big_digit::BITS <= 128 {
+        let v = data
+            .iter()
+            .rev()
+            .fold(0u128, |acc, &d| (acc << big_digit::BITS) | u128::from(d));
+        match u64::try_from(v) {
+            Ok(v) => v.hash(state),
+            Err(_) => v.hash(state),
+        }
+    } else {
+        data.hash(state);
     }
 }
 
@@ -237,11 +301,67 @@ fn from_inexact_bitwise_digits_le(v: &[u8], bits: usize) -> BigUint {
     BigUint::new_native(data)
 }
 
+// Returns the value of an ASCII digit in radix 36, or u8::MAX if it isn't one
+fn digit_value(b: u8) -> u8 {
+    match b {
+        b'0'..=b'9' => b - b'0',
+        b'a'..=b'z' => b - b'a' + 10,
+        b'A'..=b'Z' => b - b'A' + 10,
+        _ => u8::MAX,
+    }
+}
+
+// Parse ASCII digits in a power of two radix (bits == ilog2(radix)), walking the string from
+// its least significant end and accumulating bits in big_digits, like
+// from_inexact_bitwise_digits_le
+fn from_bitwise_str(s: &[u8], bits: usize) -> Result<BigUint, ParseBigIntError> {
+    debug_assert!(bits <= 5);
+
+    let big_digits = idiv_ceil(s.len() * bits, big_digit::BITS);
+    let mut data = SmallVec::with_capacity(big_digits);
+
+    let mut d = 0;
+    let mut dbits = 0; // number of bits we currently have in d
+
+    for &b in s.iter().rev() {
+        if b == b'_' {
+            continue;
+        }
+        let c = digit_value(b);
+        if c >= 1 << bits {
+            return Err(ParseBigIntError::invalid());
+        }
+
+        d |= (c as BigDigit) << dbits;
+        dbits += bits;
+
+        if dbits >= big_digit::BITS {
+            data.push(d);
+            dbits -= big_digit::BITS;
+            // grab the bits of c that didn't fit in d
+            d = (c as BigDigit) >> (bits - dbits);
+        }
+    }
+
+    if dbits > 0 {
+        data.push(d);
+    }
+
+    Ok(BigUint::new_native(data))
+}
+
 // Read little-endian radix digits
 fn from_radix_digits_be(v: &[u8], radix: u32) -> BigUint {
     debug_assert!(!v.is_empty() && !radix.is_power_of_two());
     debug_assert!(v.iter().all(|&c| (c as u32) < radix));
 
+    #[cfg(feature = "rayon")]
+    {
+        if use_parallel(v.len() / get_radix_base(radix).1) {
+            return from_radix_digits_be_split(v, radix);
+        }
+    }
+
     // Estimate how big the result will be, so we can pre-allocate it.
     let bits = ilog2(radix) * v.len();
     let big_digits = idiv_ceil(bits, big_digit::BITS);
@@ -276,64 +396,105 @@ fn from_radix_digits_be(v: &[u8], radix: u32) -> BigUint {
     BigUint::new_native(data)
 }
 
+// Split huge inputs at a power of the radix and convert both halves in
+// parallel: `v = hi * base^half + lo`.
+#[cfg(feature = "rayon")]
+fn from_radix_digits_be_split(v: &[u8], radix: u32) -> BigUint {
+    let (base, power) = get_radix_base(radix);
+    let len = v.len() / power;
+    let half = len / 2;
+    let (hi, lo) = v.split_at(v.len() - half * power);
+
+    let ((hi, lo), shift) = parallel::join(
+        len,
+        || {
+            parallel::join(
+                len,
+                || from_radix_digits_be(hi, radix),
+                || from_radix_digits_be(lo, radix),
+            )
+        },
+        || Pow::pow(&BigUint::from(base), half),
+    );
+    hi * shift + lo
+}
+
+static PARSE_LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Sets the largest value, in bits, that string parsing will accept, or
+/// removes the limit with `None`.
+///
+/// The limit applies to `from_str_radix`, `parse_bytes` and `FromStr` for
+/// both `BigUint` and `BigInt`, so a service handling untrusted input can
+/// reject oversized numbers before doing the quadratic conversion work.
+/// Parsing is unlimited by default.
+pub fn set_parse_limit(max_bits: Option<usize>) {
+    PARSE_LIMIT.store(max_bits.unwrap_or(usize::MAX), AtomicOrdering::Relaxed);
+}
+
+/// Returns the limit set by [`set_parse_limit`], if any.
+pub fn parse_limit() -> Option<usize> {
+    match PARSE_LIMIT.load(AtomicOrdering::Relaxed) {
+        usize::MAX => None,
+        max_bits => Some(max_bits),
+    }
+}
+
 impl Num for BigUint {
     type FromStrRadixErr = ParseBigIntError;
 
     /// Creates and initializes a `BigUint`.
     fn from_str_radix(s: &str, radix: u32) -> Result<BigUint, ParseBigIntError> {
-        assert!(2 <= radix && radix <= 36, "The radix must be within 2...36");
-        let mut s = s;
-        if s.starts_with('+') {
-            let tail = &s[1..];
-            if !tail.starts_with('+') {
-                s = tail
-            }
-        }
+        let max_bits = parse_limit().unwrap_or(usize::MAX);
+        BigUint::from_str_radix_bounded(s, radix, max_bits)
+    }
+}
 
-        if s.is_empty() {
-            return Err(ParseBigIntError::empty());
+/// Parses `s`, which has already been checked against the size limit.
+fn parse_str_radix(s: &str, radix: u32) -> Result<BigUint, ParseBigIntError> {
+    assert!((2..=36).contains(&radix), "The radix must be within 2...36");
+    let mut s = s;
+    if s.starts_with('+') {
+        let tail = &s[1..];
+        if !tail.starts_with('+') {
+            s = tail
         }
+    }
 
-        if s.starts_with('_') {
-            // Must lead with a real digit!
-            return Err(ParseBigIntError::invalid());
-        }
+    if s.is_empty() {
+        return Err(ParseBigIntError::empty());
+    }
 
-        // First normalize all characters to plain digit values
-        let mut v = Vec::with_capacity(s.len());
-        for b in s.bytes() {
-            let d = match b {
-                b'0'..=b'9' => b - b'0',
-                b'a'..=b'z' => b - b'a' + 10,
-                b'A'..=b'Z' => b - b'A' + 10,
-                b'_' => continue,
-                _ => u8::MAX,
-            };
-            if d < radix as u8 {
-                v.push(d);
-            } else {
-                return Err(ParseBigIntError::invalid());
-            }
-        }
+    if s.starts_with('_') {
+        // Must lead with a real digit!
+        return Err(ParseBigIntError::invalid());
+    }
 
-        let res = if radix.is_power_of_two() {
-            // Powers of two can use bitwise masks and shifting instead of multiplication
-            let bits = ilog2(radix);
-            v.reverse();
-            if big_digit::BITS % bits == 0 {
-                from_bitwise_digits_le(&v, bits)
-            } else {
-                from_inexact_bitwise_digits_le(&v, bits)
-            }
-        } else {
-            from_radix_digits_be(&v, radix)
+    if radix.is_power_of_two() {
+        // Powers of two can be shifted straight into place, without normalizing the
+        // characters first
+        return from_bitwise_str(s.as_bytes(), ilog2(radix));
+    }
+
+    // First normalize all characters to plain digit values
+    let mut v = Vec::with_capacity(s.len());
+    for b in s.bytes() {
+        let d = match b {
+            b'_' => continue,
+            _ => digit_value(b),
         };
-        Ok(res)
+        if d < radix as u8 {
+            v.push(d);
+        } else {
+            return Err(ParseBigIntError::invalid());
+        }
     }
+
+    Ok(from_radix_digits_be(&v, radix))
 }
 
 forward_val_val_binop!(impl BitAnd for BigUint, bitand);
-forward_ref_val_binop!(impl BitAnd for BigUint, bitand);
+forward_ref_val_binop_commutative!(impl BitAnd for BigUint, bitand);
 
 // do not use forward_ref_ref_binop_commutative! for bitand so that we can
 // clone the smaller value rather than the larger, avoiding over-allocation
@@ -550,6 +711,28 @@ pow_impl!(usize);
 #[cfg(has_i128)]
 pow_impl!(u128);
 
+impl<'a, 'b> Pow<&'b BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    /// Panics if the result would need more than `POW_MAX_BITS` bits.
+    #[inline]
+    fn pow(self, exp: &BigUint) -> BigUint {
+        match self.checked_pow_bits(exp, POW_MAX_BITS) {
+            Some(res) => res,
+            None => panic!("pow result exceeds {} bits", POW_MAX_BITS),
+        }
+    }
+}
+
+impl<'a> Pow<BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn pow(self, exp: BigUint) -> BigUint {
+        self.pow(&exp)
+    }
+}
+
 forward_all_binop_to_val_ref_commutative!(impl Add for BigUint, add);
 forward_val_assign!(impl AddAssign for BigUint, add_assign);
 
@@ -580,6 +763,7 @@ impl<'a> AddAssign<&'a BigUint> for BigUint {
 
 promote_unsigned_scalars!(impl Add for BigUint, add);
 promote_unsigned_scalars_assign!(impl AddAssign for BigUint, add_assign);
+forward_unsigned_scalars_ref_assign!(impl AddAssign for BigUint, add_assign);
 forward_all_scalar_binop_to_val_val_commutative!(impl Add<u32> for BigUint, add);
 forward_all_scalar_binop_to_val_val_commutative!(impl Add<u64> for BigUint, add);
 #[cfg(has_i128)]
@@ -714,10 +898,23 @@ impl AddAssign<u128> for BigUint {
     }
 }
 
-forward_val_val_binop!(impl Sub for BigUint, sub);
 forward_ref_ref_binop!(impl Sub for BigUint, sub);
 forward_val_assign!(impl SubAssign for BigUint, sub_assign);
 
+impl Sub<BigUint> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn sub(self, other: BigUint) -> BigUint {
+        // forward to val-ref or ref-val, with the larger capacity as val
+        if self.capacity() >= other.capacity() {
+            self - &other
+        } else {
+            &self - other
+        }
+    }
+}
+
 impl<'a> Sub<&'a BigUint> for BigUint {
     type Output = BigUint;
 
@@ -753,6 +950,7 @@ impl<'a> Sub<BigUint> for &'a BigUint {
 
 promote_unsigned_scalars!(impl Sub for BigUint, sub);
 promote_unsigned_scalars_assign!(impl SubAssign for BigUint, sub_assign);
+forward_unsigned_scalars_ref_assign!(impl SubAssign for BigUint, sub_assign);
 forward_all_scalar_binop_to_val_val!(impl Sub<u32> for BigUint, sub);
 forward_all_scalar_binop_to_val_val!(impl Sub<u64> for BigUint, sub);
 #[cfg(has_i128)]
@@ -912,9 +1110,33 @@ impl Sub<BigUint> for u128 {
     }
 }
 
-forward_all_binop_to_ref_ref!(impl Mul for BigUint, mul);
+forward_ref_val_binop_commutative!(impl Mul for BigUint, mul);
 forward_val_assign!(impl MulAssign for BigUint, mul_assign);
 
+impl Mul<BigUint> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn mul(self, other: BigUint) -> BigUint {
+        // forward to val-ref, keeping a single digit operand as the ref
+        if self.data.len() == 1 {
+            other * &self
+        } else {
+            self * &other
+        }
+    }
+}
+
+impl<'a> Mul<&'a BigUint> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn mul(mut self, other: &BigUint) -> BigUint {
+        self *= other;
+        self
+    }
+}
+
 impl<'a, 'b> Mul<&'b BigUint> for &'a BigUint {
     type Output = BigUint;
 
@@ -929,22 +1151,51 @@ impl<'a, 'b> Mul<&'a BigInt> for &'b BigUint {
 
     #[inline]
     fn mul(self, other: &BigInt) -> BigInt {
-        BigInt {
-            data: mul3(&self.data[..], &other.digits()[..]),
-            sign: other.sign,
-        }
+        BigInt::from_biguint(other.sign, mul3(&self.data[..], other.digits()))
+    }
+}
+
+impl<'a, 'b> Mul<&'a BigUint> for &'b BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn mul(self, other: &BigUint) -> BigInt {
+        other * self
+    }
+}
+
+impl<'a> Mul<&'a BigUint> for BigInt {
+    type Output = BigInt;
+
+    #[inline]
+    fn mul(self, other: &BigUint) -> BigInt {
+        other * &self
+    }
+}
+
+impl<'a> Mul<BigInt> for &'a BigUint {
+    type Output = BigInt;
+
+    #[inline]
+    fn mul(self, other: BigInt) -> BigInt {
+        self * &other
     }
 }
 
 impl<'a> MulAssign<&'a BigUint> for BigUint {
     #[inline]
     fn mul_assign(&mut self, other: &'a BigUint) {
-        *self = &*self * other
+        match other.data[..] {
+            [] => self.data.clear(),
+            [digit] => *self *= digit,
+            _ => *self = &*self * other,
+        }
     }
 }
 
 promote_unsigned_scalars!(impl Mul for BigUint, mul);
 promote_unsigned_scalars_assign!(impl MulAssign for BigUint, mul_assign);
+forward_unsigned_scalars_ref_assign!(impl MulAssign for BigUint, mul_assign);
 forward_all_scalar_binop_to_val_val_commutative!(impl Mul<u32> for BigUint, mul);
 forward_all_scalar_binop_to_val_val_commutative!(impl Mul<u64> for BigUint, mul);
 #[cfg(has_i128)]
@@ -1049,6 +1300,46 @@ impl MulAssign<u128> for BigUint {
     }
 }
 
+impl MulAdd<BigUint> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn mul_add(self, a: BigUint, b: BigUint) -> BigUint {
+        let mut acc = b;
+        acc.add_mul_assign(&self, &a);
+        acc
+    }
+}
+
+impl<'a, 'b, 'c> MulAdd<&'b BigUint, &'c BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn mul_add(self, a: &BigUint, b: &BigUint) -> BigUint {
+        let mut acc = b.clone();
+        acc.add_mul_assign(self, a);
+        acc
+    }
+}
+
+impl MulAddAssign<BigUint> for BigUint {
+    #[inline]
+    fn mul_add_assign(&mut self, a: BigUint, b: BigUint) {
+        let mut acc = b;
+        acc.add_mul_assign(self, &a);
+        *self = acc;
+    }
+}
+
+impl<'a, 'b> MulAddAssign<&'a BigUint, &'b BigUint> for BigUint {
+    #[inline]
+    fn mul_add_assign(&mut self, a: &BigUint, b: &BigUint) {
+        let mut acc = b.clone();
+        acc.add_mul_assign(self, a);
+        *self = acc;
+    }
+}
+
 forward_all_binop_to_ref_ref!(impl Div for BigUint, div);
 forward_val_assign!(impl DivAssign for BigUint, div_assign);
 
@@ -1070,6 +1361,7 @@ impl<'a> DivAssign<&'a BigUint> for BigUint {
 
 promote_unsigned_scalars!(impl Div for BigUint, div);
 promote_unsigned_scalars_assign!(impl DivAssign for BigUint, div_assign);
+forward_unsigned_scalars_ref_assign!(impl DivAssign for BigUint, div_assign);
 forward_all_scalar_binop_to_val_val!(impl Div<u32> for BigUint, div);
 forward_all_scalar_binop_to_val_val!(impl Div<u64> for BigUint, div);
 #[cfg(has_i128)]
@@ -1080,6 +1372,9 @@ impl Div<u32> for BigUint {
 
     #[inline]
     fn div(self, other: u32) -> BigUint {
+        if other == 0 {
+            panic!()
+        }
         let (q, _) = div_rem_digit(self, other as BigDigit);
         q
     }
@@ -1087,7 +1382,8 @@ impl Div<u32> for BigUint {
 impl DivAssign<u32> for BigUint {
     #[inline]
     fn div_assign(&mut self, other: u32) {
-        *self = &*self / other;
+        let n = mem::take(self);
+        *self = n / other;
     }
 }
 
@@ -1107,16 +1403,32 @@ impl Div<BigUint> for u32 {
 impl Div<u64> for BigUint {
     type Output = BigUint;
 
+    #[cfg(not(feature = "u64_digit"))]
+    #[inline]
+    fn div(self, other: u64) -> BigUint {
+        if other <= BigDigit::MAX as u64 {
+            self / other as BigDigit
+        } else {
+            let (q, _) = div_rem(&self, &From::from(other));
+            q
+        }
+    }
+
+    #[cfg(feature = "u64_digit")]
     #[inline]
     fn div(self, other: u64) -> BigUint {
-        let (q, _) = self.div_rem(&From::from(other));
+        if other == 0 {
+            panic!()
+        }
+        let (q, _) = div_rem_digit(self, other);
         q
     }
 }
 impl DivAssign<u64> for BigUint {
     #[inline]
     fn div_assign(&mut self, other: u64) {
-        *self = &*self / other;
+        let n = mem::take(self);
+        *self = n / other;
     }
 }
 
@@ -1151,8 +1463,12 @@ impl Div<u128> for BigUint {
 
     #[inline]
     fn div(self, other: u128) -> BigUint {
-        let (q, _) = self.div_rem(&From::from(other));
-        q
+        if other <= BigDigit::MAX as u128 {
+            self / other as BigDigit
+        } else {
+            let (q, _) = div_rem(&self, &From::from(other));
+            q
+        }
     }
 }
 
@@ -1160,7 +1476,8 @@ impl Div<u128> for BigUint {
 impl DivAssign<u128> for BigUint {
     #[inline]
     fn div_assign(&mut self, other: u128) {
-        *self = &*self / other;
+        let n = mem::take(self);
+        *self = n / other;
     }
 }
 
@@ -1205,6 +1522,13 @@ impl<'a, 'b> Rem<&'b BigUint> for &'a BigUint {
 
     #[inline]
     fn rem(self, other: &BigUint) -> BigUint {
+        #[cfg(feature = "known-primes")]
+        {
+            if let Some(prime) = KnownPrime::detect(other) {
+                return prime.rem(self, other);
+            }
+        }
+
         let (_, r) = self.div_rem(other);
         r
     }
@@ -1218,18 +1542,21 @@ impl<'a> RemAssign<&'a BigUint> for BigUint {
 
 promote_unsigned_scalars!(impl Rem for BigUint, rem);
 promote_unsigned_scalars_assign!(impl RemAssign for BigUint, rem_assign);
-forward_all_scalar_binop_to_val_val!(impl Rem<u32> for BigUint, rem);
-forward_all_scalar_binop_to_val_val!(impl Rem<u64> for BigUint, rem);
+forward_unsigned_scalars_ref_assign!(impl RemAssign for BigUint, rem_assign);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u32> for BigUint, rem);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u64> for BigUint, rem);
 #[cfg(has_i128)]
-forward_all_scalar_binop_to_val_val!(impl Rem<u128> for BigUint, rem);
+forward_all_scalar_binop_to_ref_val!(impl Rem<u128> for BigUint, rem);
 
-impl Rem<u32> for BigUint {
+impl<'a> Rem<u32> for &'a BigUint {
     type Output = BigUint;
 
     #[inline]
     fn rem(self, other: u32) -> BigUint {
-        let (_, r) = div_rem_digit(self, other as BigDigit);
-        From::from(r)
+        if other == 0 {
+            panic!()
+        }
+        From::from(rem_digit(&self.data, other as BigDigit))
     }
 }
 impl RemAssign<u32> for BigUint {
@@ -1280,13 +1607,27 @@ impl_rem_assign_scalar!(i32, to_i32);
 impl_rem_assign_scalar!(i16, to_i16);
 impl_rem_assign_scalar!(i8, to_i8);
 
-impl Rem<u64> for BigUint {
+impl<'a> Rem<u64> for &'a BigUint {
     type Output = BigUint;
 
+    #[cfg(not(feature = "u64_digit"))]
     #[inline]
     fn rem(self, other: u64) -> BigUint {
-        let (_, r) = self.div_rem(&From::from(other));
-        r
+        if other <= BigDigit::MAX as u64 {
+            self % other as BigDigit
+        } else {
+            let (_, r) = div_rem(self, &From::from(other));
+            r
+        }
+    }
+
+    #[cfg(feature = "u64_digit")]
+    #[inline]
+    fn rem(self, other: u64) -> BigUint {
+        if other == 0 {
+            panic!()
+        }
+        From::from(rem_digit(&self.data, other))
     }
 }
 impl RemAssign<u64> for BigUint {
@@ -1307,13 +1648,17 @@ impl Rem<BigUint> for u64 {
 }
 
 #[cfg(has_i128)]
-impl Rem<u128> for BigUint {
+impl<'a> Rem<u128> for &'a BigUint {
     type Output = BigUint;
 
     #[inline]
     fn rem(self, other: u128) -> BigUint {
-        let (_, r) = self.div_rem(&From::from(other));
-        r
+        if other <= BigDigit::MAX as u128 {
+            self % other as BigDigit
+        } else {
+            let (_, r) = div_rem(self, &From::from(other));
+            r
+        }
     }
 }
 #[cfg(has_i128)]
@@ -1436,7 +1781,7 @@ impl Integer for BigUint {
     /// Returns `true` if the number is a multiple of `other`.
     #[inline]
     fn is_multiple_of(&self, other: &BigUint) -> bool {
-        (self % other).is_zero()
+        BigUint::is_multiple_of(self, other)
     }
 
     /// Returns `true` if the number is divisible by `2`.
@@ -1485,6 +1830,37 @@ where
     x
 }
 
+/// Returns the square root and remainder of `m`, for `2^(2l-2) <= m < 2^(2l)`.
+///
+/// Reference: Brent & Zimmermann, Modern Computer Arithmetic, v0.5.9,
+/// Algorithm 1.12, with the base `β = 2^k` for `k = l / 2`.
+fn sqrt_rem_normalized(m: &BigUint, l: usize) -> (BigUint, BigUint) {
+    if l <= 32 {
+        let m = m.to_u64().unwrap();
+        let s = m.sqrt();
+        return (s.into(), (m - s * s).into());
+    }
+
+    // m = a * 2^(2k) + a1 * 2^k + a0, where `a` has 2(l - k) bits and is
+    // itself normalized.
+    let k = l / 2;
+    let (s1, r1) = sqrt_rem_normalized(&(m >> (2 * k)), l - k);
+    let a1 = (m >> k).truncate_to_bits(k);
+    let a0 = m.truncate_to_bits(k);
+
+    let (q, u) = ((r1 << k) + a1).div_rem(&(&s1 << 1));
+    let s = (s1 << k) + &q;
+    let t = (u << k) + a0;
+    let q2 = &q * &q;
+    if t >= q2 {
+        (s, t - q2)
+    } else {
+        // The root is one too large, which the remainder can absorb.
+        let r = t + (&s << 1) - 1u32 - q2;
+        (s - 1u32, r)
+    }
+}
+
 impl Roots for BigUint {
     // nth_root, sqrt and cbrt use Newton's method to compute
     // principal root of a given degree for a given integer.
@@ -1973,6 +2349,25 @@ fn to_inexact_bitwise_digits_le(u: &BigUint, bits: usize) -> Vec<u8> {
 fn to_radix_digits_le(u: &BigUint, radix: u32) -> Vec<u8> {
     debug_assert!(!u.is_zero() && !radix.is_power_of_two());
 
+    #[cfg(feature = "rayon")]
+    {
+        if use_parallel(u.data.len()) {
+            return to_radix_digits_le_split(u, radix);
+        }
+    }
+
+    // An unlimited budget never runs out.
+    to_radix_digits_le_with_budget(u, radix, &mut Budget::unlimited()).unwrap()
+}
+
+// Converts on one thread, charging `budget` for each division by the radix base.
+fn to_radix_digits_le_with_budget(
+    u: &BigUint,
+    radix: u32,
+    budget: &mut Budget,
+) -> Result<Vec<u8>, BudgetExceeded> {
+    debug_assert!(!u.is_zero() && !radix.is_power_of_two());
+
     // Estimate how big the result will be, so we can pre-allocate it.
     let bits = ilog2(radix);
     let radix_digits = idiv_ceil(u.bits(), bits);
@@ -1983,6 +2378,7 @@ fn to_radix_digits_le(u: &BigUint, radix: u32) -> Vec<u8> {
     let radix = radix as BigDigit;
 
     while digits.data.len() > 1 {
+        budget.charge(digits.data.len() as u64)?;
         let (q, mut r) = div_rem_digit(digits, base);
         for _ in 0..power {
             res.push((r % radix) as u8);
@@ -1997,6 +2393,31 @@ fn to_radix_digits_le(u: &BigUint, radix: u32) -> Vec<u8> {
         r /= radix;
     }
 
+    Ok(res)
+}
+
+// Split huge inputs at a power of the radix, `u = hi * base^half + lo`, and
+// convert both halves in parallel. `lo` always fills exactly `half * power`
+// radix digits, so it is padded with zeros.
+#[cfg(feature = "rayon")]
+fn to_radix_digits_le_split(u: &BigUint, radix: u32) -> Vec<u8> {
+    let (base, power) = get_radix_base(radix);
+    let half = u.data.len() / 2;
+    let (hi, lo) = u.div_rem(&Pow::pow(&BigUint::from(base), half));
+
+    let (mut res, hi) = parallel::join(
+        u.data.len(),
+        || {
+            if lo.is_zero() {
+                Vec::new()
+            } else {
+                to_radix_digits_le(&lo, radix)
+            }
+        },
+        || to_radix_digits_le(&hi, radix),
+    );
+    res.resize(half * power, 0);
+    res.extend(hi);
     res
 }
 
@@ -2020,6 +2441,44 @@ pub fn to_radix_le(u: &BigUint, radix: u32) -> Vec<u8> {
     }
 }
 
+// Write ASCII digits in a power of two radix (bits == ilog2(radix)), most significant first,
+// reading the bits of each digit straight out of u
+fn to_bitwise_str_be(u: &BigUint, bits: usize) -> Vec<u8> {
+    debug_assert!(!u.is_zero() && bits <= 5);
+
+    let mask: BigDigit = (1 << bits) - 1;
+    let digits = idiv_ceil(u.bits(), bits);
+    let mut res = Vec::with_capacity(digits);
+
+    for i in (0..digits).rev() {
+        let (j, shift) = (i * bits / big_digit::BITS, i * bits % big_digit::BITS);
+        let mut r = u.data[j] >> shift;
+        // the digit may straddle two big_digits
+        if shift + bits > big_digit::BITS {
+            if let Some(&hi) = u.data.get(j + 1) {
+                r |= hi << (big_digit::BITS - shift);
+            }
+        }
+
+        let r = (r & mask) as u8;
+        res.push(if r < 10 { r + b'0' } else { r - 10 + b'a' });
+    }
+
+    res
+}
+
+// Returns the ASCII digits of u, most significant first
+pub(crate) fn to_str_radix_be(u: &BigUint, radix: u32) -> Vec<u8> {
+    if radix.is_power_of_two() && !u.is_zero() {
+        assert!((2..=36).contains(&radix), "The radix must be within 2...36");
+        return to_bitwise_str_be(u, ilog2(radix));
+    }
+
+    let mut v = to_str_radix_reversed(u, radix);
+    v.reverse();
+    v
+}
+
 pub fn to_str_radix_reversed(u: &BigUint, radix: u32) -> Vec<u8> {
     assert!(2 <= radix && radix <= 36, "The radix must be within 2...36");
 
@@ -2028,9 +2487,13 @@ pub fn to_str_radix_reversed(u: &BigUint, radix: u32) -> Vec<u8> {
     }
 
     let mut res = to_radix_le(u, radix);
+    digits_to_ascii(&mut res, radix);
+    res
+}
 
-    // Now convert everything to ASCII digits.
-    for r in &mut res {
+// Converts digit values to their ASCII characters in place.
+fn digits_to_ascii(digits: &mut [u8], radix: u32) {
+    for r in digits {
         debug_assert!((*r as u32) < radix);
         if *r < 10 {
             *r += b'0';
@@ -2038,7 +2501,6 @@ pub fn to_str_radix_reversed(u: &BigUint, radix: u32) -> Vec<u8> {
             *r += b'a' - 10;
         }
     }
-    res
 }
 
 #[cfg(not(feature = "u64_digit"))]
@@ -2101,10 +2563,50 @@ impl BigUint {
         BigUint::new_native(slice.into())
     }
 
+    /// Creates a `BigUint` from little-endian digits, in a `const` context.
+    ///
+    /// The digits are stored inline, so this can define well-known values as
+    /// a `const` or `static` without any runtime initialization. `N` must not
+    /// be larger than the inline capacity `VEC_SIZE`; a longer array fails to
+    /// evaluate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// const SEVEN: BigUint = BigUint::from_limbs_const([7, 0]);
+    /// assert_eq!(SEVEN, BigUint::from(7u32));
+    /// ```
+    pub const fn from_limbs_const<const N: usize>(limbs: [BigDigit; N]) -> BigUint {
+        let mut buf = [0; VEC_SIZE];
+        let mut len = 0;
+        let mut i = 0;
+        while i < N {
+            buf[i] = limbs[i];
+            if limbs[i] != 0 {
+                len = i + 1;
+            }
+            i += 1;
+        }
+
+        // Safety: `len <= N`, and `N <= VEC_SIZE` or writing `buf[i]` above
+        // would have failed.
+        BigUint {
+            data: unsafe { SmallVec::from_const_with_len_unchecked(buf, len) },
+        }
+    }
+
     pub fn get_limb(&self, i: usize) -> BigDigit {
         self.data[i]
     }
 
+    /// Returns a borrowed view of the digits of `self`.
+    #[inline]
+    pub fn as_ref_view(&self) -> BigUintRef<'_> {
+        BigUintRef::new(&self.data)
+    }
+
     /// Assign a value to a `BigUint`.
     ///
     /// The digits are in little-endian base 2<sup>32</sup>.
@@ -2194,7 +2696,52 @@ impl BigUint {
             .and_then(|s| BigUint::from_str_radix(s, radix).ok())
     }
 
-    /// Creates and initializes a `BigUint`. Each u8 of the input slice is
+    /// Parses `s` like `from_str_radix`, but fails if the value would need
+    /// more than `max_bits` bits.
+    ///
+    /// Oversized input is rejected from its digit count alone, before any
+    /// conversion work is done. This bound replaces the global
+    /// [`set_parse_limit`](fn.set_parse_limit.html) for this call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// assert_eq!(BigUint::from_str_radix_bounded("255", 10, 8), Ok(BigUint::from(255u32)));
+    /// assert!(BigUint::from_str_radix_bounded("256", 10, 8).is_err());
+    ///
+    /// let huge = "9".repeat(1_000_000);
+    /// assert!(BigUint::from_str_radix_bounded(&huge, 10, 4096).is_err());
+    /// ```
+    pub fn from_str_radix_bounded(
+        s: &str,
+        radix: u32,
+        max_bits: usize,
+    ) -> Result<BigUint, ParseBigIntError> {
+        assert!((2..=36).contains(&radix), "The radix must be within 2...36");
+
+        // A number with `n` significant digits is at least `radix^(n - 1)`,
+        // so it has more than `(n - 1) * ilog2(radix)` bits.
+        let digits = s
+            .trim_start_matches('+')
+            .bytes()
+            .skip_while(|&b| b == b'0' || b == b'_')
+            .filter(|&b| b != b'_')
+            .count();
+        let min_bits = digits.saturating_sub(1).saturating_mul(ilog2(radix));
+        if min_bits >= max_bits && digits > 0 {
+            return Err(ParseBigIntError::too_large());
+        }
+
+        let n = parse_str_radix(s, radix)?;
+        if n.bits() > max_bits {
+            return Err(ParseBigIntError::too_large());
+        }
+        Ok(n)
+    }
+
+    /// Creates and initializes a `BigUint`. Each u8 of the input slice is
     /// interpreted as one digit of the number
     /// and must therefore be less than `radix`.
     ///
@@ -2329,8 +2876,7 @@ impl BigUint {
     /// ```
     #[inline]
     pub fn to_str_radix(&self, radix: u32) -> String {
-        let mut v = to_str_radix_reversed(self, radix);
-        v.reverse();
+        let v = to_str_radix_be(self, radix);
         unsafe { String::from_utf8_unchecked(v) }
     }
 
@@ -2384,13 +2930,432 @@ impl BigUint {
         self.data.len() * big_digit::BITS - zeros as usize
     }
 
+    /// Returns `true` if `self` is `2^k` for some `k`.
+    pub fn is_power_of_two(&self) -> bool {
+        match self.data.split_last() {
+            Some((&hi, lo)) => hi.is_power_of_two() && lo.iter().all(Zero::is_zero),
+            None => false,
+        }
+    }
+
+    /// Returns the smallest power of two greater than or equal to `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// assert_eq!(BigUint::from(0u32).next_power_of_two(), BigUint::from(1u32));
+    /// assert_eq!(BigUint::from(64u32).next_power_of_two(), BigUint::from(64u32));
+    /// assert_eq!(BigUint::from(65u32).next_power_of_two(), BigUint::from(128u32));
+    /// ```
+    pub fn next_power_of_two(&self) -> BigUint {
+        if self.is_power_of_two() {
+            return self.clone();
+        }
+        BigUint::one() << self.bits()
+    }
+
+    /// Returns the largest power of two less than or equal to `self`, or
+    /// zero if `self` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// assert_eq!(BigUint::from(0u32).prev_power_of_two(), BigUint::from(0u32));
+    /// assert_eq!(BigUint::from(64u32).prev_power_of_two(), BigUint::from(64u32));
+    /// assert_eq!(BigUint::from(127u32).prev_power_of_two(), BigUint::from(64u32));
+    /// ```
+    pub fn prev_power_of_two(&self) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        BigUint::one() << (self.bits() - 1)
+    }
+
+    /// Returns the base 2 logarithm of `self` as an `f64`, or negative
+    /// infinity for zero.
+    ///
+    /// Only the leading 64 bits are converted, so the result is accurate to
+    /// double precision even for values far beyond the range of `f64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(1u32) << 5000;
+    /// assert_eq!(n.log2_f64(), 5000.0);
+    /// assert_eq!(BigUint::from(8u32).log2_f64(), 3.0);
+    /// ```
+    pub fn log2_f64(&self) -> f64 {
+        if self.is_zero() {
+            return f64::NEG_INFINITY;
+        }
+        let (top, shift) = self.leading_u64();
+        log2(top as f64) + shift as f64
+    }
+
+    /// Returns the natural logarithm of `self` as an `f64`, or negative
+    /// infinity for zero.
+    ///
+    /// Like [`log2_f64`](#method.log2_f64), this never overflows.
+    pub fn ln_f64(&self) -> f64 {
+        self.log2_f64() * f64::consts::LN_2
+    }
+
+    /// Returns the leading 64 bits of `self` and the number of bits below
+    /// them, so that `self` is `top << shift` plus the dropped bits.
+    fn leading_u64(&self) -> (u64, usize) {
+        // The top 128 bits always hold at least 64 significant ones.
+        let n = cmp::min(self.data.len(), 128 / big_digit::BITS);
+        let mut acc: u128 = 0;
+        for &d in self.data.iter().rev().take(n) {
+            acc = (acc << big_digit::BITS) | u128::from(d);
+        }
+        let below = (self.data.len() - n) * big_digit::BITS;
+        let acc_bits = 128 - acc.leading_zeros() as usize;
+        let s = acc_bits.saturating_sub(64);
+        ((acc >> s) as u64, below + s)
+    }
+
+    /// Returns the low 64 bits of `self`, like an `as u64` cast of a primitive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = (BigUint::from(1u32) << 64) + 5u32;
+    /// assert_eq!(n.to_u64_wrapping(), 5);
+    /// ```
+    pub fn to_u64_wrapping(&self) -> u64 {
+        let mut ret: u64 = 0;
+        for (i, &d) in self.data.iter().take(64 / big_digit::BITS).enumerate() {
+            ret |= u64::from(d) << (i * big_digit::BITS);
+        }
+        ret
+    }
+
+    /// Returns the low 128 bits of `self`, like an `as u128` cast of a primitive.
+    #[cfg(has_i128)]
+    pub fn to_u128_wrapping(&self) -> u128 {
+        let mut ret: u128 = 0;
+        for (i, &d) in self.data.iter().take(128 / big_digit::BITS).enumerate() {
+            ret |= (d as u128) << (i * big_digit::BITS);
+        }
+        ret
+    }
+
+    /// Compares `self` with a `u64`, without converting it to a `BigUint`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cmp::Ordering;
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(300u32);
+    /// assert_eq!(n.cmp_u64(255), Ordering::Greater);
+    /// assert_eq!(n.cmp_u64(300), Ordering::Equal);
+    /// assert_eq!((n << 64).cmp_u64(u64::MAX), Ordering::Greater);
+    /// ```
+    pub fn cmp_u64(&self, other: u64) -> Ordering {
+        if self.bits() > 64 {
+            return Greater;
+        }
+        self.to_u64_wrapping().cmp(&other)
+    }
+
+    /// Returns `true` if `self` equals the `u64` value `other`.
+    #[inline]
+    pub fn eq_u64(&self, other: u64) -> bool {
+        self.cmp_u64(other) == Equal
+    }
+
+    /// Compares `self` with a `u128`, without converting it to a `BigUint`.
+    #[cfg(has_i128)]
+    pub fn cmp_u128(&self, other: u128) -> Ordering {
+        if self.bits() > 128 {
+            return Greater;
+        }
+        self.to_u128_wrapping().cmp(&other)
+    }
+
+    /// Returns the low `bits` bits of `self`, that is `self mod 2^bits`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(0x1234_5678u32);
+    /// assert_eq!(n.truncate_to_bits(12), BigUint::from(0x678u32));
+    /// assert_eq!(n.truncate_to_bits(64), n);
+    /// ```
+    pub fn truncate_to_bits(&self, bits: usize) -> BigUint {
+        let digits = cmp::min(self.data.len(), idiv_ceil(bits, big_digit::BITS));
+        let mut ret = BigUint::from_slice_native(&self.data[..digits]);
+        ret.keep_low_bits_assign(bits);
+        ret
+    }
+
+    /// Truncates `self` to its low `bits` bits in place, that is `self %= 2^bits`.
+    ///
+    /// This drops the digits above `bits` and masks the top remaining one, without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let mut n = BigUint::from(0x1234_5678u32);
+    /// n.keep_low_bits_assign(12);
+    /// assert_eq!(n, BigUint::from(0x678u32));
+    /// ```
+    #[inline]
+    pub fn keep_low_bits_assign(&mut self, bits: usize) {
+        truncate_bits(self, bits);
+    }
+
+    /// Returns the low `bits` bits of `self`, like
+    /// [`truncate_to_bits`](#method.truncate_to_bits). The in-place form is
+    /// [`keep_low_bits_assign`](#method.keep_low_bits_assign).
+    #[inline]
+    pub fn low_bits(&self, bits: usize) -> BigUint {
+        self.truncate_to_bits(bits)
+    }
+
+    /// Returns the bits of `self` from position `bits` up, that is
+    /// `self >> bits`, copying only the digits that are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(0x1234_5678u32);
+    /// assert_eq!(n.high_bits(12), BigUint::from(0x1_2345u32));
+    /// assert_eq!(n.low_bits(12), BigUint::from(0x678u32));
+    /// ```
+    #[inline]
+    pub fn high_bits(&self, bits: usize) -> BigUint {
+        biguint_shr(Cow::Borrowed(self), bits)
+    }
+
+    /// Replaces `self` with its bits from position `bits` up, moving the
+    /// kept digits down within the existing storage.
+    pub fn high_bits_assign(&mut self, bits: usize) {
+        let digits = bits / big_digit::BITS;
+        if digits >= self.data.len() {
+            self.data.clear();
+            return;
+        }
+        self.data.drain(..digits);
+
+        let shift = bits % big_digit::BITS;
+        if shift > 0 {
+            let mut borrow = 0;
+            for d in self.data.iter_mut().rev() {
+                let next = *d << (big_digit::BITS - shift);
+                *d = (*d >> shift) | borrow;
+                borrow = next;
+            }
+            self.normalize();
+        }
+    }
+
+    /// Returns the low `width` bits of `self` in reverse order, so bit `i`
+    /// of the result is bit `width - 1 - i` of `self`. Bits at `width` and
+    /// above are ignored.
+    ///
+    /// Each digit is reversed with `reverse_bits`, and the digits are
+    /// then shifted into place together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(0b0001_1011u32);
+    /// assert_eq!(n.bit_reverse(8), BigUint::from(0b1101_1000u32));
+    /// assert_eq!(n.bit_reverse(5), BigUint::from(0b1_1011u32));
+    /// assert_eq!(n.bit_reverse(3), BigUint::from(0b110u32));
+    /// ```
+    pub fn bit_reverse(&self, width: usize) -> BigUint {
+        // Digits past the end of `self` would only reverse to zeros, so they
+        // are not materialized.
+        let digits = cmp::min(idiv_ceil(width, big_digit::BITS), self.data.len());
+        let mut data = SmallVec::with_capacity(digits);
+        for &d in self.data[..digits].iter().rev() {
+            data.push(d.reverse_bits());
+        }
+
+        // This reversed the low `digits * BITS` bits. If that is more than
+        // `width`, the bits from `width` up are now the lowest ones, so shift
+        // them out. Otherwise shift the result up to the top of the width.
+        let mut ret = BigUint::new_native(data);
+        let reversed = digits * big_digit::BITS;
+        if reversed > width {
+            ret.high_bits_assign(reversed - width);
+        } else if !ret.is_zero() {
+            ret <<= width - reversed;
+        }
+        ret
+    }
+
+    /// Splits `self` at bit position `at`, returning `(high, low)` such that
+    /// `self == (high << at) | low` with `low < 2^at`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(0xabcd_ef01u32);
+    /// let (high, low) = n.split_bits(12);
+    /// assert_eq!(high, BigUint::from(0xa_bcdeu32));
+    /// assert_eq!(low, BigUint::from(0xf01u32));
+    /// assert_eq!(BigUint::concat_bits(&high, &low, 12), n);
+    /// ```
+    pub fn split_bits(&self, at: usize) -> (BigUint, BigUint) {
+        (self.high_bits(at), self.low_bits(at))
+    }
+
+    /// Returns `(high << low_width) | low`, the inverse of
+    /// [`split_bits`](#method.split_bits).
+    ///
+    /// Panics if `low` doesn't fit in `low_width` bits.
+    pub fn concat_bits(high: &BigUint, low: &BigUint, low_width: usize) -> BigUint {
+        assert!(
+            low.bits() <= low_width,
+            "low part does not fit in {} bits",
+            low_width
+        );
+
+        let mut ret = high << low_width;
+        if ret.data.len() < low.data.len() {
+            ret.data.resize(low.data.len(), 0);
+        }
+        for (d, &l) in ret.data.iter_mut().zip(low.data.iter()) {
+            *d |= l;
+        }
+        ret
+    }
+
+    /// Returns the inverse of `self` modulo `2^k`, or `None` if `self` is even
+    /// and `k` is not zero.
+    ///
+    /// The inverse is found by Newton's iteration `x = x * (2 - self * x)`,
+    /// which doubles the number of correct low bits each step, so only
+    /// `log2(k)` multiplications are needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let a = BigUint::from(12345u32);
+    /// let inv = a.inv_mod_2k(100).unwrap();
+    /// assert_eq!((&a * &inv).truncate_to_bits(100), BigUint::from(1u32));
+    /// assert_eq!(BigUint::from(6u32).inv_mod_2k(100), None);
+    /// ```
+    pub fn inv_mod_2k(&self, k: usize) -> Option<BigUint> {
+        if k == 0 {
+            return Some(BigUint::zero());
+        }
+        if self.is_even() {
+            return None;
+        }
+
+        let a = self.truncate_to_bits(k);
+        let mut x = BigUint::one();
+        let mut bits = 1;
+        while bits < k {
+            bits = cmp::min(2 * bits, k);
+            // x - x * (a * x - 1), where a * x is odd so the subtraction can't wrap.
+            let mut e = &a * &x;
+            truncate_bits(&mut e, bits);
+            e -= 1u32;
+            let mut xe = &x * e;
+            truncate_bits(&mut xe, bits);
+            x += BigUint::one() << bits;
+            x -= xe;
+            truncate_bits(&mut x, bits);
+        }
+        Some(x)
+    }
+
+    /// Swaps `a` and `b` if `choice` is `true`, and leaves them alone
+    /// otherwise.
+    ///
+    /// Both values are padded to a common length and every digit pair is
+    /// exchanged through a mask, so the work done and the memory touched
+    /// don't depend on `choice`. Only the lengths of the inputs and results
+    /// are observable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let mut a = BigUint::from(1u32);
+    /// let mut b = BigUint::from(2u32) << 100;
+    /// BigUint::conditional_swap(&mut a, &mut b, true);
+    /// assert_eq!(a, BigUint::from(2u32) << 100);
+    /// assert_eq!(b, BigUint::from(1u32));
+    /// ```
+    pub fn conditional_swap(a: &mut BigUint, b: &mut BigUint, choice: bool) {
+        let len = cmp::max(a.data.len(), b.data.len());
+        a.data.resize(len, 0);
+        b.data.resize(len, 0);
+
+        let mask = BigDigit::from(choice).wrapping_neg();
+        for (x, y) in a.data.iter_mut().zip(b.data.iter_mut()) {
+            let t = (*x ^ *y) & mask;
+            *x ^= t;
+            *y ^= t;
+        }
+
+        a.normalize();
+        b.normalize();
+    }
+
+    /// Returns a copy of `b` if `choice` is `true`, or of `a` otherwise,
+    /// reading both inputs in full either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let a = BigUint::from(7u32);
+    /// let b = BigUint::from(9u32);
+    /// assert_eq!(BigUint::conditional_select(&a, &b, false), a);
+    /// assert_eq!(BigUint::conditional_select(&a, &b, true), b);
+    /// ```
+    pub fn conditional_select(a: &BigUint, b: &BigUint, choice: bool) -> BigUint {
+        let len = cmp::max(a.data.len(), b.data.len());
+        let mask = BigDigit::from(choice).wrapping_neg();
+        let digit = |x: &BigUint, i: usize| x.data.get(i).copied().unwrap_or(0);
+        let data = (0..len)
+            .map(|i| digit(a, i) ^ ((digit(a, i) ^ digit(b, i)) & mask))
+            .collect();
+        BigUint::new_native(data)
+    }
+
     /// Strips off trailing zero bigdigits - comparisons require the last element in the vector to
     /// be nonzero.
+    ///
+    /// The kernels only leave the few top digits they may have cleared, so this scans a short
+    /// tail and then truncates once, rather than popping digit by digit.
     #[inline]
     pub(crate) fn normalize(&mut self) {
-        while let Some(&0) = self.data.last() {
-            self.data.pop();
-        }
+        let len = self.data.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+        self.data.truncate(len);
     }
 
     /// Returns a normalized `BigUint`.
@@ -2408,10 +3373,20 @@ impl BigUint {
 
         // For an odd modulus, we can use Montgomery multiplication in base 2^32.
         if modulus.is_odd() {
+            trace(
+                Algorithm::Montgomery,
+                exponent.data.len(),
+                modulus.data.len(),
+            );
             return monty_modpow(self, exponent, modulus);
         }
 
         // Otherwise do basically the same as `num::pow`, but with a modulus.
+        trace(
+            Algorithm::PlainModpow,
+            exponent.data.len(),
+            modulus.data.len(),
+        );
         let one = BigUint::one();
         if exponent.is_zero() {
             return one;
@@ -2438,36 +3413,1267 @@ impl BigUint {
         acc
     }
 
-    /// Returns the truncated principal square root of `self` --
-    /// see [Roots::sqrt](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#method.sqrt)
-    pub fn sqrt(&self) -> Self {
-        Roots::sqrt(self)
-    }
+    /// Returns `self ^ exponent`, or `None` if the result would need more
+    /// than `max_bits` bits.
+    ///
+    /// The bound is checked before any multiplication is done, so a huge
+    /// exponent is rejected cheaply.
+    pub fn checked_pow_bits(&self, exponent: &Self, max_bits: usize) -> Option<Self> {
+        if exponent.is_zero() || self.is_one() {
+            return Some(BigUint::one()).filter(|_| max_bits >= 1);
+        }
+        if self.is_zero() {
+            return Some(BigUint::zero());
+        }
 
-    /// Returns the truncated principal cube root of `self` --
-    /// see [Roots::cbrt](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#method.cbrt).
-    pub fn cbrt(&self) -> Self {
-        Roots::cbrt(self)
-    }
+        // The result has at least `(bits - 1) * exp + 1` bits.
+        let exp = exponent.to_usize()?;
+        let low = (self.bits() - 1).checked_mul(exp)?.checked_add(1)?;
+        if low > max_bits {
+            return None;
+        }
 
-    /// Returns the truncated principal `n`th root of `self` --
-    /// see [Roots::nth_root](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#tymethod.nth_root).
-    pub fn nth_root(&self, n: u32) -> Self {
-        Roots::nth_root(self, n)
+        let res = Pow::pow(self, exp);
+        if res.bits() > max_bits {
+            return None;
+        }
+        Some(res)
     }
 
-    pub fn trailing_zeros(&self) -> Option<usize> {
-        trailing_zeros(self)
+    /// Returns `self << bits`, or `None` if the result would need more than
+    /// `max_bits` bits.
+    ///
+    /// The size is checked before anything is allocated, so a shift like
+    /// `x << usize::MAX` is rejected cheaply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let x = BigUint::from(5u32);
+    /// assert_eq!(x.checked_shl(5, 8), Some(BigUint::from(160u32)));
+    /// assert_eq!(x.checked_shl(6, 8), None);
+    /// assert_eq!(x.checked_shl(usize::MAX, 1 << 20), None);
+    /// ```
+    pub fn checked_shl(&self, bits: usize, max_bits: usize) -> Option<Self> {
+        if self.is_zero() {
+            return Some(BigUint::zero());
+        }
+        if self.bits().checked_add(bits)? > max_bits {
+            return None;
+        }
+        Some(self << bits)
     }
 
-    /// Sets the value to the provided digit, reusing internal storage.
-    pub fn set_digit(&mut self, digit: BigDigit) {
-        if self.is_zero() {
-            self.data.resize(1, digit);
-        } else {
-            self.data.resize(1, 0);
-            self.data[0] = digit;
+    /// Returns `self >> bits`, or `None` if the result would need more than
+    /// `max_bits` bits.
+    pub fn checked_shr(&self, bits: usize, max_bits: usize) -> Option<Self> {
+        if self.bits().saturating_sub(bits) > max_bits {
+            return None;
         }
+        Some(self >> bits)
+    }
+
+    /// Returns `k` if `self` is exactly `base ^ k`, or `None` otherwise.
+    ///
+    /// One is `base ^ 0` for every base, and zero is only a power of zero.
+    /// The exponent is estimated from the logarithms and the bit lengths,
+    /// so at most one power is computed to confirm it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let base = BigUint::from(10u32);
+    /// let n = BigUint::parse_bytes(b"1000000000000000000000000000000", 10).unwrap();
+    /// assert_eq!(n.is_power_of(&base), Some(30));
+    /// assert_eq!((n + 1u32).is_power_of(&base), None);
+    /// assert_eq!(BigUint::from(1u32).is_power_of(&base), Some(0));
+    /// ```
+    pub fn is_power_of(&self, base: &BigUint) -> Option<u64> {
+        if self.is_one() {
+            return Some(0);
+        }
+        if base.is_zero() || base.is_one() || self.is_zero() {
+            return Some(1).filter(|_| self == base);
+        }
+
+        // `base ^ k` has between `k * (b - 1) + 1` and `k * b` bits, where `b`
+        // is the bit length of `base`.
+        let (bits, b) = (self.bits() as u64, base.bits() as u64);
+        let min = (bits + b - 1) / b;
+        let max = (bits - 1) / (b - 1);
+        let k = (self.log2_f64() / base.log2_f64()).round() as u64;
+        if k < min || k > max {
+            return None;
+        }
+
+        Some(k).filter(|&k| Pow::pow(base, k) == *self)
+    }
+
+    /// Divides out every factor of `p` from `self`, returning how many there
+    /// were: the `p`-adic valuation of the original value.
+    ///
+    /// Like GMP's `mpz_remove`, this divides by `p`, `p^2`, `p^4`, ... while
+    /// they divide, then by the same powers in decreasing order, so it takes
+    /// `O(log v)` divisions for a valuation of `v`. Powers of two are removed
+    /// with a shift. Zero is left unchanged and reported as having no
+    /// factors.
+    ///
+    /// Panics if `p` is less than two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    /// use num_traits::Pow;
+    ///
+    /// let mut n = BigUint::from(3u32).pow(40u32) * 10u32;
+    /// assert_eq!(n.remove_factor(&BigUint::from(3u32)), 40);
+    /// assert_eq!(n, BigUint::from(10u32));
+    /// assert_eq!(n.remove_factor(&BigUint::from(4u32)), 0);
+    /// ```
+    pub fn remove_factor(&mut self, p: &BigUint) -> u64 {
+        assert!(p.bits() > 1, "the factor must be at least two");
+        if self.is_zero() {
+            return 0;
+        }
+
+        if p.is_power_of_two() {
+            let s = (p.bits() - 1) as u64;
+            let v = self.trailing_zeros().unwrap() as u64 / s;
+            *self >>= (v * s) as usize;
+            return v;
+        }
+
+        let mut powers = Vec::new();
+        let mut count = 0;
+        let mut q = BigUint::zero();
+        let mut r = BigUint::zero();
+        let mut pw = p.clone();
+        loop {
+            // A power longer than `self` cannot divide it.
+            if pw.bits() > self.bits() {
+                break;
+            }
+            q.clone_from(self);
+            q.div_rem_assign(&pw, &mut r);
+            if !r.is_zero() {
+                break;
+            }
+            mem::swap(self, &mut q);
+            count += 1 << powers.len();
+            let next = &pw * &pw;
+            powers.push(mem::replace(&mut pw, next));
+        }
+
+        // What is left has a valuation below 2^powers.len(), so one pass
+        // down the powers finds its binary digits.
+        for (i, pw) in powers.iter().enumerate().rev() {
+            if pw.bits() > self.bits() {
+                continue;
+            }
+            q.clone_from(self);
+            q.div_rem_assign(pw, &mut r);
+            if r.is_zero() {
+                mem::swap(self, &mut q);
+                count += 1 << i;
+            }
+        }
+        count
+    }
+
+    /// Returns the truncated principal square root of `self` --
+    /// see [Roots::sqrt](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#method.sqrt)
+    pub fn sqrt(&self) -> Self {
+        Roots::sqrt(self)
+    }
+
+    /// Returns the truncated principal square root of `self` together with
+    /// the remainder `self - root^2`.
+    ///
+    /// Both come out of the same recursive computation, so the root does
+    /// not need to be squared again, e.g. to test for a perfect square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(1_000_000_007u64);
+    /// let (root, rem) = n.sqrt_rem();
+    /// assert_eq!(root, BigUint::from(31622u32));
+    /// assert_eq!(rem, BigUint::from(49123u32));
+    /// ```
+    pub fn sqrt_rem(&self) -> (Self, Self) {
+        if self.is_zero() {
+            return (Self::zero(), Self::zero());
+        }
+        sqrt_rem_normalized(self, (self.bits() + 1) / 2)
+    }
+
+    /// Returns the truncated principal cube root of `self` --
+    /// see [Roots::cbrt](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#method.cbrt).
+    pub fn cbrt(&self) -> Self {
+        Roots::cbrt(self)
+    }
+
+    /// Returns the truncated principal `n`th root of `self` --
+    /// see [Roots::nth_root](https://docs.rs/num-integer/0.1/num_integer/trait.Roots.html#tymethod.nth_root).
+    pub fn nth_root(&self, n: u32) -> Self {
+        Roots::nth_root(self, n)
+    }
+
+    /// Returns the multinomial coefficient `(n_1 + ... + n_k)! / (n_1! ... n_k!)`
+    /// for the parts `ns`, or one if there are none.
+    ///
+    /// This is the product of the binomials `C(n_1 + ... + n_i, n_i)`, each
+    /// built up one exact step at a time, so no factorial is ever formed.
+    /// The largest part is taken first, where its binomial is just one.
+    ///
+    /// Panics if the parts sum to more than `u64::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// // The arrangements of the letters of "MISSISSIPPI".
+    /// assert_eq!(BigUint::multinomial(&[1, 4, 4, 2]), BigUint::from(34650u32));
+    /// ```
+    pub fn multinomial(ns: &[u64]) -> Self {
+        let mut ns = ns.to_vec();
+        ns.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut total = 0u64;
+        let mut binomials = Vec::with_capacity(ns.len());
+        for &k in &ns {
+            total = total
+                .checked_add(k)
+                .expect("multinomial parts sum to more than u64::MAX");
+            // C(total, k) = C(total, total - k), and the loop is shorter for
+            // the smaller one.
+            let k = cmp::min(k, total - k);
+            let mut c = BigUint::one();
+            for j in 1..=k {
+                c *= total - k + j;
+                c /= j;
+            }
+            binomials.push(c);
+        }
+        product_of(&binomials)
+    }
+
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        trailing_zeros(self)
+    }
+
+    /// Returns the non-adjacent form of `self`, least significant digit first.
+    ///
+    /// This is [`to_wnaf`](#method.to_wnaf) with a window of 2: every digit is -1, 0 or 1,
+    /// no two adjacent digits are non-zero, and `self` is the sum of `digit * 2^i`.
+    pub fn to_naf(&self) -> Vec<i8> {
+        self.to_wnaf(2)
+    }
+
+    /// Returns the width-`w` non-adjacent form of `self`, least significant digit first.
+    ///
+    /// Every non-zero digit is odd and less than `2^(w - 1)` in absolute value, any `w`
+    /// consecutive digits contain at most one non-zero digit, and `self` is the sum of
+    /// `digit * 2^i`. The representation is at most one digit longer than the binary one,
+    /// and zero has no digits.
+    ///
+    /// Panics if `w` is not between 2 and 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(7u32);
+    /// assert_eq!(n.to_naf(), vec![-1, 0, 0, 1]);
+    /// assert_eq!(n.to_wnaf(3), vec![-1, 0, 0, 1]);
+    /// assert_eq!(n.to_wnaf(4), vec![7]);
+    /// ```
+    pub fn to_wnaf(&self, w: usize) -> Vec<i8> {
+        assert!((2..=8).contains(&w), "w must be between 2 and 8");
+
+        let width = 1u32 << w;
+        let bits = self.bits();
+        let mut naf = Vec::with_capacity(bits + 1);
+        let mut pos = 0;
+        let mut carry = 0;
+        while pos < bits || carry != 0 {
+            // The next w bits, which may straddle two digits.
+            let (i, shift) = (pos / big_digit::BITS, pos % big_digit::BITS);
+            let mut buf = self.data.get(i).map_or(0, |&d| d >> shift);
+            if shift + w > big_digit::BITS {
+                buf |= self
+                    .data
+                    .get(i + 1)
+                    .map_or(0, |&d| d << (big_digit::BITS - shift));
+            }
+
+            let window = carry + (buf as u32 & (width - 1));
+            if window & 1 == 0 {
+                pos += 1;
+                continue;
+            }
+
+            naf.resize(pos + 1, 0);
+            if window < width / 2 {
+                carry = 0;
+                naf[pos] = window as i8;
+            } else {
+                carry = 1;
+                naf[pos] = (window as i16 - width as i16) as i8;
+            }
+            pos += w;
+        }
+        naf
+    }
+
+    /// Returns the smallest prime factor of `self` that is less than `bound`, or `None` if
+    /// there is none.
+    ///
+    /// Candidates are taken from a mod-30 wheel, so only 8 in every 30 integers are tried,
+    /// and several are tested at once with a single-digit remainder by their product. The
+    /// search stops early past the square root of `self`, so a prime `self` never reports
+    /// itself as a factor, and `None` with `bound * bound > self` proves `self` prime.
+    ///
+    /// Returns `None` for zero and one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(1009u32) * 1013u32;
+    /// assert_eq!(n.trial_divide(1000), None);
+    /// assert_eq!(n.trial_divide(1010), Some(1009));
+    /// assert_eq!(BigUint::from(1009u32).trial_divide(1 << 20), None);
+    /// ```
+    pub fn trial_divide(&self, bound: u64) -> Option<u64> {
+        const WHEEL: [u64; 8] = [1, 7, 11, 13, 17, 19, 23, 29];
+
+        if self.data.len() <= 1 && self.data.first().map_or(true, |&d| d < 2) {
+            return None;
+        }
+        let small = self.to_u64();
+        let mut batch = TrialBatch {
+            n: self,
+            divisors: SmallVec::new(),
+            product: 1,
+        };
+
+        let wheel = (0u64..).flat_map(|k| WHEEL.iter().map(move |&w| 30 * k + w));
+        for d in [2, 3, 5].iter().copied().chain(wheel.skip(1)) {
+            if d >= bound || small.map_or(false, |n| d.saturating_mul(d) > n) {
+                break;
+            }
+            if let Some(f) = batch.push(d) {
+                return Some(f);
+            }
+        }
+        batch.flush()
+    }
+
+    /// Sets the value to the provided digit, reusing internal storage.
+    pub fn set_digit(&mut self, digit: BigDigit) {
+        if self.is_zero() {
+            self.data.resize(1, digit);
+        } else {
+            self.data.resize(1, 0);
+            self.data[0] = digit;
+        }
+    }
+
+    /// Returns the midpoint `(self + other) / 2`, rounded down.
+    ///
+    /// This is computed as `(self & other) + ((self ^ other) >> 1)`, so the
+    /// full sum is never materialized.
+    pub fn midpoint(&self, other: &Self) -> Self {
+        (self & other) + ((self ^ other) >> 1)
+    }
+
+    /// Adds `other` to `self` in place, growing the existing storage only if
+    /// the sum needs more digits.
+    #[inline]
+    pub fn add_assign_ref(&mut self, other: &Self) {
+        *self += other;
+    }
+
+    /// Sets `self` to `self + a * b`, accumulating the product in place.
+    ///
+    /// `self` is grown as needed, and no temporary is allocated for the
+    /// product. This is the inner step of Horner evaluation, CRT
+    /// recombination and matrix products.
+    pub fn add_mul_assign(&mut self, a: &Self, b: &Self) {
+        if a.is_zero() || b.is_zero() {
+            return;
+        }
+
+        let len = cmp::max(self.data.len(), a.data.len() + b.data.len()) + 1;
+        self.data.resize(len, 0);
+        mac3(&mut self.data, &a.data, &b.data);
+        self.normalize();
+    }
+
+    /// Sets `self` to `a * b`, writing the product into the storage `self`
+    /// already owns instead of allocating a new value.
+    pub fn mul_assign_reuse(&mut self, a: &Self, b: &Self) {
+        self.data.clear();
+        if a.is_zero() || b.is_zero() {
+            return;
+        }
+
+        self.data.resize(a.data.len() + b.data.len() + 1, 0);
+        mac3(&mut self.data, &a.data, &b.data);
+        self.normalize();
+    }
+
+    /// Divides `self` by `d` in place, leaving the quotient in `self` and
+    /// writing the remainder into `rem`.
+    ///
+    /// Single digit divisors, and divisors larger than `self`, reuse the
+    /// storage of both `self` and `rem`.
+    ///
+    /// Panics if `d` is zero.
+    pub fn div_rem_assign(&mut self, d: &Self, rem: &mut Self) {
+        assert!(!d.is_zero(), "attempt to divide by zero");
+
+        if d.data.len() == 1 {
+            let mut r = 0;
+            for x in self.data.iter_mut().rev() {
+                let (q, rr) = div_wide(r, *x, d.data[0]);
+                *x = q;
+                r = rr;
+            }
+            self.normalize();
+            rem.data.clear();
+            if r != 0 {
+                rem.data.push(r);
+            }
+        } else if *self < *d {
+            mem::swap(self, rem);
+            self.data.clear();
+        } else {
+            let (q, r) = div_rem(self, d);
+            *self = q;
+            *rem = r;
+        }
+    }
+
+    /// Returns `(self / d, self % d)`, computed with the given division
+    /// algorithm.
+    ///
+    /// All strategies return the same result; `DivStrategy::Auto` is what
+    /// `div_rem` uses.
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigUint, DivStrategy};
+    ///
+    /// let n = BigUint::from(1u32) << 200;
+    /// let d = BigUint::from(1_000_003u32);
+    /// let expected = (&n / &d, &n % &d);
+    /// assert_eq!(n.div_rem_with_strategy(&d, DivStrategy::Montgomery), expected);
+    /// assert_eq!(n.div_rem_with_strategy(&d, DivStrategy::Binary), expected);
+    /// ```
+    pub fn div_rem_with_strategy(&self, d: &Self, strategy: DivStrategy) -> (Self, Self) {
+        assert!(!d.is_zero(), "attempt to divide by zero");
+        match strategy {
+            DivStrategy::Auto => div_rem(self, d),
+            // The other strategies assume a nonzero quotient.
+            _ if *self < *d => (BigUint::zero(), self.clone()),
+            DivStrategy::Knuth => div_rem_knuth(self, d),
+            DivStrategy::Montgomery => monty_div_rem(self, d),
+            DivStrategy::Binary => div_rem_binary(self, d),
+        }
+    }
+
+    /// Returns `(self * b / c, self * b % c)`.
+    ///
+    /// The product is divided in place, so it is the only intermediate
+    /// value allocated. A nonzero remainder means the quotient was
+    /// truncated.
+    ///
+    /// Panics if `c` is zero.
+    pub fn mul_div_rem(&self, b: &Self, c: &Self) -> (Self, Self) {
+        assert!(!c.is_zero(), "attempt to divide by zero");
+        let mut q = BigUint::zero();
+        q.mul_assign_reuse(self, b);
+        let mut r = BigUint::zero();
+        q.div_rem_assign(c, &mut r);
+        (q, r)
+    }
+
+    /// Returns `self * b / c`, rounded down.
+    ///
+    /// Panics if `c` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// // A third of 1000 units, split by a 7 / 21 share.
+    /// let total = BigUint::from(1000u32);
+    /// let (share, shares) = (BigUint::from(7u32), BigUint::from(21u32));
+    /// assert_eq!(total.mul_div_floor(&share, &shares), BigUint::from(333u32));
+    /// assert_eq!(total.mul_div_exact(&share, &shares), None);
+    /// assert_eq!(total.mul_div_exact(&shares, &share), Some(BigUint::from(3000u32)));
+    /// ```
+    pub fn mul_div_floor(&self, b: &Self, c: &Self) -> Self {
+        self.mul_div_rem(b, c).0
+    }
+
+    /// Returns `self * b / c` if `c` divides the product exactly, or `None`
+    /// if the result would be truncated.
+    ///
+    /// Panics if `c` is zero.
+    pub fn mul_div_exact(&self, b: &Self, c: &Self) -> Option<Self> {
+        let (q, r) = self.mul_div_rem(b, c);
+        if r.is_zero() {
+            Some(q)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `(self * b) >> k`, the product divided by `2^k` and rounded
+    /// down.
+    ///
+    /// For operands short enough to use long multiplication, the partial
+    /// products that only affect the discarded low bits are skipped. The
+    /// result is still exact: if the skipped part could carry into the kept
+    /// bits, the full product is computed instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// // Fixed point multiplication with 128 fractional bits.
+    /// let one_and_half = BigUint::from(3u32) << 127;
+    /// let x = BigUint::from(10u32) << 128;
+    /// assert_eq!(x.mul_shr(&one_and_half, 128), BigUint::from(15u32) << 128);
+    /// ```
+    pub fn mul_shr(&self, b: &Self, k: usize) -> Self {
+        // Skip all but two digits below the cut, so that the columns left
+        // out are far too small to reach it except in rare cases.
+        let d = k / big_digit::BITS;
+        let short = cmp::min(self.data.len(), b.data.len());
+        if d < 3 || short > 32 {
+            return (self * b) >> k;
+        }
+
+        let t = d - 2;
+        let hi = mul_hi(&self.data, &b.data, t);
+        let shift = k - t * big_digit::BITS;
+        let slack = BigUint::from(t) << big_digit::BITS;
+        let q = &hi >> shift;
+        if (hi + slack) >> shift == q {
+            q
+        } else {
+            (self * b) >> k
+        }
+    }
+
+    /// Returns the low `n` bits of `self * other`, that is
+    /// `(self * other) mod 2^n`.
+    ///
+    /// For operands short enough to use long multiplication, only the
+    /// partial products that reach the low `n` bits are computed, which is
+    /// about half the work of the full product when `n` is half its length.
+    /// The result is exact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let x = BigUint::from(0xffff_ffffu32);
+    /// assert_eq!(x.mul_low_bits(&x, 32), BigUint::from(1u32));
+    /// assert_eq!(x.mul_low_bits(&x, 128), &x * &x);
+    /// ```
+    pub fn mul_low_bits(&self, other: &Self, n: usize) -> Self {
+        let t = idiv_ceil(n, big_digit::BITS);
+        let short = cmp::min(cmp::min(self.data.len(), other.data.len()), t);
+        let prod = if short > 32 {
+            self.truncate_to_bits(n) * other.truncate_to_bits(n)
+        } else {
+            mul_lo(&self.data, &other.data, t)
+        };
+        prod.truncate_to_bits(n)
+    }
+
+    /// Returns an approximation of `(self * other) >> n` from the high part
+    /// of the product only.
+    ///
+    /// For operands short enough to use long multiplication, the partial
+    /// products lying entirely below bit `n`, except for one guard digit,
+    /// are skipped. The result is never larger than the exact value, and
+    /// is smaller by at most `n / 32`. Use [`mul_shr`](#method.mul_shr) for
+    /// the exact value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let x = (BigUint::from(1u32) << 1000) - 1u32;
+    /// let exact = (&x * &x) >> 1000;
+    /// let approx = x.mul_high_bits(&x, 1000);
+    /// assert!(approx <= exact && &exact - &approx <= BigUint::from(1000u32 / 32));
+    /// ```
+    pub fn mul_high_bits(&self, other: &Self, n: usize) -> Self {
+        // Dropping the columns below digit t leaves out less than t * B^(t+1),
+        // which is less than t * 2^n with the guard digit.
+        let t = (n / big_digit::BITS).saturating_sub(1);
+        let short = cmp::min(self.data.len(), other.data.len());
+        if t == 0 || short > 32 {
+            return (self * other) >> n;
+        }
+
+        mul_hi(&self.data, &other.data, t) >> (n - t * big_digit::BITS)
+    }
+
+    /// Returns the quotient and remainder of `self / d`.
+    ///
+    /// Whenever `d` fits in a single digit this is a single pass over the
+    /// digits of `self`, and `d` is never promoted to a `BigUint`.
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(1_000_000_007u64) * 1_000_000_009u64 + 5u32;
+    /// let (q, r) = n.div_rem_u64(1_000_000_009);
+    /// assert_eq!(q, BigUint::from(1_000_000_007u64));
+    /// assert_eq!(r, 5);
+    /// ```
+    #[cfg(feature = "u64_digit")]
+    pub fn div_rem_u64(&self, d: u64) -> (BigUint, u64) {
+        assert!(d != 0, "attempt to divide by zero");
+        div_rem_digit(self.clone(), d)
+    }
+    #[cfg(not(feature = "u64_digit"))]
+    pub fn div_rem_u64(&self, d: u64) -> (BigUint, u64) {
+        assert!(d != 0, "attempt to divide by zero");
+
+        if d <= BigDigit::MAX as u64 {
+            let (q, r) = div_rem_digit(self.clone(), d as BigDigit);
+            (q, r as u64)
+        } else {
+            let (q, r) = div_rem(self, &BigUint::from(d));
+            (q, r.to_u64().unwrap())
+        }
+    }
+
+    /// Returns the remainder of `self / d`, without computing the quotient.
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::parse_bytes(b"1000000000000000000000000000007", 10).unwrap();
+    /// assert_eq!(n.rem_u64(10), 7);
+    /// ```
+    #[cfg(feature = "u64_digit")]
+    pub fn rem_u64(&self, d: u64) -> u64 {
+        assert!(d != 0, "attempt to divide by zero");
+        rem_digit(&self.data, d)
+    }
+    #[cfg(not(feature = "u64_digit"))]
+    pub fn rem_u64(&self, d: u64) -> u64 {
+        assert!(d != 0, "attempt to divide by zero");
+
+        if d <= BigDigit::MAX as u64 {
+            rem_digit(&self.data, d as BigDigit) as u64
+        } else {
+            (self % d).to_u64().unwrap()
+        }
+    }
+
+    /// Returns `true` if `self` is a multiple of `d`. Zero is only a multiple of
+    /// itself.
+    ///
+    /// No quotient is computed. Single digit divisors are checked with
+    /// [`divisible_by_u64`](#method.divisible_by_u64), and larger ones are
+    /// rejected early when `self` is smaller or has fewer trailing zeros.
+    pub fn is_multiple_of(&self, d: &BigUint) -> bool {
+        if d.is_zero() || self.is_zero() {
+            return self.is_zero();
+        }
+        if d.data.len() == 1 {
+            return divisible_by_digit(&self.data, d.data[0]);
+        }
+        if *self < *d || self.trailing_zeros() < d.trailing_zeros() {
+            return false;
+        }
+        (self % d).is_zero()
+    }
+
+    /// Returns `true` if `self` is a multiple of `d`. Zero is only a multiple of
+    /// itself.
+    ///
+    /// Divisors that divide `BigDigit::MAX`, such as 3, 5 and 17, are checked
+    /// with a sum of the digits. Other odd divisors use an exact division
+    /// by the inverse of `d`, so neither needs a hardware division per digit.
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(3u32 * 7 * 64) * u64::MAX;
+    /// assert!(n.divisible_by_u64(3 * 7 * 64));
+    /// assert!(n.divisible_by_u64(u64::MAX));
+    /// assert!(!n.divisible_by_u64(128));
+    /// assert!(!n.divisible_by_u64(11));
+    /// ```
+    #[cfg(feature = "u64_digit")]
+    pub fn divisible_by_u64(&self, d: u64) -> bool {
+        if d == 0 {
+            return self.is_zero();
+        }
+        divisible_by_digit(&self.data, d)
+    }
+    #[cfg(not(feature = "u64_digit"))]
+    pub fn divisible_by_u64(&self, d: u64) -> bool {
+        if d == 0 {
+            return self.is_zero();
+        }
+        if d <= BigDigit::MAX as u64 {
+            divisible_by_digit(&self.data, d as BigDigit)
+        } else {
+            self.is_multiple_of(&BigUint::from(d))
+        }
+    }
+
+    /// Sets `self` to `self * other`, using `ws` for the product.
+    pub fn mul_with_workspace(&mut self, other: &Self, ws: &mut Workspace) {
+        ws.mul(self, other);
+    }
+
+    /// Divides `self` by `d` in place like [`div_rem_assign`], keeping the
+    /// intermediates of long division in `ws`.
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// [`div_rem_assign`]: #method.div_rem_assign
+    pub fn div_rem_with_workspace(&mut self, d: &Self, rem: &mut Self, ws: &mut Workspace) {
+        ws.div_rem(self, d, rem);
+    }
+
+    /// Sets `self` to `(self * other) % modulus`, using `ws` for the product
+    /// and the reduction.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn mul_mod_with_workspace(&mut self, other: &Self, modulus: &Self, ws: &mut Workspace) {
+        ws.mul_mod(self, other, modulus);
+    }
+
+    /// Returns `(self ^ exponent) % modulus`, doing every multiplication and
+    /// reduction in `ws`.
+    ///
+    /// This always uses plain square-and-multiply, so for a single large
+    /// exponentiation with an odd modulus [`modpow`] is usually faster. It
+    /// pays off when many small exponentiations are done back to back.
+    ///
+    /// Panics if `modulus` is zero.
+    ///
+    /// [`modpow`]: #method.modpow
+    pub fn modpow_with_workspace(
+        &self,
+        exponent: &Self,
+        modulus: &Self,
+        ws: &mut Workspace,
+    ) -> Self {
+        assert!(!modulus.is_zero(), "divide by zero!");
+
+        let mut base = self.clone();
+        ws.reduce(&mut base, modulus);
+
+        let mut acc = BigUint::one();
+        ws.reduce(&mut acc, modulus);
+        for i in (0..exponent.bits()).rev() {
+            ws.sqr_mod(&mut acc, modulus);
+            if (exponent.data[i / big_digit::BITS] >> (i % big_digit::BITS)) & 1 == 1 {
+                ws.mul_mod(&mut acc, &base, modulus);
+            }
+        }
+        acc
+    }
+
+    /// Returns `self * other`, or an error once `budget` runs out.
+    ///
+    /// The product is split into blocks of bounded size, and the budget is
+    /// charged before each block, so it is checked throughout even when
+    /// both operands are large.
+    pub fn mul_with_budget(
+        &self,
+        other: &Self,
+        budget: &mut Budget,
+    ) -> Result<Self, BudgetExceeded> {
+        if self.is_zero() || other.is_zero() {
+            return Ok(BigUint::zero());
+        }
+
+        let mut prod = BigUint {
+            data: smallvec![0; self.data.len() + other.data.len() + 1],
+        };
+        mac3_with_budget(&mut prod.data, &self.data, &other.data, budget)?;
+        Ok(prod.normalized())
+    }
+
+    /// Returns `(self / d, self % d)`, or an error once `budget` runs out.
+    ///
+    /// The budget is charged before each digit of the quotient.
+    ///
+    /// Panics if `d` is zero.
+    pub fn div_rem_with_budget(
+        &self,
+        d: &Self,
+        budget: &mut Budget,
+    ) -> Result<(Self, Self), BudgetExceeded> {
+        assert!(!d.is_zero(), "attempt to divide by zero");
+        if d.data.len() == 1 || *self <= *d {
+            budget.charge(self.data.len() as u64)?;
+            return Ok(div_rem(self, d));
+        }
+
+        // Normalize as `div_rem` does, so the top bit of the divisor is set.
+        let shift = d.data.last().unwrap().leading_zeros() as usize;
+        let mut a = self << shift;
+        let b = d << shift;
+        let mut q = BigUint::zero();
+        let (mut a0, mut prod) = (BigUint::zero(), BigUint::zero());
+        div_rem_core_with_budget(&mut a, &b, &mut q, &mut a0, &mut prod, budget)?;
+        Ok((q, a >> shift))
+    }
+
+    /// Returns the integer formatted in the given radix like
+    /// [`to_str_radix`], or an error once `budget` runs out.
+    ///
+    /// Radixes that aren't powers of two take time quadratic in the length,
+    /// and the budget is charged before each division by the radix.
+    ///
+    /// [`to_str_radix`]: #method.to_str_radix
+    pub fn to_str_radix_with_budget(
+        &self,
+        radix: u32,
+        budget: &mut Budget,
+    ) -> Result<String, BudgetExceeded> {
+        assert!((2..=36).contains(&radix), "The radix must be within 2...36");
+        if self.is_zero() || radix.is_power_of_two() {
+            budget.charge(self.data.len() as u64)?;
+            return Ok(self.to_str_radix(radix));
+        }
+
+        let mut v = to_radix_digits_le_with_budget(self, radix, budget)?;
+        digits_to_ascii(&mut v, radix);
+        v.reverse();
+        Ok(unsafe { String::from_utf8_unchecked(v) })
+    }
+
+    /// Returns `(self ^ exponent) % modulus`, or an error once `budget`
+    /// runs out.
+    ///
+    /// The budget is charged before each window of the exponent with an odd
+    /// modulus, and before each bit of it otherwise.
+    ///
+    /// Panics if the modulus is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigUint, Budget};
+    ///
+    /// let m = (BigUint::from(1u32) << 4096) - 1u32;
+    /// let e = m.clone();
+    /// let b = BigUint::from(3u32);
+    ///
+    /// let mut budget = Budget::unlimited().with_work_limit(100_000);
+    /// assert!(b.modpow_with_budget(&e, &m, &mut budget).is_err());
+    ///
+    /// let e = BigUint::from(65537u32);
+    /// let mut budget = Budget::unlimited().with_work_limit(1_000_000);
+    /// assert_eq!(b.modpow_with_budget(&e, &m, &mut budget), Ok(b.modpow(&e, &m)));
+    /// ```
+    pub fn modpow_with_budget(
+        &self,
+        exponent: &Self,
+        modulus: &Self,
+        budget: &mut Budget,
+    ) -> Result<Self, BudgetExceeded> {
+        assert!(!modulus.is_zero(), "divide by zero!");
+
+        let n = modulus.data.len();
+        budget.charge_mul(self.data.len(), n)?;
+        if modulus.is_odd() {
+            let params = ModulusParams::new(modulus);
+            let options = ModPowOptions::default();
+            return monty_modpow_with_budget(self, exponent, &params, &options, budget);
+        }
+
+        let base = self % modulus;
+        let mut acc = BigUint::one() % modulus;
+        for i in (0..exponent.bits()).rev() {
+            // A squaring, a multiplication and their reductions.
+            budget.charge_mul(4 * n, n)?;
+            acc = &acc * &acc % modulus;
+            if (exponent.data[i / big_digit::BITS] >> (i % big_digit::BITS)) & 1 == 1 {
+                acc = acc * &base % modulus;
+            }
+        }
+        Ok(acc)
+    }
+
+    /// Returns `(self ^ exponent) % m`, where `params` holds the Montgomery
+    /// parameters of the odd modulus `m`.
+    ///
+    /// Gives the same result as [`modpow`], but skips recomputing
+    /// `-m^-1 mod 2^BITS` and `R^2 mod m` on every call, which matters when
+    /// many exponentiations share one modulus.
+    ///
+    /// [`modpow`]: #method.modpow
+    #[inline]
+    pub fn modpow_with_params(&self, exponent: &Self, params: &ModulusParams) -> Self {
+        monty_modpow_with_params(self, exponent, params)
+    }
+
+    /// Returns `(base ^ exponent) % modulus` for each of `bases`.
+    ///
+    /// For an odd modulus the Montgomery parameters are computed once and
+    /// shared by every exponentiation, as with
+    /// [`modpow_with_params`](#method.modpow_with_params). Each base still
+    /// needs its own squarings, since they are squarings of different values.
+    ///
+    /// Panics if the modulus is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let m = BigUint::from(1_000_000_007u32);
+    /// let e = BigUint::from(65537u32);
+    /// let bases: Vec<BigUint> = (2u32..6).map(BigUint::from).collect();
+    /// let powers = BigUint::modpow_batch(&bases, &e, &m);
+    /// for (b, p) in bases.iter().zip(&powers) {
+    ///     assert_eq!(*p, b.modpow(&e, &m));
+    /// }
+    /// ```
+    pub fn modpow_batch(bases: &[BigUint], exponent: &Self, modulus: &Self) -> Vec<BigUint> {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        if modulus.is_odd() && !bases.is_empty() {
+            let params = ModulusParams::new(modulus);
+            bases
+                .iter()
+                .map(|b| monty_modpow_with_params(b, exponent, &params))
+                .collect()
+        } else {
+            bases.iter().map(|b| b.modpow(exponent, modulus)).collect()
+        }
+    }
+
+    /// Returns `(self ^ exponent) % modulus`, with the algorithm tuned by
+    /// `options`.
+    ///
+    /// Gives the same result as [`modpow`] for any choice of options.
+    ///
+    /// Panics if the modulus is zero, or if `options.window_bits` is not
+    /// between 1 and 8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::{BigUint, ModPowOptions};
+    ///
+    /// let options = ModPowOptions {
+    ///     window_bits: 6,
+    ///     ..ModPowOptions::default()
+    /// };
+    /// let b = BigUint::from(3u32);
+    /// let e = BigUint::from(1000u32);
+    /// let m = BigUint::from(1_000_000_007u32);
+    /// assert_eq!(b.modpow_with_options(&e, &m, &options), b.modpow(&e, &m));
+    /// ```
+    ///
+    /// [`modpow`]: #method.modpow
+    pub fn modpow_with_options(
+        &self,
+        exponent: &Self,
+        modulus: &Self,
+        options: &ModPowOptions,
+    ) -> Self {
+        assert!(!modulus.is_zero(), "divide by zero!");
+
+        if modulus.is_odd() {
+            let params = ModulusParams::new(modulus);
+            return monty_modpow_with_options(self, exponent, &params, options);
+        }
+        if !options.use_crt_for_even_modulus {
+            return self.modpow(exponent, modulus);
+        }
+
+        // modulus = 2^k * q, with q odd
+        let k = modulus.trailing_zeros().unwrap();
+        let low = modpow_pow2(self, exponent, k);
+        let q = modulus >> k;
+        if q.is_one() {
+            return low;
+        }
+
+        // Garner: r = low + 2^k * ((high - low) * 2^-k mod q)
+        let params = ModulusParams::new(&q);
+        let high = monty_modpow_with_options(self, exponent, &params, options);
+        let pow2 = BigUint::one() << k;
+        let inv = (&pow2 % &q)
+            .mod_inverse(&q)
+            .unwrap()
+            .into_biguint()
+            .unwrap();
+        let h = high.sub_mod(&low, &q).mul_mod(&inv, &q);
+        low + (h << k)
+    }
+
+    /// Returns `self ^ d mod (p * q)`, computed with the Chinese remainder
+    /// theorem as in RSA decryption.
+    ///
+    /// `dp` and `dq` are `d mod (p - 1)` and `d mod (q - 1)`, and `qinv` is
+    /// `q^-1 mod p`. The two half-size exponentiations are combined with
+    /// Garner's formula:
+    ///
+    /// ```text
+    /// m1 = self ^ dp mod p
+    /// m2 = self ^ dq mod q
+    /// h  = qinv * (m1 - m2) mod p
+    /// m  = m2 + h * q
+    /// ```
+    ///
+    /// Panics if `p` or `q` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let (p, q) = (BigUint::from(61u32), BigUint::from(53u32));
+    /// let d = BigUint::from(2753u32);
+    /// let c = BigUint::from(2790u32);
+    ///
+    /// let dp = &d % (&p - 1u32);
+    /// let dq = &d % (&q - 1u32);
+    /// let qinv = BigUint::from(38u32); // 53 * 38 = 1 (mod 61)
+    ///
+    /// assert_eq!(c.modpow_crt(&dp, &dq, &p, &q, &qinv), BigUint::from(65u32));
+    /// ```
+    pub fn modpow_crt(&self, dp: &Self, dq: &Self, p: &Self, q: &Self, qinv: &Self) -> Self {
+        let m1 = self.modpow(dp, p);
+        let m2 = self.modpow(dq, q);
+
+        // m1 - m2 mod p, kept non-negative.
+        let m2p = &m2 % p;
+        let diff = if m1 >= m2p { m1 - m2p } else { m1 + p - m2p };
+        let h = diff * qinv % p;
+
+        m2 + h * q
+    }
+
+    /// Returns `(self + other) % modulus`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let mut x = self.clone();
+        x.add_mod_assign(other, modulus);
+        x
+    }
+
+    /// Sets `self` to `(self + other) % modulus`.
+    ///
+    /// When both operands are already less than `modulus`, this is a single
+    /// addition and at most one subtraction, with no division.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn add_mod_assign(&mut self, other: &Self, modulus: &Self) {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        if *other >= *modulus {
+            return self.add_mod_assign(&(other % modulus), modulus);
+        }
+        if *self >= *modulus {
+            *self %= modulus;
+        }
+
+        *self += other;
+        if *self >= *modulus {
+            *self -= modulus;
+        }
+    }
+
+    /// Returns `(self - other) mod modulus`, in `[0, modulus)`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let mut x = self.clone();
+        x.sub_mod_assign(other, modulus);
+        x
+    }
+
+    /// Sets `self` to `(self - other) mod modulus`, in `[0, modulus)`.
+    ///
+    /// When both operands are already less than `modulus`, this is at most
+    /// one addition and one subtraction, with no division.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn sub_mod_assign(&mut self, other: &Self, modulus: &Self) {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        if *other >= *modulus {
+            return self.sub_mod_assign(&(other % modulus), modulus);
+        }
+        if *self >= *modulus {
+            *self %= modulus;
+        }
+
+        if *self < *other {
+            *self += modulus;
+        }
+        *self -= other;
+    }
+
+    /// Returns `-self mod modulus`, in `[0, modulus)`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn neg_mod(&self, modulus: &Self) -> Self {
+        let mut x = self.clone();
+        x.neg_mod_assign(modulus);
+        x
+    }
+
+    /// Sets `self` to `-self mod modulus`, in `[0, modulus)`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn neg_mod_assign(&mut self, modulus: &Self) {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        if *self >= *modulus {
+            *self %= modulus;
+        }
+        if self.is_zero() {
+            return;
+        }
+
+        self.data.resize(modulus.data.len(), 0);
+        sub2rev(&modulus.data, &mut self.data);
+        self.normalize();
+    }
+
+    /// Returns `(self * other) % modulus`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let mut x = self.clone();
+        x.mul_mod_assign(other, modulus);
+        x
+    }
+
+    /// Sets `self` to `(self * other) % modulus`.
+    ///
+    /// This uses a temporary [`Workspace`] for the product and the reduction.
+    /// To reuse that scratch space across calls, use
+    /// [`mul_mod_with_workspace`] instead.
+    ///
+    /// Panics if `modulus` is zero.
+    ///
+    /// [`mul_mod_with_workspace`]: #method.mul_mod_with_workspace
+    pub fn mul_mod_assign(&mut self, other: &Self, modulus: &Self) {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        self.mul_mod_with_workspace(other, modulus, &mut Workspace::new());
+    }
+}
+
+/// Sets `x` to `x mod 2^bits`.
+fn truncate_bits(x: &mut BigUint, bits: usize) {
+    let digits = bits / big_digit::BITS;
+    let rem = bits % big_digit::BITS;
+    if x.data.len() > digits {
+        if rem == 0 {
+            x.data.truncate(digits);
+        } else {
+            x.data.truncate(digits + 1);
+            x.data[digits] &= (1 << rem) - 1;
+        }
+        x.normalize();
+    }
+}
+
+/// Returns `x ^ e mod 2^bits`, reducing by truncation.
+fn modpow_pow2(x: &BigUint, e: &BigUint, bits: usize) -> BigUint {
+    let mut base = x.clone();
+    truncate_bits(&mut base, bits);
+    let mut acc = BigUint::one();
+    truncate_bits(&mut acc, bits);
+
+    for i in (0..e.bits()).rev() {
+        acc = &acc * &acc;
+        truncate_bits(&mut acc, bits);
+        if (e.data[i / big_digit::BITS] >> (i % big_digit::BITS)) & 1 == 1 {
+            acc *= &base;
+            truncate_bits(&mut acc, bits);
+        }
+    }
+    acc
+}
+
+/// Trial divisors waiting to be tested together, by one remainder modulo their product.
+struct TrialBatch<'a> {
+    n: &'a BigUint,
+    divisors: SmallVec<[u64; 16]>,
+    product: BigDigit,
+}
+
+impl<'a> TrialBatch<'a> {
+    /// Adds `d` to the batch, first testing the pending divisors if their product would
+    /// overflow a digit. Returns the smallest divisor found.
+    fn push(&mut self, d: u64) -> Option<u64> {
+        let max = u128::from(BigDigit::MAX);
+        if u128::from(self.product) * u128::from(d) > max {
+            if let Some(f) = self.flush() {
+                return Some(f);
+            }
+            if u128::from(d) > max {
+                return if (self.n % d).is_zero() {
+                    Some(d)
+                } else {
+                    None
+                };
+            }
+        }
+        self.product *= d as BigDigit;
+        self.divisors.push(d);
+        None
+    }
+
+    /// Tests the pending divisors and empties the batch.
+    fn flush(&mut self) -> Option<u64> {
+        if self.divisors.is_empty() {
+            return None;
+        }
+        let r = rem_digit(&self.n.data, self.product);
+        let found = self
+            .divisors
+            .iter()
+            .copied()
+            .find(|&d| r % d as BigDigit == 0);
+        self.divisors.clear();
+        self.product = 1;
+        found
     }
 }
 
@@ -0,0 +1,201 @@
+//! A borrowed view of a big unsigned integer.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Add, Div, Mul, Rem, Sub};
+
+use smallvec::SmallVec;
+
+use crate::algorithms::{__add2, cmp_slice, div_rem, mul3, sub2};
+use crate::big_digit::{self, BigDigit};
+use crate::biguint::hash_digits;
+use crate::BigUint;
+use crate::VEC_SIZE;
+
+/// A big unsigned integer borrowed from a slice of digits.
+///
+/// This lets parsers and deserializers work on numbers that sit inside a
+/// larger buffer without first copying them into a `BigUint`. The digits
+/// are little-endian, in base `BigDigit`.
+///
+/// Comparison, hashing and the `Add`, `Sub` and `Mul` operators read the
+/// slice directly. `Div`, `Rem` and formatting copy the digits into a
+/// `BigUint` first, as the underlying algorithms work on owned values.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, BigUintRef};
+///
+/// let a = BigUint::from(1234u32);
+/// let b = BigUint::from(5678u32);
+/// assert_eq!(a.as_ref_view() * b.as_ref_view(), &a * &b);
+/// assert!(a.as_ref_view() < b.as_ref_view());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BigUintRef<'a> {
+    data: &'a [BigDigit],
+}
+
+impl<'a> BigUintRef<'a> {
+    /// Creates a view of `digits`, ignoring any high zero digits.
+    #[inline]
+    pub fn new(digits: &'a [BigDigit]) -> BigUintRef<'a> {
+        let len = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+        BigUintRef {
+            data: &digits[..len],
+        }
+    }
+
+    /// Returns the digits of the view, without any high zero digits.
+    #[inline]
+    pub fn digits(&self) -> &'a [BigDigit] {
+        self.data
+    }
+
+    /// Returns `true` if the view is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Determines the fewest bits necessary to express the value.
+    pub fn bits(&self) -> usize {
+        match self.data.last() {
+            Some(last) => self.data.len() * big_digit::BITS - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Copies the digits into an owned `BigUint`.
+    #[inline]
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint {
+            data: SmallVec::from_slice(self.data),
+        }
+    }
+}
+
+impl<'a> From<BigUintRef<'a>> for BigUint {
+    #[inline]
+    fn from(n: BigUintRef<'a>) -> BigUint {
+        n.to_biguint()
+    }
+}
+
+impl<'a, 'b> PartialEq<BigUintRef<'b>> for BigUintRef<'a> {
+    #[inline]
+    fn eq(&self, other: &BigUintRef<'b>) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<'a> Eq for BigUintRef<'a> {}
+
+impl<'a, 'b> PartialOrd<BigUintRef<'b>> for BigUintRef<'a> {
+    #[inline]
+    fn partial_cmp(&self, other: &BigUintRef<'b>) -> Option<Ordering> {
+        Some(cmp_slice(self.data, other.data))
+    }
+}
+
+impl<'a> Ord for BigUintRef<'a> {
+    #[inline]
+    fn cmp(&self, other: &BigUintRef<'a>) -> Ordering {
+        cmp_slice(self.data, other.data)
+    }
+}
+
+impl<'a> Hash for BigUintRef<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_digits(self.data, state);
+    }
+}
+
+impl<'a> fmt::Display for BigUintRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a> fmt::LowerHex for BigUintRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a> fmt::UpperHex for BigUintRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a> fmt::Binary for BigUintRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Binary::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a> fmt::Octal for BigUintRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Octal::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a, 'b> Add<BigUintRef<'b>> for BigUintRef<'a> {
+    type Output = BigUint;
+
+    fn add(self, other: BigUintRef<'b>) -> BigUint {
+        let (a, b) = if self.data.len() >= other.data.len() {
+            (self.data, other.data)
+        } else {
+            (other.data, self.data)
+        };
+
+        let mut sum: SmallVec<[BigDigit; VEC_SIZE]> = SmallVec::with_capacity(a.len() + 1);
+        sum.extend_from_slice(a);
+        let carry = __add2(&mut sum, b);
+        if carry != 0 {
+            sum.push(carry);
+        }
+        BigUint { data: sum }
+    }
+}
+
+impl<'a, 'b> Sub<BigUintRef<'b>> for BigUintRef<'a> {
+    type Output = BigUint;
+
+    fn sub(self, other: BigUintRef<'b>) -> BigUint {
+        let mut diff = self.to_biguint();
+        sub2(&mut diff.data, other.data);
+        diff.normalized()
+    }
+}
+
+impl<'a, 'b> Mul<BigUintRef<'b>> for BigUintRef<'a> {
+    type Output = BigUint;
+
+    #[inline]
+    fn mul(self, other: BigUintRef<'b>) -> BigUint {
+        mul3(self.data, other.data)
+    }
+}
+
+impl<'a, 'b> Div<BigUintRef<'b>> for BigUintRef<'a> {
+    type Output = BigUint;
+
+    #[inline]
+    fn div(self, other: BigUintRef<'b>) -> BigUint {
+        div_rem(&self.to_biguint(), &other.to_biguint()).0
+    }
+}
+
+impl<'a, 'b> Rem<BigUintRef<'b>> for BigUintRef<'a> {
+    type Output = BigUint;
+
+    #[inline]
+    fn rem(self, other: BigUintRef<'b>) -> BigUint {
+        div_rem(&self.to_biguint(), &other.to_biguint()).1
+    }
+}
@@ -0,0 +1,131 @@
+//! Bounding the work done by a single large operation.
+
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// A limit on how much work an operation may do before giving up.
+///
+/// The `_with_budget` methods on [`BigUint`](struct.BigUint.html) check
+/// their budget between steps of the computation and stop with a
+/// [`BudgetExceeded`] error once it runs out, so a server can bound the
+/// time spent on adversarial inputs. A budget can combine any of:
+///
+/// - a work limit, counted in digit by digit multiplications of the
+///   schoolbook algorithms, which overestimates the cost of the faster
+///   algorithms actually used;
+/// - a cancellation flag, which another thread can set at any time;
+/// - a deadline, with the `std` feature.
+///
+/// Work is deducted as it is done, so one budget can be shared by a
+/// sequence of operations.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, Budget, BudgetExceeded};
+///
+/// let x = (BigUint::from(1u32) << 100_000) - 1u32;
+///
+/// let mut budget = Budget::unlimited().with_work_limit(1000);
+/// assert_eq!(x.mul_with_budget(&x, &mut budget), Err(BudgetExceeded::Work));
+///
+/// let mut budget = Budget::unlimited();
+/// assert_eq!(x.mul_with_budget(&x, &mut budget), Ok(&x * &x));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    work: Option<u64>,
+    cancel: Option<Arc<AtomicBool>>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+/// The error returned when an operation runs out of [`Budget`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetExceeded {
+    /// The work limit was used up.
+    Work,
+    /// The cancellation flag was set.
+    Cancelled,
+    /// The deadline passed.
+    Deadline,
+}
+
+impl Budget {
+    /// Creates a budget with no limits. Limits are added with the `with_`
+    /// methods.
+    pub fn unlimited() -> Budget {
+        Budget::default()
+    }
+
+    /// Limits the budget to `work` digit multiplications.
+    pub fn with_work_limit(mut self, work: u64) -> Budget {
+        self.work = Some(work);
+        self
+    }
+
+    /// Stops operations once `flag` is set to `true`.
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Budget {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Stops operations once `deadline` has passed.
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: Instant) -> Budget {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns the work left, or `None` if there is no work limit.
+    pub fn remaining_work(&self) -> Option<u64> {
+        self.work
+    }
+
+    /// Deducts `work` from the budget, failing if the work limit is used up
+    /// or the operation has been cancelled or timed out.
+    pub(crate) fn charge(&mut self, work: u64) -> Result<(), BudgetExceeded> {
+        if let Some(left) = self.work {
+            self.work = Some(left.checked_sub(work).ok_or(BudgetExceeded::Work)?);
+        }
+        if let Some(flag) = &self.cancel {
+            if flag.load(Ordering::Relaxed) {
+                return Err(BudgetExceeded::Cancelled);
+            }
+        }
+        #[cfg(feature = "std")]
+        {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    return Err(BudgetExceeded::Deadline);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deducts the cost of multiplying an `a` digit number by a `b` digit one.
+    pub(crate) fn charge_mul(&mut self, a: usize, b: usize) -> Result<(), BudgetExceeded> {
+        self.charge((a as u64).saturating_mul(b as u64))
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BudgetExceeded::Work => "operation ran out of work budget",
+            BudgetExceeded::Cancelled => "operation was cancelled",
+            BudgetExceeded::Deadline => "operation passed its deadline",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BudgetExceeded {}
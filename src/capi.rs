@@ -0,0 +1,213 @@
+//! A C interface over `BigUint`, for embedding the library in code that isn't written in
+//! Rust.
+//!
+//! Numbers are passed around as opaque `num_bigint_t *` handles. A handle is created by
+//! `num_bigint_new`, `num_bigint_from_bytes_be` or one of the arithmetic functions, and must
+//! be released exactly once with `num_bigint_free`. Handles are never modified after they
+//! are created, so the same handle may be used as several inputs of one call.
+//!
+//! Every fallible function returns a [`NumBigintStatus`] and writes its result through an
+//! out pointer, which is left untouched unless the status is `NUM_BIGINT_OK`. None of the
+//! functions panic on invalid input.
+//!
+//! A matching declaration of the interface is:
+//!
+//! ```c
+//! typedef struct num_bigint num_bigint_t;
+//!
+//! typedef enum {
+//!     NUM_BIGINT_OK = 0,
+//!     NUM_BIGINT_NULL_POINTER = 1,
+//!     NUM_BIGINT_DIVIDE_BY_ZERO = 2,
+//!     NUM_BIGINT_BUFFER_TOO_SMALL = 3,
+//! } num_bigint_status_t;
+//!
+//! num_bigint_t *num_bigint_new(void);
+//! void num_bigint_free(num_bigint_t *n);
+//! num_bigint_status_t num_bigint_from_bytes_be(const uint8_t *bytes, size_t len,
+//!                                              num_bigint_t **out);
+//! num_bigint_status_t num_bigint_to_bytes_be(const num_bigint_t *n, uint8_t *buf,
+//!                                            size_t cap, size_t *len);
+//! num_bigint_status_t num_bigint_add(const num_bigint_t *a, const num_bigint_t *b,
+//!                                    num_bigint_t **out);
+//! num_bigint_status_t num_bigint_mul(const num_bigint_t *a, const num_bigint_t *b,
+//!                                    num_bigint_t **out);
+//! num_bigint_status_t num_bigint_div(const num_bigint_t *a, const num_bigint_t *b,
+//!                                    num_bigint_t **out);
+//! num_bigint_status_t num_bigint_modpow(const num_bigint_t *base, const num_bigint_t *exp,
+//!                                       const num_bigint_t *modulus, num_bigint_t **out);
+//! ```
+
+use alloc::boxed::Box;
+use core::{ptr, slice};
+
+use num_traits::Zero;
+
+use crate::BigUint;
+
+/// The result of a call through the C interface.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumBigintStatus {
+    /// The call succeeded and its result was written.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The divisor or modulus was zero.
+    DivideByZero = 2,
+    /// The output buffer is too short; the required length was written instead.
+    BufferTooSmall = 3,
+}
+
+/// Moves `n` to the heap and stores the new handle in `out`.
+unsafe fn emit(out: *mut *mut BigUint, n: BigUint) -> NumBigintStatus {
+    *out = Box::into_raw(Box::new(n));
+    NumBigintStatus::Ok
+}
+
+/// Creates a handle holding zero.
+#[no_mangle]
+pub extern "C" fn num_bigint_new() -> *mut BigUint {
+    Box::into_raw(Box::new(BigUint::zero()))
+}
+
+/// Releases a handle. Null is ignored.
+///
+/// # Safety
+///
+/// `n` must be null or a handle returned by this interface that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_free(n: *mut BigUint) {
+    if !n.is_null() {
+        drop(Box::from_raw(n));
+    }
+}
+
+/// Creates a handle from `len` big-endian bytes. `bytes` may be null if `len` is zero.
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes unless `len` is zero, and `out` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_from_bytes_be(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut BigUint,
+) -> NumBigintStatus {
+    if out.is_null() || (bytes.is_null() && len != 0) {
+        return NumBigintStatus::NullPointer;
+    }
+    let bytes = if len == 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(bytes, len)
+    };
+    emit(out, BigUint::from_bytes_be(bytes))
+}
+
+/// Writes the big-endian bytes of `n` to `buf` and their count to `len`. Zero is written as
+/// a single zero byte.
+///
+/// If `cap` is less than the count, nothing is written to `buf`, the count is still written
+/// to `len` and `BufferTooSmall` is returned, so a caller can query the size by passing a
+/// null `buf` and a `cap` of zero.
+///
+/// # Safety
+///
+/// `n` must be a live handle, `len` must be null or valid for writes, and `buf` must point
+/// to `cap` writable bytes unless `cap` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_to_bytes_be(
+    n: *const BigUint,
+    buf: *mut u8,
+    cap: usize,
+    len: *mut usize,
+) -> NumBigintStatus {
+    if n.is_null() || len.is_null() || (buf.is_null() && cap != 0) {
+        return NumBigintStatus::NullPointer;
+    }
+    let bytes = (*n).to_bytes_be();
+    *len = bytes.len();
+    if cap < bytes.len() {
+        return NumBigintStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+    NumBigintStatus::Ok
+}
+
+/// Stores a new handle holding `a + b` in `out`.
+///
+/// # Safety
+///
+/// `a` and `b` must be live handles, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_add(
+    a: *const BigUint,
+    b: *const BigUint,
+    out: *mut *mut BigUint,
+) -> NumBigintStatus {
+    if a.is_null() || b.is_null() || out.is_null() {
+        return NumBigintStatus::NullPointer;
+    }
+    emit(out, &*a + &*b)
+}
+
+/// Stores a new handle holding `a * b` in `out`.
+///
+/// # Safety
+///
+/// `a` and `b` must be live handles, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_mul(
+    a: *const BigUint,
+    b: *const BigUint,
+    out: *mut *mut BigUint,
+) -> NumBigintStatus {
+    if a.is_null() || b.is_null() || out.is_null() {
+        return NumBigintStatus::NullPointer;
+    }
+    emit(out, &*a * &*b)
+}
+
+/// Stores a new handle holding the truncated quotient `a / b` in `out`.
+///
+/// # Safety
+///
+/// `a` and `b` must be live handles, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_div(
+    a: *const BigUint,
+    b: *const BigUint,
+    out: *mut *mut BigUint,
+) -> NumBigintStatus {
+    if a.is_null() || b.is_null() || out.is_null() {
+        return NumBigintStatus::NullPointer;
+    }
+    if (*b).is_zero() {
+        return NumBigintStatus::DivideByZero;
+    }
+    emit(out, &*a / &*b)
+}
+
+/// Stores a new handle holding `base ^ exp mod modulus` in `out`.
+///
+/// # Safety
+///
+/// `base`, `exp` and `modulus` must be live handles, and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn num_bigint_modpow(
+    base: *const BigUint,
+    exp: *const BigUint,
+    modulus: *const BigUint,
+    out: *mut *mut BigUint,
+) -> NumBigintStatus {
+    if base.is_null() || exp.is_null() || modulus.is_null() || out.is_null() {
+        return NumBigintStatus::NullPointer;
+    }
+    if (*modulus).is_zero() {
+        return NumBigintStatus::DivideByZero;
+    }
+    emit(out, (*base).modpow(&*exp, &*modulus))
+}
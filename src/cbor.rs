@@ -0,0 +1,251 @@
+//! CBOR bignums, as defined in RFC 8949 section 3.4.3.
+//!
+//! A non-negative value n is encoded as tag 2 followed by a byte string with the big-endian
+//! bytes of n, and a negative value n as tag 3 followed by the bytes of -1 - n.
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use num_traits::{One, Zero};
+
+use crate::{BigInt, BigUint, Sign};
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TAG: u8 = 6;
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+/// Additional information for an indefinite length item.
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+
+/// The error returned when decoding a CBOR bignum fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CborError {
+    kind: CborErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CborErrorKind {
+    Truncated,
+    TrailingBytes,
+    UnexpectedItem,
+    Negative,
+}
+
+impl CborError {
+    fn __description(&self) -> &str {
+        use self::CborErrorKind::*;
+        match self.kind {
+            Truncated => "CBOR input ended in the middle of an item",
+            TrailingBytes => "CBOR input has bytes after the bignum",
+            UnexpectedItem => "CBOR item is not an integer or bignum",
+            Negative => "CBOR integer is negative",
+        }
+    }
+
+    fn new(kind: CborErrorKind) -> Self {
+        CborError { kind }
+    }
+}
+
+impl fmt::Display for CborError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.__description().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for CborError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+}
+
+impl BigUint {
+    /// Encodes `self` as a CBOR bignum: tag 2 followed by its big-endian bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(1u32) << 64;
+    /// assert_eq!(n.to_cbor(), [0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// assert_eq!(BigUint::from_cbor(&n.to_cbor()), Ok(n));
+    /// ```
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_bignum(&mut out, TAG_POSITIVE_BIGNUM, self);
+        out
+    }
+
+    /// Decodes a CBOR bignum with tag 2, or an unsigned integer, which must make up all of
+    /// `bytes`.
+    ///
+    /// Byte strings of both definite and indefinite length are accepted.
+    pub fn from_cbor(bytes: &[u8]) -> Result<BigUint, CborError> {
+        match BigInt::from_cbor(bytes)? {
+            n if n.sign() == Sign::Minus => Err(CborError::new(CborErrorKind::Negative)),
+            n => Ok(n.data),
+        }
+    }
+}
+
+impl BigInt {
+    /// Encodes `self` as a CBOR bignum: tag 2 followed by the big-endian bytes of `self`
+    /// if it is non-negative, and tag 3 followed by those of `-1 - self` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let n = BigInt::from(-256);
+    /// assert_eq!(n.to_cbor(), [0xc3, 0x41, 0xff]);
+    /// assert_eq!(BigInt::from_cbor(&n.to_cbor()), Ok(n));
+    /// ```
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self.sign() {
+            Sign::Minus => {
+                let n = &self.data - BigUint::one();
+                write_bignum(&mut out, TAG_NEGATIVE_BIGNUM, &n);
+            }
+            _ => write_bignum(&mut out, TAG_POSITIVE_BIGNUM, &self.data),
+        }
+        out
+    }
+
+    /// Decodes a CBOR bignum with tag 2 or 3, or an integer, which must make up all of
+    /// `bytes`.
+    ///
+    /// Byte strings of both definite and indefinite length are accepted.
+    pub fn from_cbor(bytes: &[u8]) -> Result<BigInt, CborError> {
+        let mut input = bytes;
+        let (major, arg) = read_head(&mut input)?;
+        let n = match major {
+            MAJOR_UNSIGNED => BigInt::from(read_arg(&mut input, arg)?),
+            MAJOR_NEGATIVE => -BigInt::from(read_arg(&mut input, arg)?) - 1,
+            MAJOR_TAG => {
+                let tag = read_arg(&mut input, arg)?;
+                let magnitude = read_bytes(&mut input)?;
+                match tag {
+                    TAG_POSITIVE_BIGNUM => BigInt::from(magnitude),
+                    TAG_NEGATIVE_BIGNUM => -BigInt::from(magnitude) - 1,
+                    _ => return Err(CborError::new(CborErrorKind::UnexpectedItem)),
+                }
+            }
+            _ => return Err(CborError::new(CborErrorKind::UnexpectedItem)),
+        };
+
+        if !input.is_empty() {
+            return Err(CborError::new(CborErrorKind::TrailingBytes));
+        }
+        Ok(n)
+    }
+}
+
+fn write_bignum(out: &mut Vec<u8>, tag: u64, n: &BigUint) {
+    write_head(out, MAJOR_TAG, tag);
+    if n.is_zero() {
+        write_head(out, MAJOR_BYTES, 0);
+    } else {
+        let bytes = n.to_bytes_be();
+        write_head(out, MAJOR_BYTES, bytes.len() as u64);
+        out.extend_from_slice(&bytes);
+    }
+}
+
+/// Writes the initial byte of an item and its argument, in the shortest form.
+fn write_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= u64::from(u8::MAX) {
+        out.push(major | 24);
+        out.push(arg as u8);
+    } else if arg <= u64::from(u16::MAX) {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= u64::from(u32::MAX) {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+/// Reads the initial byte of an item, returning its major type and additional information.
+fn read_head(input: &mut &[u8]) -> Result<(u8, u8), CborError> {
+    let (&first, rest) = input
+        .split_first()
+        .ok_or_else(|| CborError::new(CborErrorKind::Truncated))?;
+    *input = rest;
+    Ok((first >> 5, first & 0x1f))
+}
+
+/// Reads the argument whose size is given by the additional information `info`.
+fn read_arg(input: &mut &[u8], info: u8) -> Result<u64, CborError> {
+    let len = match info {
+        0..=23 => return Ok(u64::from(info)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return Err(CborError::new(CborErrorKind::UnexpectedItem)),
+    };
+    if input.len() < len {
+        return Err(CborError::new(CborErrorKind::Truncated));
+    }
+    let (arg, rest) = input.split_at(len);
+    *input = rest;
+    Ok(arg.iter().fold(0, |acc, &b| acc << 8 | u64::from(b)))
+}
+
+/// Reads a byte string, of definite or indefinite length, as a big-endian number.
+fn read_bytes(input: &mut &[u8]) -> Result<BigUint, CborError> {
+    let (major, info) = read_head(input)?;
+    if major != MAJOR_BYTES {
+        return Err(CborError::new(CborErrorKind::UnexpectedItem));
+    }
+    if info != INDEFINITE {
+        return Ok(BigUint::from_bytes_be(read_chunk(input, info)?));
+    }
+
+    // An indefinite length string is a sequence of definite length chunks ended by a break.
+    let mut bytes = Vec::new();
+    loop {
+        match input.first() {
+            None => return Err(CborError::new(CborErrorKind::Truncated)),
+            Some(&BREAK) => {
+                *input = &input[1..];
+                return Ok(BigUint::from_bytes_be(&bytes));
+            }
+            Some(_) => {
+                let (major, info) = read_head(input)?;
+                if major != MAJOR_BYTES || info == INDEFINITE {
+                    return Err(CborError::new(CborErrorKind::UnexpectedItem));
+                }
+                bytes.extend_from_slice(read_chunk(input, info)?);
+            }
+        }
+    }
+}
+
+/// Reads the contents of a definite length byte string.
+fn read_chunk<'a>(input: &mut &'a [u8], info: u8) -> Result<&'a [u8], CborError> {
+    let len = read_arg(input, info)?;
+    if (input.len() as u64) < len {
+        return Err(CborError::new(CborErrorKind::Truncated));
+    }
+    let (chunk, rest) = input.split_at(len as usize);
+    *input = rest;
+    Ok(chunk)
+}
@@ -0,0 +1,202 @@
+//! A compact, self-delimiting binary encoding of `BigUint` and `BigInt`.
+//!
+//! A `BigUint` is encoded as the byte length of its magnitude, written as an unsigned
+//! LEB128 varint, followed by the little-endian bytes of the magnitude without trailing
+//! zeros. Zero is the single byte `0`. A `BigInt` uses the same layout with the sign folded
+//! into the low bit of the prefix, which holds `2 * len + 1` for negative values.
+//!
+//! Every value has exactly one encoding, and decoding rejects anything else, so the
+//! encoding can be compared and hashed byte for byte. It will not change between releases.
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::{BigInt, BigUint, Sign};
+
+/// The error returned when decoding a compact encoding fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactError {
+    kind: CompactErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CompactErrorKind {
+    Truncated,
+    LengthOverflow,
+    NonCanonical,
+}
+
+impl CompactError {
+    fn __description(&self) -> &str {
+        use self::CompactErrorKind::*;
+        match self.kind {
+            Truncated => "compact input ended in the middle of a number",
+            LengthOverflow => "compact length prefix is too large",
+            NonCanonical => "compact encoding is not in canonical form",
+        }
+    }
+
+    fn new(kind: CompactErrorKind) -> Self {
+        CompactError { kind }
+    }
+}
+
+impl fmt::Display for CompactError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.__description().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for CompactError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+}
+
+impl BigUint {
+    /// Encodes `self` as a varint byte length followed by its little-endian bytes.
+    ///
+    /// See [`from_compact_bytes`](#method.from_compact_bytes) for the inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// assert_eq!(BigUint::from(0u32).to_compact_bytes(), [0]);
+    /// assert_eq!(BigUint::from(0x1234u32).to_compact_bytes(), [2, 0x34, 0x12]);
+    /// ```
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_body(&mut out, byte_len(self), self);
+        out
+    }
+
+    /// Decodes a number written by [`to_compact_bytes`](#method.to_compact_bytes) from the
+    /// start of `bytes`, returning it with the bytes that follow it.
+    ///
+    /// Encodings with an overlong length prefix or trailing zero bytes are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let (n, rest) = BigUint::from_compact_bytes(&[2, 0x34, 0x12, 0xff]).unwrap();
+    /// assert_eq!(n, BigUint::from(0x1234u32));
+    /// assert_eq!(rest, [0xff]);
+    /// ```
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<(BigUint, &[u8]), CompactError> {
+        let (len, input) = read_varint(bytes)?;
+        read_body(input, len)
+    }
+}
+
+impl BigInt {
+    /// Encodes `self` as a varint holding twice the byte length of its magnitude, plus one
+    /// if it is negative, followed by the little-endian bytes of the magnitude.
+    ///
+    /// See [`from_compact_bytes`](#method.from_compact_bytes) for the inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// assert_eq!(BigInt::from(0x1234).to_compact_bytes(), [4, 0x34, 0x12]);
+    /// assert_eq!(BigInt::from(-0x1234).to_compact_bytes(), [5, 0x34, 0x12]);
+    /// ```
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let negative = self.sign() == Sign::Minus;
+        let mut out = Vec::new();
+        write_body(
+            &mut out,
+            byte_len(&self.data) << 1 | u64::from(negative),
+            &self.data,
+        );
+        out
+    }
+
+    /// Decodes a number written by [`to_compact_bytes`](#method.to_compact_bytes) from the
+    /// start of `bytes`, returning it with the bytes that follow it.
+    ///
+    /// Encodings with an overlong length prefix or trailing zero bytes, and negative zero,
+    /// are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let (n, rest) = BigInt::from_compact_bytes(&[5, 0x34, 0x12]).unwrap();
+    /// assert_eq!(n, BigInt::from(-0x1234));
+    /// assert!(rest.is_empty());
+    /// ```
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<(BigInt, &[u8]), CompactError> {
+        let (prefix, input) = read_varint(bytes)?;
+        let negative = prefix & 1 == 1;
+        let (n, rest) = read_body(input, prefix >> 1)?;
+        if negative && n.data.is_empty() {
+            return Err(CompactError::new(CompactErrorKind::NonCanonical));
+        }
+        let sign = if negative { Sign::Minus } else { Sign::Plus };
+        Ok((BigInt::from_biguint(sign, n), rest))
+    }
+}
+
+/// Writes `prefix` followed by the little-endian bytes of `n`, if it is nonzero.
+fn write_body(out: &mut Vec<u8>, prefix: u64, n: &BigUint) {
+    write_varint(out, prefix);
+    if !n.data.is_empty() {
+        out.extend_from_slice(&n.to_bytes_le());
+    }
+}
+
+/// Returns the number of bytes in the magnitude of `n`, which is zero for zero.
+fn byte_len(n: &BigUint) -> u64 {
+    (n.bits() as u64 + 7) / 8
+}
+
+/// Reads `len` little-endian bytes, which must not end in a zero byte.
+fn read_body(input: &[u8], len: u64) -> Result<(BigUint, &[u8]), CompactError> {
+    if (input.len() as u64) < len {
+        return Err(CompactError::new(CompactErrorKind::Truncated));
+    }
+    let (body, rest) = input.split_at(len as usize);
+    if body.last() == Some(&0) {
+        return Err(CompactError::new(CompactErrorKind::NonCanonical));
+    }
+    Ok((BigUint::from_bytes_le(body), rest))
+}
+
+/// Writes `n` as an unsigned LEB128 varint.
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Reads an unsigned LEB128 varint in its shortest form.
+fn read_varint(input: &[u8]) -> Result<(u64, &[u8]), CompactError> {
+    let mut n = 0u64;
+    for (i, &byte) in input.iter().enumerate() {
+        let shift = 7 * i as u32;
+        let bits = u64::from(byte & 0x7f);
+        if shift >= 64 || (shift > 0 && bits >> (64 - shift) != 0) {
+            return Err(CompactError::new(CompactErrorKind::LengthOverflow));
+        }
+        n |= bits << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return Err(CompactError::new(CompactErrorKind::NonCanonical));
+            }
+            return Ok((n, &input[i + 1..]));
+        }
+    }
+    Err(CompactError::new(CompactErrorKind::Truncated))
+}
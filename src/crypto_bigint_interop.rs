@@ -0,0 +1,131 @@
+//! Conversions between `BigUint` and the fixed-width types of `crypto-bigint`.
+//!
+//! Converting into `Uint<LIMBS>` fails if the value needs more than `LIMBS` words, and
+//! converting into `DynResidueParams<LIMBS>` also fails if the modulus is even, since
+//! Montgomery form needs an odd modulus.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::{Uint, Word};
+use integer::Integer;
+
+use crate::{BigUint, TryFromBigIntError};
+
+const WORD_BYTES: usize = size_of::<Word>();
+
+/// The error returned when a `BigUint` can't be used as the modulus of a
+/// `DynResidueParams`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResidueParamsError {
+    kind: ResidueParamsErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResidueParamsErrorKind {
+    OutOfRange,
+    EvenModulus,
+}
+
+impl ResidueParamsError {
+    fn __description(&self) -> &str {
+        use self::ResidueParamsErrorKind::*;
+        match self.kind {
+            OutOfRange => "modulus does not fit in the residue's limbs",
+            EvenModulus => "modulus must be odd",
+        }
+    }
+
+    fn new(kind: ResidueParamsErrorKind) -> Self {
+        ResidueParamsError { kind }
+    }
+}
+
+impl fmt::Display for ResidueParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.__description().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ResidueParamsError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+}
+
+impl<const LIMBS: usize> From<&Uint<LIMBS>> for BigUint {
+    fn from(n: &Uint<LIMBS>) -> Self {
+        let mut bytes = Vec::with_capacity(LIMBS * WORD_BYTES);
+        for word in n.as_words() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        BigUint::from_bytes_le(&bytes)
+    }
+}
+
+impl<const LIMBS: usize> From<Uint<LIMBS>> for BigUint {
+    fn from(n: Uint<LIMBS>) -> Self {
+        BigUint::from(&n)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<&BigUint> for Uint<LIMBS> {
+    type Error = TryFromBigIntError<()>;
+
+    fn try_from(n: &BigUint) -> Result<Self, Self::Error> {
+        let bytes = n.to_bytes_le();
+        if bytes.len() > LIMBS * WORD_BYTES {
+            return Err(TryFromBigIntError { original: () });
+        }
+
+        let mut words = [0; LIMBS];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks(WORD_BYTES)) {
+            let mut buf = [0; WORD_BYTES];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *word = Word::from_le_bytes(buf);
+        }
+        Ok(Uint::from_words(words))
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUint> for Uint<LIMBS> {
+    type Error = TryFromBigIntError<BigUint>;
+
+    fn try_from(n: BigUint) -> Result<Self, Self::Error> {
+        Uint::try_from(&n).map_err(|_| TryFromBigIntError { original: n })
+    }
+}
+
+impl<const LIMBS: usize> From<&DynResidueParams<LIMBS>> for BigUint {
+    /// Returns the modulus.
+    fn from(params: &DynResidueParams<LIMBS>) -> Self {
+        BigUint::from(params.modulus())
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<&BigUint> for DynResidueParams<LIMBS> {
+    type Error = ResidueParamsError;
+
+    /// Precomputes the Montgomery parameters for the modulus `n`, which must be odd.
+    fn try_from(n: &BigUint) -> Result<Self, Self::Error> {
+        if n.is_even() {
+            return Err(ResidueParamsError::new(ResidueParamsErrorKind::EvenModulus));
+        }
+        let n = Uint::try_from(n)
+            .map_err(|_| ResidueParamsError::new(ResidueParamsErrorKind::OutOfRange))?;
+        Ok(DynResidueParams::new(&n))
+    }
+}
+
+impl<const LIMBS: usize> From<&DynResidue<LIMBS>> for BigUint {
+    /// Returns the residue as an integer in `[0, modulus)`.
+    fn from(r: &DynResidue<LIMBS>) -> Self {
+        BigUint::from(&r.retrieve())
+    }
+}
@@ -0,0 +1,92 @@
+//! A `Debug` view of the internal representation of `BigUint` and `BigInt`.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::big_digit::{self, BigDigit};
+use crate::{BigInt, BigUint, Sign};
+
+/// Formats the sign, bit length and raw limbs of a number, as returned by
+/// [`BigUint::debug_limbs`] and [`BigInt::debug_limbs`].
+///
+/// The limbs are printed in hexadecimal, least significant first, exactly as stored, so
+/// an unnormalized number shows its trailing zero limbs. Use `{:#?}` to print one field per
+/// line.
+#[derive(Clone, Copy)]
+pub struct DebugLimbs<'a> {
+    sign: Sign,
+    limbs: &'a [BigDigit],
+}
+
+/// A single limb, printed as zero-padded hexadecimal.
+struct Limb(BigDigit);
+
+impl fmt::Debug for Limb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#0width$x}", self.0, width = big_digit::BITS / 4 + 2)
+    }
+}
+
+impl<'a> fmt::Debug for DebugLimbs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Count the bits from the top nonzero limb, so an unnormalized number still reports
+        // its true length.
+        let bits = match self.limbs.iter().rposition(|&d| d != 0) {
+            Some(i) => (i + 1) * big_digit::BITS - self.limbs[i].leading_zeros() as usize,
+            None => 0,
+        };
+        let limbs: Vec<Limb> = self.limbs.iter().map(|&d| Limb(d)).collect();
+        f.debug_struct("DebugLimbs")
+            .field("sign", &self.sign)
+            .field("bits", &bits)
+            .field("limbs", &limbs)
+            .finish()
+    }
+}
+
+impl BigUint {
+    /// Returns a value whose `Debug` output shows the bit length and raw limbs of `self`,
+    /// for debugging code that works with the digits directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let n = BigUint::from(0x1234u32);
+    /// let s = format!("{:?}", n.debug_limbs());
+    /// assert!(s.starts_with("DebugLimbs { sign: Plus, bits: 13, limbs: [0x0"));
+    /// assert!(s.ends_with("1234] }"));
+    /// ```
+    pub fn debug_limbs(&self) -> DebugLimbs<'_> {
+        let sign = if self.data.is_empty() {
+            Sign::NoSign
+        } else {
+            Sign::Plus
+        };
+        DebugLimbs {
+            sign,
+            limbs: &self.data,
+        }
+    }
+}
+
+impl BigInt {
+    /// Returns a value whose `Debug` output shows the sign, bit length and raw limbs of the
+    /// magnitude of `self`, for debugging code that works with the digits directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint_dig::BigInt;
+    ///
+    /// let s = format!("{:?}", BigInt::from(-1).debug_limbs());
+    /// assert!(s.starts_with("DebugLimbs { sign: Minus, bits: 1, limbs: [0x0"));
+    /// ```
+    pub fn debug_limbs(&self) -> DebugLimbs<'_> {
+        DebugLimbs {
+            sign: self.sign,
+            limbs: &self.data.data,
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Exponentiation of a fixed base with precomputed tables.
+
+use alloc::vec::Vec;
+
+use num_traits::{One, Zero};
+
+use crate::biguint::monty::window_at;
+use crate::integer::Integer;
+use crate::{BarrettReducer, BigUint, ModInt, ModulusParams};
+
+/// The window used by [`FixedBasePow::new`].
+const DEFAULT_WINDOW_BITS: usize = 4;
+
+/// Precomputed powers of a fixed base for computing `base ^ e % m` for many
+/// exponents `e`.
+///
+/// The exponent is split into windows of `w` bits, and the table holds
+/// `base ^ (j * 2^(w * i)) % m` for every window `i` and every nonzero
+/// digit `j`. A power is then the product of one table entry per window:
+/// about `bits / w` multiplications and no squarings, where
+/// [`modpow`](struct.BigUint.html#method.modpow) needs `bits` squarings plus
+/// its multiplications. The table has `bits / w * (2^w - 1)` entries, so
+/// larger windows trade memory for speed.
+///
+/// Odd moduli use Montgomery multiplication and even ones Barrett
+/// reduction. Exponents longer than the table was built for fall back to
+/// `modpow`.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, FixedBasePow};
+///
+/// let g = BigUint::from(5u32);
+/// let p = (BigUint::from(1u32) << 127) - 1u32;
+/// let table = FixedBasePow::new(&g, &p, 128);
+///
+/// for e in [1u32, 12345, 4_000_000_000] {
+///     let e = BigUint::from(e);
+///     assert_eq!(table.pow(&e), g.modpow(&e, &p));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FixedBasePow {
+    base: BigUint,
+    modulus: BigUint,
+    window_bits: usize,
+    max_bits: usize,
+    table: Table,
+}
+
+#[derive(Clone, Debug)]
+enum Table {
+    Montgomery(ModulusParams, Vec<ModInt>),
+    Barrett(BarrettReducer, Vec<BigUint>),
+}
+
+impl FixedBasePow {
+    /// Precomputes the powers of `base` modulo `modulus` needed for
+    /// exponents of up to `max_bits` bits, with 4 bit windows.
+    ///
+    /// Panics if the modulus is zero.
+    pub fn new(base: &BigUint, modulus: &BigUint, max_bits: usize) -> FixedBasePow {
+        FixedBasePow::with_window(base, modulus, max_bits, DEFAULT_WINDOW_BITS)
+    }
+
+    /// Like [`new`](#method.new), with windows of `window_bits` bits.
+    ///
+    /// Panics if the modulus is zero, or if `window_bits` is not between 1
+    /// and 8.
+    pub fn with_window(
+        base: &BigUint,
+        modulus: &BigUint,
+        max_bits: usize,
+        window_bits: usize,
+    ) -> FixedBasePow {
+        assert!(!modulus.is_zero(), "divide by zero!");
+        assert!(
+            (1..=8).contains(&window_bits),
+            "the window must be between 1 and 8 bits"
+        );
+
+        let windows = (max_bits + window_bits - 1) / window_bits;
+        let table = if modulus.is_odd() {
+            let params = ModulusParams::new(modulus);
+            let powers = powers(
+                ModInt::new(base, &params),
+                ModInt::one(&params),
+                windows,
+                window_bits,
+                |x, y| x * y,
+            );
+            Table::Montgomery(params, powers)
+        } else {
+            let reducer = BarrettReducer::new(modulus);
+            let powers = powers(
+                base % &reducer,
+                BigUint::one() % &reducer,
+                windows,
+                window_bits,
+                |x, y| &(x * y) % &reducer,
+            );
+            Table::Barrett(reducer, powers)
+        };
+
+        FixedBasePow {
+            base: base.clone(),
+            modulus: modulus.clone(),
+            window_bits,
+            max_bits: windows * window_bits,
+            table,
+        }
+    }
+
+    /// Returns the base.
+    #[inline]
+    pub fn base(&self) -> &BigUint {
+        &self.base
+    }
+
+    /// Returns the modulus.
+    #[inline]
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Returns `base ^ exponent % modulus`.
+    pub fn pow(&self, exponent: &BigUint) -> BigUint {
+        if exponent.bits() > self.max_bits {
+            return self.base.modpow(exponent, &self.modulus);
+        }
+
+        let digits = self.window_bits;
+        let row = (1 << digits) - 1;
+        let windows = (exponent.bits() + digits - 1) / digits;
+        let entries = (0..windows).filter_map(|i| {
+            let j = window_at(&exponent.data, i * digits, digits);
+            if j == 0 {
+                None
+            } else {
+                Some(i * row + j - 1)
+            }
+        });
+
+        match &self.table {
+            Table::Montgomery(params, powers) => {
+                let mut acc = ModInt::one(params);
+                for k in entries {
+                    acc *= &powers[k];
+                }
+                acc.to_biguint()
+            }
+            Table::Barrett(reducer, powers) => {
+                let mut acc = BigUint::one() % reducer;
+                for k in entries {
+                    acc = &(acc * &powers[k]) % reducer;
+                }
+                acc
+            }
+        }
+    }
+}
+
+/// Returns `base ^ (j * 2^(w * i))` for `i` in `0..windows` and `j` in
+/// `1..2^w`, row by row, given the multiplication `mul` and its identity.
+fn powers<T: Clone, F: Fn(&T, &T) -> T>(
+    base: T,
+    one: T,
+    windows: usize,
+    w: usize,
+    mul: F,
+) -> Vec<T> {
+    let row = (1 << w) - 1;
+    let mut table = Vec::with_capacity(windows * row);
+    let mut b = base;
+    for _ in 0..windows {
+        // b ^ 1, ..., b ^ (2^w - 1), and then b ^ 2^w starts the next row.
+        let mut x = one.clone();
+        for _ in 0..row {
+            x = mul(&x, &b);
+            table.push(x.clone());
+        }
+        b = mul(&x, &b);
+    }
+    table
+}
@@ -0,0 +1,297 @@
+//! Dedicated reduction for a handful of primes that are common in elliptic curve
+//! cryptography.
+//!
+//! `%` checks whether its modulus is one of these primes, by length first and then by value,
+//! and if so reduces without a long division:
+//!
+//! - P-256 and P-384 use the word-level identities from FIPS 186-4, appendix D.2.
+//! - secp256k1 (`2^256 - 2^32 - 977`), `2^255 - 19` and `2^448 - 2^224 - 1` fold the high
+//!   bits back in times `c = 2^k - p`, through a [`SpecialModulus`] built once per prime.
+
+use num_traits::One;
+
+use crate::algorithms::div_rem;
+use crate::big_digit;
+use crate::BigUint;
+use crate::SpecialModulus;
+
+lazy_static! {
+    static ref SECP256K1_MODULUS: SpecialModulus =
+        SpecialModulus::from_form(256, BigUint::from((1u64 << 32) + 977));
+    static ref CURVE25519_MODULUS: SpecialModulus =
+        SpecialModulus::from_form(255, BigUint::from(19u32));
+    static ref GOLDILOCKS_MODULUS: SpecialModulus =
+        SpecialModulus::from_form(448, (BigUint::one() << 224) + 1u32);
+}
+
+/// A prime recognized by [`KnownPrime::detect`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KnownPrime {
+    P256,
+    P384,
+    Secp256k1,
+    Curve25519,
+    Goldilocks,
+}
+
+// Little-endian 32-bit words of each prime.
+const P256: [u32; 8] = [
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0,
+    0,
+    0,
+    1,
+    0xffff_ffff,
+];
+const P384: [u32; 12] = [
+    0xffff_ffff,
+    0,
+    0,
+    0xffff_ffff,
+    0xffff_fffe,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+];
+const SECP256K1: [u32; 8] = [
+    0xffff_fc2f,
+    0xffff_fffe,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+];
+const CURVE25519: [u32; 8] = [
+    0xffff_ffed,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0x7fff_ffff,
+];
+const GOLDILOCKS: [u32; 14] = [
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_fffe,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+    0xffff_ffff,
+];
+
+// The FIPS 186-4 reduction terms, each a coefficient and the source word of every output
+// word, written most significant first as in the standard. `Z` stands for a zero word.
+const Z: u8 = u8::MAX;
+
+const P256_TERMS: [(i64, [u8; 8]); 9] = [
+    (1, [7, 6, 5, 4, 3, 2, 1, 0]),
+    (2, [15, 14, 13, 12, 11, Z, Z, Z]),
+    (2, [Z, 15, 14, 13, 12, Z, Z, Z]),
+    (1, [15, 14, Z, Z, Z, 10, 9, 8]),
+    (1, [8, 13, 15, 14, 13, 11, 10, 9]),
+    (-1, [10, 8, Z, Z, Z, 13, 12, 11]),
+    (-1, [11, 9, Z, Z, 15, 14, 13, 12]),
+    (-1, [12, Z, 10, 9, 8, 15, 14, 13]),
+    (-1, [13, Z, 11, 10, 9, Z, 15, 14]),
+];
+
+const P384_TERMS: [(i64, [u8; 12]); 10] = [
+    (1, [11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]),
+    (2, [Z, Z, Z, Z, Z, 23, 22, 21, Z, Z, Z, Z]),
+    (1, [23, 22, 21, 20, 19, 18, 17, 16, 15, 14, 13, 12]),
+    (1, [20, 19, 18, 17, 16, 15, 14, 13, 12, 23, 22, 21]),
+    (1, [19, 18, 17, 16, 15, 14, 13, 12, 20, Z, 23, Z]),
+    (1, [Z, Z, Z, Z, 23, 22, 21, 20, Z, Z, Z, Z]),
+    (1, [Z, Z, Z, Z, Z, Z, 23, 22, 21, Z, Z, 20]),
+    (-1, [22, 21, 20, 19, 18, 17, 16, 15, 14, 13, 12, 23]),
+    (-1, [Z, Z, Z, Z, Z, Z, Z, 23, 22, 21, 20, Z]),
+    (-1, [Z, Z, Z, Z, Z, Z, Z, 23, 23, Z, Z, Z]),
+];
+
+/// Returns the `i`th little-endian 32-bit word of `x`.
+#[inline]
+fn word(x: &BigUint, i: usize) -> u32 {
+    let per_digit = big_digit::BITS / 32;
+    let digit = x.data.get(i / per_digit).copied().unwrap_or(0);
+    (digit >> (32 * (i % per_digit))) as u32
+}
+
+fn has_words(m: &BigUint, words: &[u32]) -> bool {
+    words.iter().enumerate().all(|(i, &w)| word(m, i) == w)
+}
+
+impl KnownPrime {
+    /// Returns the prime equal to `m`, if any.
+    pub(crate) fn detect(m: &BigUint) -> Option<KnownPrime> {
+        let prime = match m.bits() {
+            255 => KnownPrime::Curve25519,
+            256 if word(m, 0) == P256[0] => KnownPrime::P256,
+            256 => KnownPrime::Secp256k1,
+            384 => KnownPrime::P384,
+            448 => KnownPrime::Goldilocks,
+            _ => return None,
+        };
+        if has_words(m, prime.words()) {
+            Some(prime)
+        } else {
+            None
+        }
+    }
+
+    fn words(self) -> &'static [u32] {
+        match self {
+            KnownPrime::P256 => &P256,
+            KnownPrime::P384 => &P384,
+            KnownPrime::Secp256k1 => &SECP256K1,
+            KnownPrime::Curve25519 => &CURVE25519,
+            KnownPrime::Goldilocks => &GOLDILOCKS,
+        }
+    }
+
+    /// Returns `x % p`, where `p` is the prime itself.
+    pub(crate) fn rem(self, x: &BigUint, p: &BigUint) -> BigUint {
+        match self {
+            KnownPrime::P256 => reduce_fips(x, p, 256, &P256_TERMS),
+            KnownPrime::P384 => reduce_fips(x, p, 384, &P384_TERMS),
+            KnownPrime::Secp256k1 => SECP256K1_MODULUS.rem(x),
+            KnownPrime::Curve25519 => CURVE25519_MODULUS.rem(x),
+            KnownPrime::Goldilocks => GOLDILOCKS_MODULUS.rem(x),
+        }
+    }
+}
+
+/// Reduces `x` modulo the `k`-bit NIST prime `p` with the given FIPS 186-4 terms.
+///
+/// The terms only cover values below `2^(2k)`; anything longer is divided normally.
+fn reduce_fips<const N: usize, const T: usize>(
+    x: &BigUint,
+    p: &BigUint,
+    k: usize,
+    terms: &[(i64, [u8; N]); T],
+) -> BigUint {
+    if x.bits() > 2 * k {
+        let (_, r) = div_rem(x, p);
+        return r;
+    }
+
+    let mut columns = [0i64; N];
+    for (coefficient, sources) in terms {
+        for (column, &source) in columns.iter_mut().rev().zip(sources) {
+            if source != Z {
+                *column += coefficient * i64::from(word(x, source as usize));
+            }
+        }
+    }
+
+    let mut words = [0u32; N];
+    let mut carry = 0i64;
+    for (w, column) in words.iter_mut().zip(columns) {
+        let v = column + carry;
+        *w = v as u32;
+        carry = v >> 32;
+    }
+
+    // The value is now `words + carry * 2^k`, with a carry of a few units either way.
+    let mut r = BigUint::from_slice(&words);
+    if carry >= 0 {
+        r += BigUint::from(carry as u64) << k;
+    } else {
+        let n = carry.unsigned_abs();
+        r += p * (n + 1);
+        r -= BigUint::from(n) << k;
+    }
+    while r >= *p {
+        r -= p;
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::{One, Zero};
+
+    fn pow2(k: usize) -> BigUint {
+        BigUint::one() << k
+    }
+
+    #[test]
+    fn test_detect() {
+        let primes = [
+            (
+                pow2(256) - pow2(224) + pow2(192) + pow2(96) - 1u32,
+                KnownPrime::P256,
+            ),
+            (
+                pow2(384) - pow2(128) - pow2(96) + pow2(32) - 1u32,
+                KnownPrime::P384,
+            ),
+            (pow2(256) - pow2(32) - 977u32, KnownPrime::Secp256k1),
+            (pow2(255) - 19u32, KnownPrime::Curve25519),
+            (pow2(448) - pow2(224) - 1u32, KnownPrime::Goldilocks),
+        ];
+        for (p, prime) in &primes {
+            assert_eq!(KnownPrime::detect(p), Some(*prime));
+            assert_eq!(KnownPrime::detect(&(p + 2u32)), None);
+        }
+        assert_eq!(KnownPrime::detect(&pow2(256)), None);
+        assert_eq!(KnownPrime::detect(&BigUint::zero()), None);
+    }
+
+    #[test]
+    fn test_rem() {
+        let primes = [
+            pow2(256) - pow2(224) + pow2(192) + pow2(96) - 1u32,
+            pow2(384) - pow2(128) - pow2(96) + pow2(32) - 1u32,
+            pow2(256) - pow2(32) - 977u32,
+            pow2(255) - 19u32,
+            pow2(448) - pow2(224) - 1u32,
+        ];
+        for p in &primes {
+            let prime = KnownPrime::detect(p).unwrap();
+            let k = p.bits();
+            let mut xs = vec![
+                BigUint::zero(),
+                p - 1u32,
+                p.clone(),
+                p + 1u32,
+                pow2(k),
+                p * p - 1u32,
+                p * p,
+                pow2(2 * k) - 1u32,
+                pow2(2 * k),
+                pow2(3 * k) + 12345u32,
+            ];
+            // Values with long runs of ones and zeros in every word.
+            let mut x = BigUint::one();
+            for i in 0..2 * k {
+                x = (x << 1) + (i % 3 == 0) as u32;
+                if i % 37 == 0 {
+                    xs.push(x.clone());
+                }
+            }
+
+            for x in &xs {
+                let (_, r) = div_rem(x, p);
+                assert_eq!(prime.rem(x, p), r, "{:?} {}", prime, x);
+            }
+        }
+    }
+}
@@ -110,7 +110,7 @@ extern crate std;
 #[macro_use]
 extern crate smallvec;
 
-#[cfg(feature = "prime")]
+#[cfg(any(feature = "prime", feature = "known-primes"))]
 #[macro_use]
 extern crate lazy_static;
 
@@ -125,12 +125,40 @@ mod macros;
 
 mod bigint;
 mod biguint;
+mod biguint_ref;
+mod modint;
 
 #[cfg(feature = "prime")]
 pub mod prime;
 
 pub mod algorithms;
+mod barrett;
+mod budget;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod compact;
+#[cfg(feature = "crypto-bigint")]
+mod crypto_bigint_interop;
+mod debug;
+mod fixed_base;
+#[cfg(feature = "known-primes")]
+mod known_primes;
+pub mod range;
+#[cfg(feature = "rug")]
+mod rug_interop;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "serde")]
+mod serde_with;
+mod special_modulus;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(not(feature = "stats"))]
+mod stats;
 pub mod traits;
+mod workspace;
 
 pub use crate::traits::*;
 
@@ -156,6 +184,7 @@ pub struct ParseBigIntError {
 enum BigIntErrorKind {
     Empty,
     InvalidDigit,
+    TooLarge,
 }
 
 impl ParseBigIntError {
@@ -164,6 +193,7 @@ impl ParseBigIntError {
         match self.kind {
             Empty => "cannot parse integer from empty string",
             InvalidDigit => "invalid digit found in string",
+            TooLarge => "number too large to parse",
         }
     }
 
@@ -178,6 +208,12 @@ impl ParseBigIntError {
             kind: BigIntErrorKind::InvalidDigit,
         }
     }
+
+    fn too_large() -> Self {
+        ParseBigIntError {
+            kind: BigIntErrorKind::TooLarge,
+        }
+    }
 }
 
 impl fmt::Display for ParseBigIntError {
@@ -193,9 +229,45 @@ impl Error for ParseBigIntError {
     }
 }
 
+/// The error returned when a fallible conversion from a big integer fails because the value
+/// is out of range for the target type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TryFromBigIntError<T> {
+    original: T,
+}
+
+impl<T> TryFromBigIntError<T> {
+    fn __description(&self) -> &str {
+        "out of range conversion regarding big integer attempted"
+    }
+
+    /// Extracts the original value, if available.
+    pub fn into_original(self) -> T {
+        self.original
+    }
+}
+
+impl<T> fmt::Display for TryFromBigIntError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.__description().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> Error for TryFromBigIntError<T> {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+}
+
 pub use crate::biguint::BigUint;
 pub use crate::biguint::IntoBigUint;
 pub use crate::biguint::ToBigUint;
+pub use crate::biguint::{parse_limit, set_parse_limit};
+pub use crate::biguint_ref::BigUintRef;
+
+pub use crate::biguint::monty::{ModPowOptions, ModulusParams};
+pub use crate::modint::{from_montgomery, to_montgomery, ModInt};
 
 pub use crate::bigint::negate_sign;
 pub use crate::bigint::BigInt;
@@ -203,18 +275,70 @@ pub use crate::bigint::IntoBigInt;
 pub use crate::bigint::Sign;
 pub use crate::bigint::ToBigInt;
 
+pub use crate::algorithms::DivStrategy;
+pub use crate::barrett::BarrettReducer;
+pub use crate::budget::{Budget, BudgetExceeded};
+pub use crate::compact::CompactError;
+pub use crate::debug::DebugLimbs;
+pub use crate::fixed_base::FixedBasePow;
+pub use crate::special_modulus::SpecialModulus;
+pub use crate::workspace::Workspace;
+
+#[cfg(feature = "cbor")]
+pub use crate::cbor::CborError;
+
+#[cfg(feature = "crypto-bigint")]
+pub use crate::crypto_bigint_interop::ResidueParamsError;
+
+#[cfg(feature = "serde")]
+pub use crate::serde_with::{serde_hex, serde_string, StringInteger};
+
 #[cfg(feature = "rand")]
 pub use crate::bigrand::{RandBigInt, RandomBits, UniformBigInt, UniformBigUint};
 
 #[cfg(feature = "prime")]
 pub use bigrand::RandPrime;
 
-#[cfg(not(feature = "u64_digit"))]
+#[cfg(feature = "rayon")]
+pub use crate::algorithms::{is_parallel, set_parallel};
+
+#[cfg(feature = "trace")]
+pub use crate::algorithms::{set_trace_hook, Algorithm, AlgorithmChoice};
+
+/// The number of digits a `BigUint` stores inline before spilling to the heap.
+///
+/// By default this covers 256 bits. The `inline-4`, `inline-8` and `inline-16`
+/// features select an explicit digit count instead; if several are enabled,
+/// the largest wins.
+#[cfg(feature = "inline-16")]
+pub const VEC_SIZE: usize = 16;
+
+#[cfg(all(feature = "inline-8", not(feature = "inline-16")))]
+pub const VEC_SIZE: usize = 8;
+
+#[cfg(all(
+    feature = "inline-4",
+    not(any(feature = "inline-8", feature = "inline-16"))
+))]
+pub const VEC_SIZE: usize = 4;
+
+#[cfg(all(
+    not(feature = "u64_digit"),
+    not(any(feature = "inline-4", feature = "inline-8", feature = "inline-16"))
+))]
 pub const VEC_SIZE: usize = 8;
 
-#[cfg(feature = "u64_digit")]
+#[cfg(all(
+    feature = "u64_digit",
+    not(any(feature = "inline-4", feature = "inline-8", feature = "inline-16"))
+))]
 pub const VEC_SIZE: usize = 4;
 
+/// The largest result, in bits, that `Pow` with a big integer exponent will
+/// produce before panicking. Use `BigUint::checked_pow_bits` or
+/// `BigInt::checked_pow_bits` to choose a different bound.
+pub const POW_MAX_BITS: usize = 1 << 30;
+
 mod big_digit {
     /// A `BigDigit` is a `BigUint`'s composing element.
     #[cfg(not(feature = "u64_digit"))]
@@ -266,4 +390,101 @@ mod big_digit {
     pub fn to_doublebigdigit(hi: BigDigit, lo: BigDigit) -> DoubleBigDigit {
         (DoubleBigDigit::from(lo)) | ((DoubleBigDigit::from(hi)) << BITS)
     }
+
+    /// Returns `a + b + carry` and the carry out, like the unstable
+    /// `carrying_add` on the primitive integers.
+    #[inline]
+    pub fn carrying_add(a: BigDigit, b: BigDigit, carry: bool) -> (BigDigit, bool) {
+        let (sum, c1) = a.overflowing_add(b);
+        let (sum, c2) = sum.overflowing_add(BigDigit::from(carry));
+        (sum, c1 | c2)
+    }
+
+    /// Returns `a - b - borrow` and the borrow out, like the unstable
+    /// `borrowing_sub` on the primitive integers.
+    #[inline]
+    pub fn borrowing_sub(a: BigDigit, b: BigDigit, borrow: bool) -> (BigDigit, bool) {
+        let (diff, b1) = a.overflowing_sub(b);
+        let (diff, b2) = diff.overflowing_sub(BigDigit::from(borrow));
+        (diff, b1 | b2)
+    }
+
+    /// Divides the two digit number `hi:lo` by `d`, returning the quotient
+    /// and remainder. The quotient must fit in one digit, so `hi < d`.
+    #[cfg(not(feature = "u64_digit"))]
+    #[inline]
+    pub fn div_wide(hi: BigDigit, lo: BigDigit, d: BigDigit) -> (BigDigit, BigDigit) {
+        let n = to_doublebigdigit(hi, lo);
+        let d = DoubleBigDigit::from(d);
+        ((n / d) as BigDigit, (n % d) as BigDigit)
+    }
+
+    /// Divides the two digit number `hi:lo` by `d`, returning the quotient
+    /// and remainder. The quotient must fit in one digit, so `hi < d`.
+    ///
+    /// A `u128` division would call the general 128 by 128 bit division
+    /// routine. Instead this divides by hand in 32 bit halves, following
+    /// `divlu` from Hacker's Delight, which needs only 64 bit divisions.
+    #[cfg(feature = "u64_digit")]
+    #[inline]
+    pub fn div_wide(hi: BigDigit, lo: BigDigit, d: BigDigit) -> (BigDigit, BigDigit) {
+        const B: u64 = 1 << 32;
+        const HALF_MASK: u64 = B - 1;
+
+        // Normalize so the top bit of the divisor is set, which keeps each
+        // estimated quotient half within 2 of its true value.
+        let s = d.leading_zeros();
+        let d = d << s;
+        let (d1, d0) = (d >> 32, d & HALF_MASK);
+        let n32 = if s == 0 {
+            hi
+        } else {
+            (hi << s) | (lo >> (64 - s))
+        };
+        let n10 = lo << s;
+        let (n1, n0) = (n10 >> 32, n10 & HALF_MASK);
+
+        // Each step divides a three half number by the two half divisor,
+        // estimating from the top halves and correcting the estimate down.
+        // The short-circuit keeps `q * d0` from overflowing.
+        let mut q1 = n32 / d1;
+        let mut rhat = n32 - q1 * d1;
+        while q1 >= B || q1 * d0 > (rhat << 32 | n1) {
+            q1 -= 1;
+            rhat += d1;
+            if rhat >= B {
+                break;
+            }
+        }
+        // The true value is less than `d`, so it is correct modulo 2^64.
+        let n21 = (n32 << 32 | n1).wrapping_sub(q1.wrapping_mul(d));
+
+        let mut q0 = n21 / d1;
+        let mut rhat = n21 - q0 * d1;
+        while q0 >= B || q0 * d0 > (rhat << 32 | n0) {
+            q0 -= 1;
+            rhat += d1;
+            if rhat >= B {
+                break;
+            }
+        }
+        let r = (n21 << 32 | n0).wrapping_sub(q0.wrapping_mul(d));
+
+        (q1 << 32 | q0, r >> s)
+    }
+
+    /// Returns `a * b + c + d` as `(lo, hi)` digits. The widened result can
+    /// never overflow, since `(2^n - 1)^2 + 2 * (2^n - 1) = 2^2n - 1`.
+    #[inline]
+    pub fn carrying_mul_add(
+        a: BigDigit,
+        b: BigDigit,
+        c: BigDigit,
+        d: BigDigit,
+    ) -> (BigDigit, BigDigit) {
+        let wide = DoubleBigDigit::from(a) * DoubleBigDigit::from(b)
+            + DoubleBigDigit::from(c)
+            + DoubleBigDigit::from(d);
+        (wide as BigDigit, (wide >> BITS) as BigDigit)
+    }
 }
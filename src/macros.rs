@@ -315,6 +315,65 @@ macro_rules! forward_all_scalar_binop_to_val_val_commutative {
     }
 }
 
+// Forward everything to ref-val, for ops that only need to read the big operand
+macro_rules! forward_all_scalar_binop_to_ref_val {
+    (impl $imp:ident<$scalar:ty> for $res:ty, $method:ident) => {
+        impl $imp<$scalar> for $res {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: $scalar) -> $res {
+                $imp::$method(&self, other)
+            }
+        }
+
+        impl<'a> $imp<&'a $scalar> for $res {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: &$scalar) -> $res {
+                $imp::$method(&self, *other)
+            }
+        }
+
+        impl<'a, 'b> $imp<&'b $scalar> for &'a $res {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: &$scalar) -> $res {
+                $imp::$method(self, *other)
+            }
+        }
+
+        impl<'a> $imp<$res> for &'a $scalar {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: $res) -> $res {
+                $imp::$method(*self, other)
+            }
+        }
+
+        impl<'a> $imp<&'a $res> for $scalar {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: &$res) -> $res {
+                $imp::$method(self, other.clone())
+            }
+        }
+
+        impl<'a, 'b> $imp<&'a $res> for &'b $scalar {
+            type Output = $res;
+
+            #[inline]
+            fn $method(self, other: &$res) -> $res {
+                $imp::$method(*self, other.clone())
+            }
+        }
+    };
+}
+
 macro_rules! promote_all_scalars {
     (impl $imp:ident for $res:ty, $method:ident) => {
         promote_unsigned_scalars!(impl $imp for $res, $method);
@@ -329,6 +388,138 @@ macro_rules! promote_all_scalars_assign {
     }
 }
 
+macro_rules! forward_scalar_ref_assign {
+    (impl $imp:ident for $res:ty, $method:ident, $( $scalar:ty ),*) => {
+        $(
+            impl<'a> $imp<&'a $scalar> for $res {
+                #[inline]
+                fn $method(&mut self, other: &$scalar) {
+                    self.$method(*other);
+                }
+            }
+        )*
+    }
+}
+
+macro_rules! forward_unsigned_scalars_ref_assign {
+    (impl $imp:ident for $res:ty, $method:ident) => {
+        forward_scalar_ref_assign!(impl $imp for $res, $method, u8, u16, u32, u64, usize);
+        #[cfg(has_i128)]
+        forward_scalar_ref_assign!(impl $imp for $res, $method, u128);
+    }
+}
+
+macro_rules! forward_all_scalars_ref_assign {
+    (impl $imp:ident for $res:ty, $method:ident) => {
+        forward_unsigned_scalars_ref_assign!(impl $imp for $res, $method);
+        forward_scalar_ref_assign!(impl $imp for $res, $method, i8, i16, i32, i64, isize);
+        #[cfg(has_i128)]
+        forward_scalar_ref_assign!(impl $imp for $res, $method, i128);
+    }
+}
+
+// Mixed `BigInt` and `BigUint` operands where the `BigUint` is owned: it is
+// moved into a non-negative `BigInt`, and the `BigInt` operator does the work.
+macro_rules! forward_mixed_val_binop {
+    (impl $imp:ident, $method:ident) => {
+        impl $imp<BigUint> for BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: BigUint) -> BigInt {
+                $imp::$method(self, BigInt::from(other))
+            }
+        }
+
+        impl<'a> $imp<BigUint> for &'a BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: BigUint) -> BigInt {
+                $imp::$method(self, BigInt::from(other))
+            }
+        }
+
+        impl $imp<BigInt> for BigUint {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: BigInt) -> BigInt {
+                $imp::$method(BigInt::from(self), other)
+            }
+        }
+
+        impl<'a> $imp<&'a BigInt> for BigUint {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: &BigInt) -> BigInt {
+                $imp::$method(BigInt::from(self), other)
+            }
+        }
+    };
+}
+
+// Mixed `BigInt` and `BigUint` operands where the `BigUint` is borrowed, so it
+// has to be cloned into a `BigInt`.
+macro_rules! forward_mixed_ref_binop {
+    (impl $imp:ident, $method:ident) => {
+        impl<'a> $imp<&'a BigUint> for BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: &BigUint) -> BigInt {
+                $imp::$method(self, BigInt::from(other.clone()))
+            }
+        }
+
+        impl<'a, 'b> $imp<&'b BigUint> for &'a BigInt {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: &BigUint) -> BigInt {
+                $imp::$method(self, BigInt::from(other.clone()))
+            }
+        }
+
+        impl<'a> $imp<BigInt> for &'a BigUint {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: BigInt) -> BigInt {
+                $imp::$method(BigInt::from(self.clone()), other)
+            }
+        }
+
+        impl<'a, 'b> $imp<&'b BigInt> for &'a BigUint {
+            type Output = BigInt;
+
+            #[inline]
+            fn $method(self, other: &BigInt) -> BigInt {
+                $imp::$method(BigInt::from(self.clone()), other)
+            }
+        }
+    };
+}
+
+macro_rules! forward_mixed_assign {
+    (impl $imp:ident, $method:ident) => {
+        impl $imp<BigUint> for BigInt {
+            #[inline]
+            fn $method(&mut self, other: BigUint) {
+                self.$method(BigInt::from(other));
+            }
+        }
+
+        impl<'a> $imp<&'a BigUint> for BigInt {
+            #[inline]
+            fn $method(&mut self, other: &BigUint) {
+                self.$method(BigInt::from(other.clone()));
+            }
+        }
+    };
+}
+
 macro_rules! impl_sum_iter_type {
     ($res:ty) => {
         impl<T> Sum<T> for $res
@@ -355,7 +546,12 @@ macro_rules! impl_product_iter_type {
             where
                 I: Iterator<Item = T>,
             {
-                iter.fold(One::one(), <$res>::mul)
+                // Multiply as a balanced tree, so that the operands stay of
+                // similar size instead of one side growing at every step.
+                let values: Vec<$res> = iter.map(|x| <$res>::one() * x).collect();
+                let digits = values.iter().map(|v| v.bits()).sum::<usize>() / big_digit::BITS;
+                crate::algorithms::tree::tree_reduce(values, digits, |_| false, <$res as Mul>::mul)
+                    .unwrap_or_else(One::one)
             }
         }
     };
@@ -0,0 +1,292 @@
+//! Integers modulo an odd modulus, kept in Montgomery form.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use num_traits::{Pow, Zero};
+
+use crate::algorithms::kernels::{add_n, sub_n};
+use crate::algorithms::mod_inverse;
+use crate::big_digit;
+use crate::{BigUint, ModulusParams, Workspace};
+
+/// An integer modulo an odd modulus.
+///
+/// The value is kept in Montgomery form for its whole life. Multiplication
+/// is then a single Montgomery product with no division, and conversion only
+/// happens in [`new`](#method.new) and [`to_biguint`](#method.to_biguint).
+/// The modulus is shared through [`ModulusParams`], so creating many values
+/// for one modulus is cheap.
+///
+/// Operands of a binary operation must have the same modulus; mixing
+/// moduli panics.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, ModInt, ModulusParams};
+/// use num_traits::Pow;
+///
+/// let params = ModulusParams::new(&BigUint::from(101u32));
+/// let a = ModInt::new(&BigUint::from(7u32), &params);
+/// let b = ModInt::new(&BigUint::from(99u32), &params);
+///
+/// assert_eq!((&a + &b).to_biguint(), BigUint::from(5u32));
+/// assert_eq!((&a * &b).to_biguint(), BigUint::from(87u32));
+/// assert_eq!((&a).pow(&BigUint::from(100u32)).to_biguint(), BigUint::from(1u32));
+/// assert_eq!((a.inverse().unwrap() * &a).to_biguint(), BigUint::from(1u32));
+/// ```
+#[derive(Clone)]
+pub struct ModInt {
+    // Montgomery form, reduced and padded to the length of the modulus.
+    value: BigUint,
+    params: ModulusParams,
+}
+
+impl ModInt {
+    /// Creates the residue of `x` modulo the modulus of `params`.
+    pub fn new(x: &BigUint, params: &ModulusParams) -> ModInt {
+        ModInt {
+            value: params.to_mont(x),
+            params: params.clone(),
+        }
+    }
+
+    /// Creates the residue zero.
+    pub fn zero(params: &ModulusParams) -> ModInt {
+        let mut value = BigUint::zero();
+        value.data.resize(params.len(), 0);
+        ModInt {
+            value,
+            params: params.clone(),
+        }
+    }
+
+    /// Creates the residue one.
+    pub fn one(params: &ModulusParams) -> ModInt {
+        ModInt::new(&BigUint::from(1u32), params)
+    }
+
+    /// Returns the value as an integer in `[0, m)`.
+    pub fn to_biguint(&self) -> BigUint {
+        self.params.redc(&self.value)
+    }
+
+    /// Returns the parameters of the modulus.
+    #[inline]
+    pub fn params(&self) -> &ModulusParams {
+        &self.params
+    }
+
+    /// Returns the modulus.
+    #[inline]
+    pub fn modulus(&self) -> &BigUint {
+        self.params.modulus()
+    }
+
+    /// Returns `true` if the value is zero.
+    pub fn is_zero(&self) -> bool {
+        self.value.data.iter().all(|&d| d == 0)
+    }
+
+    /// Returns the multiplicative inverse, or `None` if the value shares a
+    /// factor with the modulus.
+    pub fn inverse(&self) -> Option<ModInt> {
+        let x = self.to_biguint();
+        let inv = mod_inverse(Cow::Owned(x), Cow::Borrowed(self.modulus()))?;
+        Some(ModInt::new(&inv.to_biguint()?, &self.params))
+    }
+
+    fn check_params(&self, other: &ModInt) {
+        assert!(
+            self.params == other.params,
+            "ModInt operands have different moduli"
+        );
+    }
+}
+
+/// Converts every value in `xs` into a residue modulo the modulus of
+/// `params`.
+///
+/// This gives the same result as calling [`ModInt::new`] on each value, but
+/// shares the scratch space used for reduction across the whole batch.
+///
+/// [`ModInt::new`]: struct.ModInt.html#method.new
+pub fn to_montgomery(xs: &[BigUint], params: &ModulusParams) -> Vec<ModInt> {
+    let mut scratch = BigUint::zero();
+    let mut ws = Workspace::new();
+    xs.iter()
+        .map(|x| ModInt {
+            value: params.to_mont_with(x, &mut scratch, &mut ws),
+            params: params.clone(),
+        })
+        .collect()
+}
+
+/// Converts every residue in `xs` back to an integer in `[0, m)`.
+///
+/// This gives the same result as calling [`ModInt::to_biguint`] on each
+/// value, but shares the scratch space of the Montgomery reduction across
+/// the whole batch.
+///
+/// [`ModInt::to_biguint`]: struct.ModInt.html#method.to_biguint
+pub fn from_montgomery(xs: &[ModInt]) -> Vec<BigUint> {
+    let mut scratch = BigUint::zero();
+    xs.iter()
+        .map(|x| x.params.redc_with(&x.value, &mut scratch))
+        .collect()
+}
+
+impl PartialEq for ModInt {
+    fn eq(&self, other: &ModInt) -> bool {
+        self.params == other.params && self.value.data == other.value.data
+    }
+}
+
+impl Eq for ModInt {}
+
+impl fmt::Debug for ModInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ModInt({} mod {})", self.to_biguint(), self.modulus())
+    }
+}
+
+impl fmt::Display for ModInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_biguint(), f)
+    }
+}
+
+impl<'a> AddAssign<&'a ModInt> for ModInt {
+    fn add_assign(&mut self, other: &ModInt) {
+        self.check_params(other);
+        let m = &self.params.modulus().data;
+        let carry = add_n(&mut self.value.data, &other.value.data);
+        if carry || self.value.data.iter().rev().cmp(m.iter().rev()) != Ordering::Less {
+            sub_n(&mut self.value.data, m);
+        }
+    }
+}
+
+impl<'a> SubAssign<&'a ModInt> for ModInt {
+    fn sub_assign(&mut self, other: &ModInt) {
+        self.check_params(other);
+        if sub_n(&mut self.value.data, &other.value.data) {
+            add_n(&mut self.value.data, &self.params.modulus().data);
+        }
+    }
+}
+
+impl<'a> MulAssign<&'a ModInt> for ModInt {
+    fn mul_assign(&mut self, other: &ModInt) {
+        self.check_params(other);
+        let mut z = BigUint::zero();
+        self.params.mul(&mut z, &self.value, &other.value);
+        self.value = z;
+    }
+}
+
+forward_val_assign!(impl AddAssign for ModInt, add_assign);
+forward_val_assign!(impl SubAssign for ModInt, sub_assign);
+forward_val_assign!(impl MulAssign for ModInt, mul_assign);
+
+macro_rules! forward_modint_binop {
+    (impl $imp:ident, $method:ident, $assign:ident) => {
+        impl<'a> $imp<&'a ModInt> for ModInt {
+            type Output = ModInt;
+
+            #[inline]
+            fn $method(mut self, other: &ModInt) -> ModInt {
+                self.$assign(other);
+                self
+            }
+        }
+
+        impl<'a, 'b> $imp<&'b ModInt> for &'a ModInt {
+            type Output = ModInt;
+
+            #[inline]
+            fn $method(self, other: &ModInt) -> ModInt {
+                $imp::$method(self.clone(), other)
+            }
+        }
+
+        forward_val_val_binop!(impl $imp for ModInt, $method);
+        forward_ref_val_binop!(impl $imp for ModInt, $method);
+    };
+}
+
+forward_modint_binop!(impl Add, add, add_assign);
+forward_modint_binop!(impl Sub, sub, sub_assign);
+forward_modint_binop!(impl Mul, mul, mul_assign);
+
+impl Neg for ModInt {
+    type Output = ModInt;
+
+    fn neg(mut self) -> ModInt {
+        if !self.is_zero() {
+            let mut m = self.params.modulus().clone();
+            sub_n(&mut m.data, &self.value.data);
+            self.value = m;
+        }
+        self
+    }
+}
+
+impl<'a> Neg for &'a ModInt {
+    type Output = ModInt;
+
+    #[inline]
+    fn neg(self) -> ModInt {
+        -self.clone()
+    }
+}
+
+impl<'a, 'b> Pow<&'b BigUint> for &'a ModInt {
+    type Output = ModInt;
+
+    fn pow(self, exponent: &BigUint) -> ModInt {
+        let mut acc = ModInt::one(&self.params);
+        let mut tmp = BigUint::zero();
+        for i in (0..exponent.bits()).rev() {
+            self.params.mul(&mut tmp, &acc.value, &acc.value);
+            core::mem::swap(&mut acc.value, &mut tmp);
+            if (exponent.data[i / big_digit::BITS] >> (i % big_digit::BITS)) & 1 == 1 {
+                self.params.mul(&mut tmp, &acc.value, &self.value);
+                core::mem::swap(&mut acc.value, &mut tmp);
+            }
+        }
+        acc
+    }
+}
+
+impl<'a> Pow<BigUint> for &'a ModInt {
+    type Output = ModInt;
+
+    #[inline]
+    fn pow(self, exponent: BigUint) -> ModInt {
+        self.pow(&exponent)
+    }
+}
+
+impl<'a> Pow<&'a BigUint> for ModInt {
+    type Output = ModInt;
+
+    #[inline]
+    fn pow(self, exponent: &BigUint) -> ModInt {
+        (&self).pow(exponent)
+    }
+}
+
+impl Pow<BigUint> for ModInt {
+    type Output = ModInt;
+
+    #[inline]
+    fn pow(self, exponent: BigUint) -> ModInt {
+        (&self).pow(&exponent)
+    }
+}
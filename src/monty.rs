@@ -1,11 +1,17 @@
 #![allow(clippy::many_single_char_names)]
 
 use num_traits::{One, Zero};
-use core::ops::Shl;
+use core::cmp::Ordering;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
+use smallvec::SmallVec;
 
-use crate::big_digit::{self, BigDigit, DoubleBigDigit, SignedDoubleBigDigit};
+use crate::big_digit::{self, BigDigit, SignedDoubleBigDigit};
+use crate::algorithms::kernels::{add_n, mac_n, sub_n};
 use crate::biguint::BigUint;
+use crate::budget::{Budget, BudgetExceeded};
+use crate::integer::Integer;
+use crate::Workspace;
 
 struct MontyReducer {
     n0inv: BigDigit,
@@ -83,15 +89,7 @@ fn montgomery(z: &mut BigUint, x: &BigUint, y: &BigUint, m: &BigUint, k: BigDigi
 
 #[inline]
 fn add_mul_vvw(z: &mut [BigDigit], x: &[BigDigit], y: BigDigit) -> BigDigit {
-    let mut c = 0;
-    for (zi, xi) in z.iter_mut().zip(x.iter()) {
-        let (z1, z0) = mul_add_www(*xi, y, *zi);
-        let (c_, zi_) = add_ww(z0, c, 0);
-        *zi = zi_;
-        c = c_ + z1;
-    }
-
-    c
+    mac_n(z, x, y)
 }
 
 /// The resulting carry c is either 0 or 1.
@@ -108,28 +106,204 @@ fn sub_vv(z: &mut [BigDigit], x: &[BigDigit], y: &[BigDigit]) -> BigDigit {
     c
 }
 
-/// z1<<_W + z0 = x+y+c, with c == 0 or 1
-#[inline]
-fn add_ww(x: BigDigit, y: BigDigit, c: BigDigit) -> (BigDigit, BigDigit) {
-    let yc = y.wrapping_add(c);
-    let z0 = x.wrapping_add(yc);
-    let z1 = if z0 < x || yc < y { 1 } else { 0 };
+/// Precomputed Montgomery parameters for an odd modulus.
+///
+/// Holds the modulus `m`, `-m^-1 mod 2^BITS` and `R^2 mod m`, where `R` is
+/// `2^(BITS * len(m))`. Building these once lets many values share them, as
+/// with [`ModInt`](struct.ModInt.html). Cloning is cheap: the parameters are
+/// reference counted.
+#[derive(Clone, Debug)]
+pub struct ModulusParams {
+    inner: Arc<Params>,
+}
 
-    (z1, z0)
+#[derive(Debug)]
+struct Params {
+    m: BigUint,
+    n0inv: BigDigit,
+    // R^2 mod m, padded to the length of m
+    rr: BigUint,
+    // 1, padded to the length of m
+    one: BigUint,
 }
 
-/// z1 << _W + z0 = x * y + c
-#[inline]
-fn mul_add_www(x: BigDigit, y: BigDigit, c: BigDigit) -> (BigDigit, BigDigit) {
-    let z = x as DoubleBigDigit * y as DoubleBigDigit + c as DoubleBigDigit;
-    ((z >> big_digit::BITS) as BigDigit, z as BigDigit)
+impl PartialEq for ModulusParams {
+    fn eq(&self, other: &ModulusParams) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner) || self.inner.m == other.inner.m
+    }
+}
+
+impl Eq for ModulusParams {}
+
+impl ModulusParams {
+    /// Precomputes the Montgomery parameters for `m`.
+    ///
+    /// Panics if `m` is even or zero.
+    pub fn new(m: &BigUint) -> ModulusParams {
+        assert!(m.is_odd(), "the modulus must be odd");
+
+        let n = m.data.len();
+        let mut rr = (BigUint::one() << (2 * n * big_digit::BITS)) % m;
+        rr.data.resize(n, 0);
+        let mut one = BigUint::one();
+        one.data.resize(n, 0);
+
+        ModulusParams {
+            inner: Arc::new(Params {
+                m: m.clone(),
+                n0inv: MontyReducer::new(m).n0inv,
+                rr,
+                one,
+            }),
+        }
+    }
+
+    /// Returns the modulus.
+    #[inline]
+    pub fn modulus(&self) -> &BigUint {
+        &self.inner.m
+    }
+
+    /// The number of digits of the modulus, which every value in Montgomery
+    /// form is padded to.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.inner.m.data.len()
+    }
+
+    /// Sets `z` to `x * y / R mod m`, fully reduced and padded to `len()`.
+    pub(crate) fn mul(&self, z: &mut BigUint, x: &BigUint, y: &BigUint) {
+        let p = &*self.inner;
+        montgomery(z, x, y, &p.m, p.n0inv, self.len());
+        // `z` is padded, so compare digit by digit from the top.
+        if z.data.iter().rev().cmp(p.m.data.iter().rev()) != Ordering::Less {
+            sub_n(&mut z.data, &p.m.data);
+        }
+    }
+
+    /// Converts `x` into Montgomery form, `x * R mod m`.
+    pub(crate) fn to_mont(&self, x: &BigUint) -> BigUint {
+        self.to_mont_with(x, &mut BigUint::zero(), &mut Workspace::new())
+    }
+
+    /// Like `to_mont`, but reduces `x` into `scratch` using `ws`, so that
+    /// converting many values only allocates their results.
+    pub(crate) fn to_mont_with(
+        &self,
+        x: &BigUint,
+        scratch: &mut BigUint,
+        ws: &mut Workspace,
+    ) -> BigUint {
+        let n = self.len();
+        scratch.data.clear();
+        scratch.data.extend_from_slice(&x.data);
+        if x.data.len() > n || *x >= self.inner.m {
+            ws.reduce(scratch, &self.inner.m);
+        }
+        scratch.data.resize(n, 0);
+
+        let mut z = BigUint {
+            data: SmallVec::with_capacity(2 * n),
+        };
+        self.mul(&mut z, scratch, &self.inner.rr);
+        z
+    }
+
+    /// Converts `x` out of Montgomery form, `x / R mod m`.
+    pub(crate) fn redc(&self, x: &BigUint) -> BigUint {
+        let mut z = BigUint {
+            data: SmallVec::with_capacity(2 * self.len()),
+        };
+        self.mul(&mut z, x, &self.inner.one);
+        z.normalized()
+    }
+
+    /// Like `redc`, but runs the Montgomery product in `scratch`, so that
+    /// converting many values only allocates their results.
+    pub(crate) fn redc_with(&self, x: &BigUint, scratch: &mut BigUint) -> BigUint {
+        self.mul(scratch, x, &self.inner.one);
+        scratch.normalize();
+        BigUint::from_slice_native(&scratch.data)
+    }
+}
+
+/// Tuning knobs for [`BigUint::modpow_with_options`].
+///
+/// The defaults match [`BigUint::modpow`].
+///
+/// [`BigUint::modpow_with_options`]: struct.BigUint.html#method.modpow_with_options
+/// [`BigUint::modpow`]: struct.BigUint.html#method.modpow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModPowOptions {
+    /// Number of exponent bits consumed per multiplication, from 1 to 8.
+    ///
+    /// A window of `w` bits precomputes `2^w` powers of the base, so larger
+    /// windows trade memory for fewer multiplications on long exponents.
+    /// The default is 4. Only used for odd moduli.
+    pub window_bits: usize,
+    /// Reads the whole table of precomputed powers on every step, instead
+    /// of indexing it with exponent bits.
+    ///
+    /// This hides the exponent from cache timing, at the cost of touching
+    /// `2^window_bits` entries per step. The rest of the arithmetic is not
+    /// constant time, so this is a mitigation rather than a guarantee. Only
+    /// used for odd moduli.
+    pub constant_time: bool,
+    /// For an even modulus `2^k * q`, exponentiates modulo the odd part `q`
+    /// with Montgomery multiplication and modulo `2^k` by truncation, and
+    /// recombines the two with the Chinese remainder theorem.
+    ///
+    /// Without this, even moduli use plain square-and-multiply with a
+    /// division per step.
+    pub use_crt_for_even_modulus: bool,
+}
+
+impl Default for ModPowOptions {
+    fn default() -> ModPowOptions {
+        ModPowOptions {
+            window_bits: 4,
+            constant_time: false,
+            use_crt_for_even_modulus: false,
+        }
+    }
 }
 
 /// Calculates x ** y mod m using a fixed, 4-bit window.
 pub fn monty_modpow(x: &BigUint, y: &BigUint, m: &BigUint) -> BigUint {
-    assert!(m.data[0] & 1 == 1);
-    let mr = MontyReducer::new(m);
-    let num_words = m.data.len();
+    monty_modpow_with_params(x, y, &ModulusParams::new(m))
+}
+
+/// Calculates x ** y mod m using a fixed, 4-bit window, with the Montgomery
+/// parameters of m already computed.
+pub fn monty_modpow_with_params(x: &BigUint, y: &BigUint, params: &ModulusParams) -> BigUint {
+    monty_modpow_with_options(x, y, params, &ModPowOptions::default())
+}
+
+/// Calculates x ** y mod m using a fixed window of `options.window_bits`
+/// bits, with the Montgomery parameters of m already computed.
+pub fn monty_modpow_with_options(
+    x: &BigUint,
+    y: &BigUint,
+    params: &ModulusParams,
+    options: &ModPowOptions,
+) -> BigUint {
+    // An unlimited budget never runs out.
+    monty_modpow_with_budget(x, y, params, options, &mut Budget::unlimited()).unwrap()
+}
+
+/// Like `monty_modpow_with_options`, but charges `budget` for each window
+/// of the exponent and stops as soon as it runs out.
+pub(crate) fn monty_modpow_with_budget(
+    x: &BigUint,
+    y: &BigUint,
+    params: &ModulusParams,
+    options: &ModPowOptions,
+    budget: &mut Budget,
+) -> Result<BigUint, BudgetExceeded> {
+    let p = &*params.inner;
+    let m = &p.m;
+    let num_words = params.len();
+    let mr = MontyReducer { n0inv: p.n0inv };
 
     let mut x = x.clone();
 
@@ -144,24 +318,24 @@ pub fn monty_modpow(x: &BigUint, y: &BigUint, m: &BigUint) -> BigUint {
     }
 
     // rr = 2**(2*_W*len(m)) mod m
-    let mut rr = BigUint::one();
-    rr = (rr.shl(2 * num_words * big_digit::BITS)) % m;
-    if rr.data.len() < num_words {
-        rr.data.resize(num_words, 0);
-    }
+    let rr = &p.rr;
     // one = 1, with equal length to that of m
-    let mut one = BigUint::one();
-    one.data.resize(num_words, 0);
+    let one = &p.one;
 
-    let n = 4;
+    let n = options.window_bits;
+    assert!(
+        (1..=MAX_WINDOW_BITS).contains(&n),
+        "window_bits must be between 1 and {}",
+        MAX_WINDOW_BITS
+    );
     // powers[i] contains x^i
     let mut powers = Vec::with_capacity(1 << n);
 
     let mut v1 = BigUint::zero();
-    montgomery(&mut v1, &one, &rr, m, mr.n0inv, num_words);
+    montgomery(&mut v1, one, rr, m, mr.n0inv, num_words);
     powers.push(v1);
     let mut v2 = BigUint::zero();
-    montgomery(&mut v2, &x, &rr, m, mr.n0inv, num_words);
+    montgomery(&mut v2, &x, rr, m, mr.n0inv, num_words);
     powers.push(v2);
     for i in 2..1 << n {
         let mut r = BigUint::zero();
@@ -174,39 +348,37 @@ pub fn monty_modpow(x: &BigUint, y: &BigUint, m: &BigUint) -> BigUint {
     z.data.resize(num_words, 0);
     let mut zz = BigUint::zero();
     zz.data.resize(num_words, 0);
+    let mut selected = BigUint::zero();
 
     // same windowed exponent, but with Montgomery multiplications
-    for i in (0..y.data.len()).rev() {
-        let mut yi = y.data[i];
-        let mut j = 0;
-        while j < big_digit::BITS {
-            if i != y.data.len() - 1 || j != 0 {
-                montgomery(&mut zz, &z, &z, m, mr.n0inv, num_words);
-                montgomery(&mut z, &zz, &zz, m, mr.n0inv, num_words);
+    let windows = (y.data.len() * big_digit::BITS + n - 1) / n;
+    for w in (0..windows).rev() {
+        // `n` squarings and a multiplication, each about `2 * num_words^2`.
+        budget.charge_mul(2 * (n + 1) * num_words, num_words)?;
+        if w != windows - 1 {
+            for _ in 0..n {
                 montgomery(&mut zz, &z, &z, m, mr.n0inv, num_words);
-                montgomery(&mut z, &zz, &zz, m, mr.n0inv, num_words);
+                core::mem::swap(&mut z, &mut zz);
             }
-            montgomery(
-                &mut zz,
-                &z,
-                &powers[(yi >> (big_digit::BITS - n)) as usize],
-                m,
-                mr.n0inv,
-                num_words,
-            );
-            core::mem::swap(&mut z, &mut zz);
-            yi <<= n;
-            j += n;
         }
+        let yi = window_at(&y.data, w * n, n);
+        let power = if options.constant_time {
+            select_power(&powers, yi, &mut selected);
+            &selected
+        } else {
+            &powers[yi]
+        };
+        montgomery(&mut zz, &z, power, m, mr.n0inv, num_words);
+        core::mem::swap(&mut z, &mut zz);
     }
 
     // convert to regular number
-    montgomery(&mut zz, &z, &one, m, mr.n0inv, num_words);
+    montgomery(&mut zz, &z, one, m, mr.n0inv, num_words);
 
     zz.normalize();
     // One last reduction, just in case.
     // See golang.org/issue/13907.
-    if &zz >= m {
+    if zz >= *m {
         // Common case is m has high bit set; in that case,
         // since zz is the same length as m, there can be just
         // one multiple of m to remove. Just subtract.
@@ -215,11 +387,102 @@ pub fn monty_modpow(x: &BigUint, y: &BigUint, m: &BigUint) -> BigUint {
         // in case our beliefs are wrong.
         // The div is not expected to be reached.
         zz -= m;
-        if &zz >= m {
+        if zz >= *m {
             zz %= m;
         }
     }
 
     zz.normalize();
-    zz
+    Ok(zz)
+}
+
+/// Divides `u` by `d`, finding the remainder by Montgomery reduction and then
+/// the quotient by exact division, which needs no quotient digit guesses.
+///
+/// `d` must be nonzero and `u` at least `d`.
+pub(crate) fn monty_div_rem(u: &BigUint, d: &BigUint) -> (BigUint, BigUint) {
+    // Montgomery reduction needs an odd modulus, so divide out the powers of
+    // two of `d` first: with d = d' * 2^s, floor(u / d) = floor((u >> s) / d').
+    let s = d.trailing_zeros().unwrap();
+    if s > 0 {
+        let (q, r) = monty_div_rem(&(u >> s), &(d >> s));
+        return (q, (r << s) | u.truncate_to_bits(s));
+    }
+
+    // Horner's rule over `n` digit chunks of `u`, from the top, keeping the
+    // remainder so far in Montgomery form: with acc = r * R, the next value
+    // is (r * R + c) * R, which is acc * R^2 / R + c * R^2 / R.
+    let params = ModulusParams::new(d);
+    let p = &*params.inner;
+    let n = params.len();
+    let mut acc = BigUint::zero();
+    acc.data.resize(n, 0);
+    let mut chunk = BigUint::zero();
+    let mut x = BigUint::zero();
+    let mut y = BigUint::zero();
+    for c in u.data.chunks(n).rev() {
+        chunk.data.clear();
+        chunk.data.extend_from_slice(c);
+        chunk.data.resize(n, 0);
+        params.mul(&mut x, &acc, &p.rr);
+        params.mul(&mut y, &chunk, &p.rr);
+
+        // Both products are below m and padded to n digits, so their sum
+        // needs at most one subtraction and stays padded.
+        core::mem::swap(&mut acc, &mut x);
+        let carry = add_n(&mut acc.data, &y.data);
+        if carry || acc.data.iter().rev().cmp(p.m.data.iter().rev()) != Ordering::Less {
+            sub_n(&mut acc.data, &p.m.data);
+        }
+    }
+    let r = params.redc(&acc);
+
+    // u - r is a multiple of d, so the quotient is (u - r) * d^-1 mod 2^k for
+    // any k with q < 2^k. `d` is odd here, so the inverse exists.
+    let k = u.bits() - d.bits() + 1;
+    let inv = d.inv_mod_2k(k).unwrap();
+    let q = ((u - &r) * inv).truncate_to_bits(k);
+    (q, r)
+}
+
+/// The largest window accepted by `monty_modpow_with_options`.
+const MAX_WINDOW_BITS: usize = 8;
+
+/// Returns the `n` bits of `y` starting at bit `pos`.
+pub(crate) fn window_at(y: &[BigDigit], pos: usize, n: usize) -> usize {
+    let i = pos / big_digit::BITS;
+    let shift = pos % big_digit::BITS;
+    let mut bits = y.get(i).map_or(0, |&d| d >> shift);
+    if shift + n > big_digit::BITS {
+        bits |= y.get(i + 1).map_or(0, |&d| d << (big_digit::BITS - shift));
+    }
+    bits as usize & ((1 << n) - 1)
+}
+
+/// Sets `out` to `powers[i]`, reading every entry of the table so that the
+/// memory access pattern does not depend on `i`.
+fn select_power(powers: &[BigUint], i: usize, out: &mut BigUint) {
+    out.data.clear();
+    out.data.resize(powers[0].data.len(), 0);
+    for (j, p) in powers.iter().enumerate() {
+        let mask = BigDigit::from(j == i).wrapping_neg();
+        for (o, &d) in out.data.iter_mut().zip(p.data.iter()) {
+            *o |= d & mask;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inv_mod_alt() {
+        // k0 must satisfy b * k0 = -1 (mod 2**BITS) for every limb width.
+        let max = BigDigit::max_value();
+        for &b in &[1, 3, 5, 0x1234_5677, max - 2, max] {
+            let k0 = inv_mod_alt(b);
+            assert_eq!(b.wrapping_mul(k0), max, "b = {:#x}", b);
+        }
+    }
 }
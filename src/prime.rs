@@ -1,15 +1,21 @@
 // https://github.com/RustCrypto/RSA/blob/master/src/prime.rs
 //! Implements probabilistic prime checkers.
 
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use byteorder::{BigEndian, ByteOrder};
+use core::cmp;
 use integer::Integer;
-use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
+use num_traits::{FromPrimitive, One, Pow, ToPrimitive, Zero};
 use rand::rngs::StdRng;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 
-use crate::algorithms::jacobi;
-use crate::big_digit;
+use crate::algorithms::{jacobi, rem_digit};
+use crate::big_digit::{self, BigDigit};
+use crate::bigint::into_magnitude;
 use crate::bigrand::RandBigInt;
+use crate::biguint::ln;
+use crate::traits::ModInverse;
 use crate::Sign::Plus;
 use crate::{BigInt, BigUint, IntoBigUint};
 
@@ -97,6 +103,111 @@ pub fn probably_prime(x: &BigUint, n: usize) -> bool {
     probably_prime_miller_rabin(x, n + 1, true) && probably_prime_lucas(x)
 }
 
+/// Where the bases of the randomized tests in [`BigUint::is_prime_with`] come from.
+///
+/// [`BigUint::is_prime_with`]: ../struct.BigUint.html#method.is_prime_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RngPolicy {
+    /// Derive the bases from the candidate, as [`probably_prime`](fn.probably_prime.html)
+    /// does, so that the answer for a given number is reproducible. The generator passed to
+    /// `is_prime_with` is not used.
+    Deterministic,
+    /// Draw the bases from the generator passed to `is_prime_with`. Use this for candidates
+    /// that an adversary may have chosen, since they cannot predict the bases.
+    Random,
+}
+
+/// The tests run by [`BigUint::is_prime_with`].
+///
+/// The default matches [`probably_prime`](fn.probably_prime.html) with `n = 20`. Other
+/// policies, such as the round counts of FIPS 186-4 Appendix C.3, can be expressed by
+/// changing the fields:
+///
+/// ```
+/// use num_bigint_dig::prime::PrimalityConfig;
+///
+/// // FIPS 186-4 Table C.3: 4 rounds of Miller-Rabin plus a Lucas test for 2048-bit primes.
+/// let fips = PrimalityConfig {
+///     mr_rounds: 4,
+///     ..PrimalityConfig::default()
+/// };
+/// ```
+///
+/// [`BigUint::is_prime_with`]: ../struct.BigUint.html#method.is_prime_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrimalityConfig {
+    /// Candidates are first checked for prime factors below this bound, with
+    /// [`BigUint::trial_divide`](../struct.BigUint.html#method.trial_divide).
+    pub trial_division_bound: u32,
+    /// Rounds of Miller-Rabin with random bases.
+    pub mr_rounds: usize,
+    /// Run a Baillie-PSW test: Miller-Rabin with base 2 and a strong Lucas test.
+    pub use_bpsw: bool,
+    /// Where the bases of the randomized tests come from.
+    pub rng_policy: RngPolicy,
+    /// Rounds of [Solovay-Strassen](fn.probably_prime_solovay_strassen.html) with random
+    /// bases.
+    pub solovay_strassen_rounds: usize,
+    /// Rounds of [Grantham's quadratic Frobenius test](fn.probably_prime_frobenius.html),
+    /// with random polynomials.
+    pub frobenius_rounds: usize,
+}
+
+impl Default for PrimalityConfig {
+    fn default() -> PrimalityConfig {
+        PrimalityConfig {
+            trial_division_bound: 64,
+            mr_rounds: 20,
+            use_bpsw: true,
+            rng_policy: RngPolicy::Deterministic,
+            solovay_strassen_rounds: 0,
+            frobenius_rounds: 0,
+        }
+    }
+}
+
+impl BigUint {
+    /// Reports whether `self` is probably prime, running the tests selected by `config`.
+    ///
+    /// Bases for the randomized tests are drawn from `rng` when `config.rng_policy` is
+    /// [`RngPolicy::Random`](prime/enum.RngPolicy.html#variant.Random).
+    ///
+    /// If `self` is prime, this always returns true. The chance that a composite passes
+    /// depends on the tests run; see the functions in the [`prime`](prime/index.html) module.
+    pub fn is_prime_with<R: Rng + ?Sized>(&self, config: &PrimalityConfig, rng: &mut R) -> bool {
+        let n = self;
+        if n < &*BIG_64 {
+            return (PRIME_BIT_MASK & (1 << n.to_u64().unwrap())) != 0;
+        }
+        let bound = u64::from(config.trial_division_bound);
+        if n.trial_divide(bound).is_some() {
+            return false;
+        }
+        if BigUint::from(bound) * bound > *n {
+            return true;
+        }
+        if n.is_even() {
+            return false;
+        }
+
+        if config.use_bpsw && !(probably_prime_miller_rabin(n, 1, true) && probably_prime_lucas(n))
+        {
+            return false;
+        }
+        match config.rng_policy {
+            RngPolicy::Deterministic => random_base_tests(n, config, &mut seeded_rng(n)),
+            RngPolicy::Random => random_base_tests(n, config, rng),
+        }
+    }
+}
+
+/// Runs the tests of `config` that use random bases.
+fn random_base_tests<R: Rng + ?Sized>(n: &BigUint, config: &PrimalityConfig, rng: &mut R) -> bool {
+    miller_rabin(n, config.mr_rounds, false, rng)
+        && solovay_strassen(n, config.solovay_strassen_rounds, rng)
+        && frobenius(n, config.frobenius_rounds, rng)
+}
+
 const NUMBER_OF_PRIMES: usize = 127;
 const PRIME_GAP: [u64; 167] = [
     2, 2, 4, 2, 4, 2, 4, 6, 2, 6, 4, 2, 4, 6, 6, 2, 6, 4, 2, 6, 4, 6, 8, 4, 2, 4, 2, 4, 14, 4, 6,
@@ -164,7 +275,9 @@ pub fn next_prime(n: &BigUint) -> BigUint {
             if !cancel {
                 res += difference;
                 difference = 0;
-                if probably_prime(&res, 20) {
+                if res.trial_divide(u64::from(TRIAL_DIVISION_LIMIT)).is_none()
+                    && probably_prime(&res, 20)
+                {
                     break 'outer;
                 }
             }
@@ -178,11 +291,206 @@ pub fn next_prime(n: &BigUint) -> BigUint {
     res
 }
 
+/// Returns an estimate of π(x), the number of primes less than or equal to x.
+///
+/// The estimate is x / (ln x - 1), which is within 1% of the true count for x ≥ 10⁶ and
+/// becomes more accurate as x grows. Values below 64 are counted exactly.
+pub fn prime_pi_approx(x: &BigUint) -> BigUint {
+    if x < &*BIG_64 {
+        let mask = PRIME_BIT_MASK & (u64::MAX >> (63 - x.to_u64().unwrap()));
+        return BigUint::from(mask.count_ones());
+    }
+
+    (x << PRIME_APPROX_SCALE) / scaled(log_e(x) - 1.0)
+}
+
+/// Returns an estimate of the nth prime, counting 2 as the first.
+///
+/// The estimate is n (ln n + ln ln n - 1), which is the inverse of
+/// [`prime_pi_approx`](fn.prime_pi_approx.html) to first order. The first 18 primes, those
+/// below 64, are returned exactly.
+///
+/// Panics if n is zero.
+pub fn nth_prime_approx(n: &BigUint) -> BigUint {
+    assert!(!n.is_zero(), "there is no zeroth prime");
+
+    if let Some(i) = n.to_u32().filter(|&i| i <= PRIME_BIT_MASK.count_ones()) {
+        let mut mask = PRIME_BIT_MASK;
+        for _ in 1..i {
+            mask &= mask - 1;
+        }
+        return BigUint::from(mask.trailing_zeros());
+    }
+
+    let ln_n = log_e(n);
+    (n * scaled(ln_n + ln(ln_n) - 1.0)) >> PRIME_APPROX_SCALE
+}
+
+/// Fractional bits kept when multiplying or dividing by a floating point factor.
+const PRIME_APPROX_SCALE: usize = 32;
+
+/// Returns f as a fixed point number with `PRIME_APPROX_SCALE` fractional bits.
+fn scaled(f: f64) -> BigUint {
+    BigUint::from_f64(f * (1u64 << PRIME_APPROX_SCALE) as f64).unwrap()
+}
+
+/// Returns the natural logarithm of a non-zero x, computed from its top 64 bits so that it
+/// doesn't overflow for values beyond the range of f64.
+fn log_e(x: &BigUint) -> f64 {
+    x.ln_f64()
+}
+
+/// Numbers covered by one segment of `PrimesInRange`.
+const SIEVE_SEGMENT_LEN: usize = 1 << 15;
+
+/// Largest prime used for sieving. Above its square the sieve only removes numbers with small
+/// factors, and the survivors are checked with `probably_prime`.
+const SIEVE_PRIME_LIMIT: u32 = 1 << 16;
+
+/// Returns an iterator over the primes p with lo ≤ p < hi, in increasing order.
+///
+/// The range is sieved in segments of 2¹⁵ numbers with the primes up to √hi, so memory use
+/// doesn't depend on the width of the range. When √hi is beyond 2¹⁶, sieving only
+/// removes numbers with a factor below 2¹⁶, and each survivor is confirmed with
+/// [`probably_prime`](fn.probably_prime.html). The latter makes it practical to count primes
+/// in a window above a large offset.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::primes_in_range;
+/// use num_bigint_dig::BigUint;
+///
+/// let lo = BigUint::from(1u32) << 100;
+/// let hi = &lo + 1000u32;
+/// let primes: Vec<BigUint> = primes_in_range(&lo, &hi).collect();
+/// assert_eq!(primes.len(), 12);
+/// assert_eq!(primes[0], &lo + 277u32);
+/// ```
+pub fn primes_in_range(lo: &BigUint, hi: &BigUint) -> PrimesInRange {
+    let (limit, exact) = if hi.is_zero() {
+        (0, true)
+    } else {
+        match (hi - &*BIG_1).sqrt().to_u32() {
+            Some(root) if root <= SIEVE_PRIME_LIMIT => (root, true),
+            _ => (SIEVE_PRIME_LIMIT, false),
+        }
+    };
+
+    PrimesInRange {
+        start: lo.clone(),
+        end: hi.clone(),
+        segment: Vec::new(),
+        pos: 0,
+        sieving_primes: small_primes(limit),
+        exact,
+    }
+}
+
+/// An iterator over the primes in a range, created by
+/// [`primes_in_range`](fn.primes_in_range.html).
+#[derive(Clone, Debug)]
+pub struct PrimesInRange {
+    // first number of the current segment
+    start: BigUint,
+    end: BigUint,
+    // segment[i] is false if start + i is known to be composite
+    segment: Vec<bool>,
+    pos: usize,
+    sieving_primes: Vec<u32>,
+    // whether every number left in the segment is prime
+    exact: bool,
+}
+
+impl PrimesInRange {
+    /// Moves on to the next segment, returning false if the range is exhausted.
+    fn next_segment(&mut self) -> bool {
+        self.start += self.segment.len();
+        if self.start >= self.end {
+            return false;
+        }
+
+        let len = match (&self.end - &self.start).to_usize() {
+            Some(rest) if rest < SIEVE_SEGMENT_LEN => rest,
+            _ => SIEVE_SEGMENT_LEN,
+        };
+        self.segment.clear();
+        self.segment.resize(len, true);
+        self.pos = 0;
+
+        if let Some(start) = self.start.to_usize().filter(|&s| s < 2) {
+            for i in start..2.min(start + len) {
+                self.segment[i - start] = false;
+            }
+        }
+
+        for &p in &self.sieving_primes {
+            let p2 = BigUint::from(u64::from(p) * u64::from(p));
+            let first = if self.start <= p2 {
+                match (p2 - &self.start).to_usize() {
+                    Some(first) if first < len => first,
+                    // The primes are increasing, so later squares are even further out.
+                    _ => break,
+                }
+            } else {
+                let r = rem_digit(&self.start.data, p as BigDigit) as usize;
+                (p as usize - r) % p as usize
+            };
+            for i in (first..len).step_by(p as usize) {
+                self.segment[i] = false;
+            }
+        }
+        true
+    }
+}
+
+impl Iterator for PrimesInRange {
+    type Item = BigUint;
+
+    fn next(&mut self) -> Option<BigUint> {
+        loop {
+            while self.pos < self.segment.len() {
+                let i = self.pos;
+                self.pos += 1;
+                if self.segment[i] {
+                    let candidate = &self.start + i;
+                    if self.exact || probably_prime(&candidate, 20) {
+                        return Some(candidate);
+                    }
+                }
+            }
+            if !self.next_segment() {
+                return None;
+            }
+        }
+    }
+}
+
+/// Returns the primes up to and including limit, by the sieve of Eratosthenes.
+fn small_primes(limit: u32) -> Vec<u32> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if !composite[n] {
+            primes.push(n as u32);
+            for m in (n * n..=limit).step_by(n) {
+                composite[m] = true;
+            }
+        }
+    }
+    primes
+}
+
 /// Reports whether n passes reps rounds of the Miller-Rabin primality test, using pseudo-randomly chosen bases.
 /// If `force2` is true, one of the rounds is forced to use base 2.
 ///
 /// See Handbook of Applied Cryptography, p. 139, Algorithm 4.24.
 pub fn probably_prime_miller_rabin(n: &BigUint, reps: usize, force2: bool) -> bool {
+    miller_rabin(n, reps, force2, &mut seeded_rng(n))
+}
+
+fn miller_rabin<R: Rng + ?Sized>(n: &BigUint, reps: usize, force2: bool, rng: &mut R) -> bool {
     // println!("miller-rabin: {}", n);
     let nm1 = n - &*BIG_1;
     // determine q, k such that nm1 = q << k
@@ -190,16 +498,6 @@ pub fn probably_prime_miller_rabin(n: &BigUint, reps: usize, force2: bool) -> bo
     let q = &nm1 >> k;
     let nm3 = n - &*BIG_3;
 
-    let mut seed_vec = vec![0u8; 8];
-    BigEndian::write_uint(
-        seed_vec.as_mut_slice(),
-        n.get_limb(0) as u64,
-        big_digit::BITS / 8,
-    );
-    let mut seed = [0u8; 32];
-    seed[0..8].copy_from_slice(&seed_vec[..]);
-    let mut rng = StdRng::from_seed(seed);
-
     'nextrandom: for i in 0..reps {
         let x = if i == reps - 1 && force2 {
             BIG_2.clone()
@@ -227,6 +525,200 @@ pub fn probably_prime_miller_rabin(n: &BigUint, reps: usize, force2: bool) -> bo
     true
 }
 
+/// Reports whether n passes reps rounds of the Solovay-Strassen primality test, using
+/// pseudo-randomly chosen bases.
+///
+/// Each round picks a base a and checks Euler's criterion, a^((n-1)/2) ≡ (a/n) (mod n),
+/// where (a/n) is the Jacobi symbol. A composite passes a round with probability at most ½,
+/// against ¼ for Miller-Rabin, so this is mostly useful where a standard calls for it.
+///
+/// See Solovay and Strassen, "A Fast Monte-Carlo Test for Primality",
+/// SIAM Journal on Computing 6(1), 1977, pp. 84-85.
+pub fn probably_prime_solovay_strassen(n: &BigUint, reps: usize) -> bool {
+    solovay_strassen(n, reps, &mut seeded_rng(n))
+}
+
+fn solovay_strassen<R: Rng + ?Sized>(n: &BigUint, reps: usize, rng: &mut R) -> bool {
+    if n < &*BIG_64 {
+        return (PRIME_BIT_MASK & (1 << n.to_u64().unwrap())) != 0;
+    }
+    if n.is_even() {
+        return false;
+    }
+
+    let nm1 = n - &*BIG_1;
+    let e = &nm1 >> 1;
+    let nm3 = n - &*BIG_3;
+    let n_int = BigInt::from_biguint(Plus, n.clone());
+
+    for _ in 0..reps {
+        let a = rng.gen_biguint_below(&nm3) + &*BIG_2;
+        let j = jacobi(&BigInt::from_biguint(Plus, a.clone()), &n_int);
+        if j == 0 {
+            return false;
+        }
+
+        let x = a.modpow(&e, n);
+        if (j == 1 && !x.is_one()) || (j == -1 && x != nm1) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Reports whether n passes reps rounds of Grantham's quadratic Frobenius test, using
+/// pseudo-randomly chosen polynomials.
+///
+/// Each round picks f(x) = x² - bx - c with (b²+4c / n) = -1 and (-c / n) = 1, and works in
+/// the ring (Z/nZ)[x] / f(x), where the Frobenius map of a prime n must send x to its
+/// conjugate b - x. Prime factors below 50000 are first ruled out by trial division, after
+/// which a composite passes a round with probability less than 1/7710, at about three times
+/// the cost of a Miller-Rabin round. This makes it a good extra check on top of
+/// [`probably_prime`](fn.probably_prime.html) when generating primes that need high
+/// assurance:
+///
+/// ```
+/// use num_bigint_dig::prime::{probably_prime, probably_prime_frobenius};
+/// use num_bigint_dig::BigUint;
+///
+/// // Curve25519: 2^255 - 19
+/// let p = (BigUint::from(1u32) << 255) - 19u32;
+/// assert!(probably_prime(&p, 20) && probably_prime_frobenius(&p, 1));
+/// ```
+///
+/// See Grantham, "A Probable Prime Test With High Confidence", Journal of Number Theory 72(1),
+/// September 1998, pp. 32-47.
+pub fn probably_prime_frobenius(n: &BigUint, reps: usize) -> bool {
+    frobenius(n, reps, &mut seeded_rng(n))
+}
+
+/// Primes below this bound are tried by trial division before the Frobenius rounds.
+const FROBENIUS_TRIAL_BOUND: u64 = 50_000;
+
+fn frobenius<R: Rng + ?Sized>(n: &BigUint, reps: usize, rng: &mut R) -> bool {
+    if n < &*BIG_64 {
+        return (PRIME_BIT_MASK & (1 << n.to_u64().unwrap())) != 0;
+    }
+    if n.is_even() {
+        return false;
+    }
+    // Trial division, which also rules out the small factors the test is weak against.
+    for p in (3u32..64).filter(|&p| PRIME_BIT_MASK & (1 << p) != 0) {
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+    // There is no suitable polynomial for a square n.
+    let root = n.sqrt();
+    if &(&root * &root) == n {
+        return false;
+    }
+    // The error bound of each round assumes n has no prime factor below 50000.
+    if reps > 0 {
+        if n.trial_divide(FROBENIUS_TRIAL_BOUND).is_some() {
+            return false;
+        }
+        if root.cmp_u64(FROBENIUS_TRIAL_BOUND) == cmp::Ordering::Less {
+            return true;
+        }
+    }
+
+    let nm1 = n - &*BIG_1;
+    let n_int = BigInt::from_biguint(Plus, n.clone());
+    let half = (n + &*BIG_1) >> 1;
+    let n2m1 = n * n - &*BIG_1;
+    let r = n2m1.trailing_zeros().unwrap();
+    let s = &n2m1 >> r;
+
+    for _ in 0..reps {
+        // Choose f(x) = x² - bx - c.
+        let (b, c) = loop {
+            let b = rng.gen_biguint_below(n);
+            let c = rng.gen_biguint_below(n);
+            let disc = (&b * &b + (&c << 2)) % n;
+            let neg_c = (n - &c) % n;
+            if disc.is_zero() || neg_c.is_zero() {
+                continue;
+            }
+            let j1 = jacobi(&BigInt::from_biguint(Plus, disc), &n_int);
+            let j2 = jacobi(&BigInt::from_biguint(Plus, neg_c), &n_int);
+            if j1 == 0 || j2 == 0 {
+                // A non-zero value shares a factor with n.
+                return false;
+            }
+            if j1 == -1 && j2 == 1 {
+                break (b, c);
+            }
+        };
+        let ring = QuadraticRing { n, b: &b, c: &c };
+
+        // x^((n+1)/2) must be in Z/nZ, and x^(n+1) must be -c.
+        let (u, v) = ring.pow_x(&half);
+        if !v.is_zero() {
+            return false;
+        }
+        if !((&u * &u + &c) % n).is_zero() {
+            return false;
+        }
+
+        // With n² - 1 = 2^r s, either x^s = 1 or x^(2^j s) = -1 for some 0 ≤ j ≤ r-2.
+        let (mut u, mut v) = ring.pow_x(&s);
+        if !(u.is_one() && v.is_zero()) {
+            let mut found = false;
+            for _ in 0..r - 1 {
+                if u == nm1 && v.is_zero() {
+                    found = true;
+                    break;
+                }
+                let (u2, v2) = ring.mul((&u, &v), (&u, &v));
+                u = u2;
+                v = v2;
+            }
+            if !found {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// The ring (Z/nZ)[x] / (x² - bx - c), whose elements are written u + vx.
+struct QuadraticRing<'a> {
+    n: &'a BigUint,
+    b: &'a BigUint,
+    c: &'a BigUint,
+}
+
+impl<'a> QuadraticRing<'a> {
+    /// Multiplies two elements, using x² = bx + c.
+    fn mul(&self, x: (&BigUint, &BigUint), y: (&BigUint, &BigUint)) -> (BigUint, BigUint) {
+        let vv = (x.1 * y.1) % self.n;
+        let u = (x.0 * y.0 + self.c * &vv) % self.n;
+        let v = (x.0 * y.1 + x.1 * y.0 + self.b * &vv) % self.n;
+        (u, v)
+    }
+
+    /// Returns x^e.
+    fn pow_x(&self, e: &BigUint) -> (BigUint, BigUint) {
+        let mut u = BigUint::one();
+        let mut v = BigUint::zero();
+        for i in (0..e.bits()).rev() {
+            let (u2, v2) = self.mul((&u, &v), (&u, &v));
+            u = u2;
+            v = v2;
+            if is_bit_set(e, i) {
+                // (u + vx) x = cv + (u + bv) x
+                let u2 = (self.c * &v) % self.n;
+                v = (&u + self.b * &v) % self.n;
+                u = u2;
+            }
+        }
+        (u, v)
+    }
+}
+
 /// Reports whether n passes the "almost extra strong" Lucas probable prime test,
 /// using Baillie-OEIS parameter selection. This corresponds to "AESLPSP" on Jacobsen's tables (link below).
 /// The combination of this test and a Miller-Rabin/Fermat test with base 2 gives a Baillie-PSW test.
@@ -419,69 +911,1107 @@ pub fn probably_prime_lucas(n: &BigUint) -> bool {
     false
 }
 
-/// Checks if the i-th bit is set
-#[inline]
-fn is_bit_set(x: &BigUint, i: usize) -> bool {
-    get_bit(x, i) == 1
-}
+/// Returns the Lucas sequences `(U_k(P, Q), V_k(P, Q))`, reduced into `[0, m)`
+/// when a modulus `m` is given.
+///
+/// The sequences are defined by
+///
+/// ```text
+/// U(0) = 0, U(1) = 1, U(k) = P U(k-1) - Q U(k-2)
+/// V(0) = 2, V(1) = P, V(k) = P V(k-1) - Q V(k-2)
+/// ```
+///
+/// and computed by a binary chain over the bits of `k`, using
+///
+/// ```text
+/// U(2k) = 2 U(k) U(k+1) - P U(k)²
+/// U(2k+1) = U(k+1)² - Q U(k)²
+/// U(2k+2) = P U(k+1)² - 2Q U(k) U(k+1)
+/// ```
+///
+/// and finally V(k) = 2 U(k+1) - P U(k). Nothing is divided, so any modulus
+/// works, even or not coprime to the discriminant P² - 4Q.
+///
+/// Without a modulus the length of the values grows linearly with `k`, so large `k` needs one.
+///
+/// Panics if the modulus is zero.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::lucas_sequence;
+/// use num_bigint_dig::{BigInt, BigUint};
+///
+/// // With P = 1 and Q = -1 these are the Fibonacci and Lucas numbers.
+/// let (f, l) = lucas_sequence(&BigInt::from(1), &BigInt::from(-1), &BigUint::from(10u32), None);
+/// assert_eq!((f, l), (BigInt::from(55), BigInt::from(123)));
+/// ```
+pub fn lucas_sequence(
+    p: &BigInt,
+    q: &BigInt,
+    k: &BigUint,
+    modulus: Option<&BigUint>,
+) -> (BigInt, BigInt) {
+    let m = modulus.map(|m| {
+        assert!(!m.is_zero(), "divide by zero!");
+        BigInt::from_biguint(Plus, m.clone())
+    });
+    let reduce = |x: BigInt| match &m {
+        Some(m) => x.mod_floor(m),
+        None => x,
+    };
 
-/// Returns the i-th bit.
-#[inline]
-fn get_bit(x: &BigUint, i: usize) -> u8 {
-    let j = i / big_digit::BITS;
-    // if is out of range of the set words, it is always false.
-    if i >= x.bits() {
-        return 0;
+    // (U(j), U(j+1)) for the prefix j of the bits of k seen so far.
+    let mut uj = BigInt::zero();
+    let mut uj1 = reduce(BigInt::one());
+    for i in (0..k.bits()).rev() {
+        let a = &uj * &uj;
+        let b = &uj1 * &uj1;
+        let c = &uj * &uj1;
+        if is_bit_set(k, i) {
+            uj = reduce(&b - q * &a);
+            uj1 = reduce(p * &b - ((q * &c) << 1));
+        } else {
+            uj1 = reduce(&b - q * &a);
+            uj = reduce((c << 1) - p * &a);
+        }
     }
 
-    (x.get_limb(j) >> (i % big_digit::BITS) & 1) as u8
+    let vk = reduce((&uj1 << 1) - p * &uj);
+    (uj, vk)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::vec::Vec;
-    // use RandBigInt;
-
-    use crate::biguint::ToBigUint;
+/// A Pratt certificate: a proof that a number is prime which can be checked without trusting
+/// the code that produced it.
+///
+/// A certificate for p names a witness a and the distinct prime factors q of p - 1, each with
+/// its own certificate. It is valid when a^(p-1) ≡ 1 (mod p), a^((p-1)/q) ≢ 1 (mod p) for
+/// every q, and the q account for all of p - 1. Then a has order p - 1, so p is prime. The
+/// recursion ends at 2, which needs no proof.
+///
+/// See Pratt, "Every Prime Has a Succinct Certificate", SIAM Journal on Computing 4(3), 1975,
+/// pp. 214-220.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrattCertificate {
+    prime: BigUint,
+    witness: BigUint,
+    factors: Vec<PrattCertificate>,
+}
 
-    lazy_static! {
-        static ref PRIMES: Vec<&'static str> = vec![
-        "2",
-        "3",
-        "5",
-        "7",
-        "11",
+impl PrattCertificate {
+    /// Returns the number this certificate proves prime.
+    pub fn prime(&self) -> &BigUint {
+        &self.prime
+    }
 
-        "13756265695458089029",
-        "13496181268022124907",
-        "10953742525620032441",
-        "17908251027575790097",
+    /// Returns the witness, an element of order p - 1 modulo p.
+    pub fn witness(&self) -> &BigUint {
+        &self.witness
+    }
 
-        // https://golang.org/issue/638
-        "18699199384836356663",
+    /// Returns the certificates of the distinct prime factors of p - 1.
+    pub fn factors(&self) -> &[PrattCertificate] {
+        &self.factors
+    }
 
-        "98920366548084643601728869055592650835572950932266967461790948584315647051443",
-        "94560208308847015747498523884063394671606671904944666360068158221458669711639",
+    /// Checks the certificate, and recursively those of the factors of p - 1.
+    pub fn verify(&self) -> bool {
+        let p = &self.prime;
+        if p == &*BIG_2 {
+            return self.factors.is_empty();
+        }
+        if p < &*BIG_2 || self.witness.is_zero() || &self.witness >= p {
+            return false;
+        }
 
-        // http://primes.utm.edu/lists/small/small3.html
-        "449417999055441493994709297093108513015373787049558499205492347871729927573118262811508386655998299074566974373711472560655026288668094291699357843464363003144674940345912431129144354948751003607115263071543163",
-        "230975859993204150666423538988557839555560243929065415434980904258310530753006723857139742334640122533598517597674807096648905501653461687601339782814316124971547968912893214002992086353183070342498989426570593",
-        "5521712099665906221540423207019333379125265462121169655563495403888449493493629943498064604536961775110765377745550377067893607246020694972959780839151452457728855382113555867743022746090187341871655890805971735385789993",
-        "203956878356401977405765866929034577280193993314348263094772646453283062722701277632936616063144088173312372882677123879538709400158306567338328279154499698366071906766440037074217117805690872792848149112022286332144876183376326512083574821647933992961249917319836219304274280243803104015000563790123",
-        // ECC primes: http://tools.ietf.org/html/draft-ladd-safecurves-02
-        "3618502788666131106986593281521497120414687020801267626233049500247285301239",                                                                                  // Curve1174: 2^251-9
-        "57896044618658097711785492504343953926634992332820282019728792003956564819949",                                                                                 // Curve25519: 2^255-19
-        "9850501549098619803069760025035903451269934817616361666987073351061430442874302652853566563721228910201656997576599",                                           // E-382: 2^382-105
-        "42307582002575910332922579714097346549017899709713998034217522897561970639123926132812109468141778230245837569601494931472367",                                 // Curve41417: 2^414-17
-        "6864797660130609714981900799081393217269435300143305409394463459185543183397656052122559640661454554977296311391480858037121987999716643812574028291115057151", // E-521: 2^521-1
-        ];
+        let pm1 = p - &*BIG_1;
+        if !self.witness.modpow(&pm1, p).is_one() {
+            return false;
+        }
 
-        static ref COMPOSITES: Vec<&'static str> = vec![
-            "0",
-            "1",
+        let mut rest = pm1.clone();
+        for (i, q) in self.factors.iter().enumerate() {
+            if self.factors[..i].iter().any(|f| f.prime == q.prime) || !q.verify() {
+                return false;
+            }
+            let (mut quo, rem) = rest.div_rem(&q.prime);
+            if !rem.is_zero() {
+                return false;
+            }
+            while (&quo % &q.prime).is_zero() {
+                quo /= &q.prime;
+            }
+            rest = quo;
 
-            "21284175091214687912771199898307297748211672914763848041968395774954376176754",
+            if self.witness.modpow(&(&pm1 / &q.prime), p).is_one() {
+                return false;
+            }
+        }
+        rest.is_one()
+    }
+}
+
+/// Proves that n is prime, returning a certificate that can be checked with
+/// [`PrattCertificate::verify`], or `None` if n is not prime.
+///
+/// [`PrattCertificate::verify`]: struct.PrattCertificate.html#method.verify
+///
+/// This needs the prime factors of n - 1, and recursively of each of their predecessors,
+/// which are found by trial division and Pollard's rho method. It is fast when n - 1 is
+/// smooth or has at most one large prime factor; otherwise factoring may take very long.
+pub fn prove_prime(n: &BigUint) -> Option<PrattCertificate> {
+    if n == &*BIG_2 {
+        return Some(PrattCertificate {
+            prime: n.clone(),
+            witness: BIG_1.clone(),
+            factors: Vec::new(),
+        });
+    }
+    if !probably_prime(n, 20) {
+        return None;
+    }
+
+    let nm1 = n - &*BIG_1;
+    let primes = nm1
+        .factorize(&PROOF_EFFORT)
+        .primes
+        .into_keys()
+        .collect::<Vec<_>>();
+    let factors = primes.iter().map(prove_prime).collect::<Option<Vec<_>>>()?;
+
+    // A prime has φ(n - 1) witnesses, and a small one is usually found quickly.
+    let mut witness = BIG_2.clone();
+    while &witness < n {
+        if witness.modpow(&nm1, n).is_one()
+            && primes
+                .iter()
+                .all(|q| !witness.modpow(&(&nm1 / q), n).is_one())
+        {
+            return Some(PrattCertificate {
+                prime: n.clone(),
+                witness,
+                factors,
+            });
+        }
+        witness += &*BIG_1;
+    }
+    None
+}
+
+/// Trial division bound used before switching to Pollard's rho method, and before running
+/// probable prime tests on generated candidates.
+pub(crate) const TRIAL_DIVISION_LIMIT: u32 = 1 << 12;
+
+/// Effort used to factor p - 1 in [`prove_prime`](fn.prove_prime.html), which has to succeed.
+const PROOF_EFFORT: FactorEffort = FactorEffort {
+    trial_division_bound: TRIAL_DIVISION_LIMIT as u64,
+    rho_iterations: u64::MAX,
+    ecm_curves: 0,
+    ecm_bound: 0,
+};
+
+/// How much work [`BigUint::factorize`] may spend on a number.
+///
+/// Factoring runs trial division, then Pollard's rho method on each remaining composite, and
+/// then, if enabled, Lenstra's elliptic curve method (ECM) on whatever rho could not split.
+/// Trial division finds all factors below its bound, rho is fast for factors up to about 20
+/// digits, and ECM reaches somewhat further, depending on its bound and number of curves.
+///
+/// [`BigUint::factorize`]: ../struct.BigUint.html#method.factorize
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FactorEffort {
+    /// Prime factors below this bound are found by
+    /// [`BigUint::trial_divide`](../struct.BigUint.html#method.trial_divide).
+    pub trial_division_bound: u64,
+    /// Iterations of Pollard's rho method spent on each composite before giving up on it.
+    pub rho_iterations: u64,
+    /// Random curves tried with ECM for each composite that rho could not split. Zero
+    /// disables ECM.
+    pub ecm_curves: usize,
+    /// The stage 1 bound of ECM. A curve finds the factor p when the order of the curve
+    /// modulo p only has prime power factors below this bound.
+    pub ecm_bound: u32,
+}
+
+impl Default for FactorEffort {
+    fn default() -> FactorEffort {
+        FactorEffort {
+            trial_division_bound: 1 << 16,
+            rho_iterations: 1 << 20,
+            ecm_curves: 0,
+            ecm_bound: 2000,
+        }
+    }
+}
+
+/// The result of [`BigUint::factorize`]: the prime factors found, with their exponents, and
+/// any composite factors that could not be split within the effort allowed.
+///
+/// The product of the prime powers and the composites is the number that was factored.
+/// Primes are only established by [`probably_prime`](fn.probably_prime.html) with 20
+/// rounds.
+///
+/// [`BigUint::factorize`]: ../struct.BigUint.html#method.factorize
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Factorization {
+    primes: BTreeMap<BigUint, usize>,
+    composites: Vec<BigUint>,
+}
+
+impl Factorization {
+    /// Returns the prime factors found, mapped to their exponents.
+    pub fn primes(&self) -> &BTreeMap<BigUint, usize> {
+        &self.primes
+    }
+
+    /// Returns the composite factors that were left, in no particular order. A composite
+    /// that divides the number more than once is listed once for each time.
+    pub fn composites(&self) -> &[BigUint] {
+        &self.composites
+    }
+
+    /// Reports whether the number was factored into primes completely.
+    pub fn is_complete(&self) -> bool {
+        self.composites.is_empty()
+    }
+
+    /// Returns Euler's totient φ(n), the number of integers in [1, n] coprime to n, or
+    /// `None` if the factorization is not complete.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::prime::FactorEffort;
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// let factors = BigUint::from(360u32).factorize(&FactorEffort::default());
+    /// assert_eq!(factors.totient(), Some(BigUint::from(96u32)));
+    /// assert_eq!(factors.carmichael_lambda(), Some(BigUint::from(12u32)));
+    /// assert_eq!(factors.sigma(1), Some(BigUint::from(1170u32)));
+    /// assert_eq!(factors.num_divisors(), Some(BigUint::from(24u32)));
+    /// ```
+    pub fn totient(&self) -> Option<BigUint> {
+        self.fold_prime_powers(BigUint::one(), |acc, p, e| {
+            acc * Pow::pow(p, e - 1) * (p - &*BIG_1)
+        })
+    }
+
+    /// Returns the Carmichael function λ(n), the smallest m such that a^m ≡ 1 (mod n) for
+    /// every a coprime to n, or `None` if the factorization is not complete.
+    pub fn carmichael_lambda(&self) -> Option<BigUint> {
+        self.fold_prime_powers(BigUint::one(), |acc, p, e| {
+            let lambda = if p == &*BIG_2 && e >= 3 {
+                BigUint::one() << (e - 2) as usize
+            } else {
+                Pow::pow(p, e - 1) * (p - &*BIG_1)
+            };
+            acc.lcm(&lambda)
+        })
+    }
+
+    /// Returns the divisor function σₖ(n), the sum of the kth powers of the divisors of n,
+    /// or `None` if the factorization is not complete.
+    ///
+    /// σ₀ is the number of divisors and σ₁ their sum.
+    pub fn sigma(&self, k: u32) -> Option<BigUint> {
+        if k == 0 {
+            return self.num_divisors();
+        }
+        self.fold_prime_powers(BigUint::one(), |acc, p, e| {
+            // 1 + p^k + ... + p^(ek) = (p^(k(e+1)) - 1) / (p^k - 1)
+            let pk = Pow::pow(p, k);
+            acc * ((Pow::pow(&pk, e + 1) - &*BIG_1) / (pk - &*BIG_1))
+        })
+    }
+
+    /// Returns the number of divisors of n, or `None` if the factorization is not complete.
+    pub fn num_divisors(&self) -> Option<BigUint> {
+        self.fold_prime_powers(BigUint::one(), |acc, _, e| acc * (e + 1))
+    }
+
+    /// Folds f over the prime powers p^e of a complete factorization.
+    fn fold_prime_powers<F>(&self, init: BigUint, mut f: F) -> Option<BigUint>
+    where
+        F: FnMut(BigUint, &BigUint, u32) -> BigUint,
+    {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(
+            self.primes
+                .iter()
+                .fold(init, |acc, (p, &e)| f(acc, p, e as u32)),
+        )
+    }
+
+    fn add_prime(&mut self, p: BigUint, exp: usize) {
+        *self.primes.entry(p).or_insert(0) += exp;
+    }
+
+    /// Factors m, which has no prime factors below `effort.trial_division_bound`.
+    fn split(&mut self, m: BigUint, effort: &FactorEffort) {
+        if m.is_one() {
+            return;
+        }
+        if probably_prime(&m, 20) {
+            self.add_prime(m, 1);
+            return;
+        }
+
+        // Neither rho nor ECM separates the factors of a perfect power. Since the roots have
+        // no small factors either, only a few exponents are possible.
+        let min_root_bits = 63 - effort.trial_division_bound.max(2).leading_zeros() as usize;
+        for k in 2..=(m.bits() / min_root_bits) as u32 {
+            let root = m.nth_root(k);
+            if Pow::pow(&root, k) == m {
+                for _ in 0..k {
+                    self.split(root.clone(), effort);
+                }
+                return;
+            }
+        }
+
+        let d = pollard_rho(&m, effort.rho_iterations)
+            .or_else(|| ecm(&m, effort.ecm_curves, effort.ecm_bound));
+        match d {
+            Some(d) => {
+                self.split(&m / &d, effort);
+                self.split(d, effort);
+            }
+            None => self.composites.push(m),
+        }
+    }
+}
+
+impl BigUint {
+    /// Factors `self` into primes, spending at most the work allowed by `effort`.
+    ///
+    /// Factors that cannot be split within the effort are reported in
+    /// [`Factorization::composites`](prime/struct.Factorization.html#method.composites).
+    ///
+    /// Panics if `self` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use num_bigint_dig::prime::FactorEffort;
+    /// use num_bigint_dig::BigUint;
+    ///
+    /// // 2^64 + 1 = 274177 * 67280421310721
+    /// let n = (BigUint::from(1u32) << 64) + 1u32;
+    /// let factors = n.factorize(&FactorEffort::default());
+    /// assert!(factors.is_complete());
+    /// assert_eq!(factors.primes().len(), 2);
+    /// assert_eq!(factors.primes()[&BigUint::from(274177u32)], 1);
+    /// ```
+    pub fn factorize(&self, effort: &FactorEffort) -> Factorization {
+        assert!(!self.is_zero(), "zero has no factorization");
+
+        let mut result = Factorization::default();
+        let mut n = self.clone();
+        while let Some(d) = n.trial_divide(effort.trial_division_bound) {
+            let mut exp = 0;
+            while (&n % d).is_zero() {
+                n /= d;
+                exp += 1;
+            }
+            result.add_prime(BigUint::from(d), exp);
+        }
+        result.split(n, effort);
+        result
+    }
+}
+
+/// Returns the multiplicative order of a modulo n, the smallest k > 0 with a^k ≡ 1 (mod n),
+/// or `None` if a is not coprime to n.
+///
+/// `lambda` is the factorization of a multiple of the order, usually of the Carmichael
+/// function λ(n) (see [`Factorization::carmichael_lambda`]) or of φ(n). The order is found by
+/// dividing out its prime factors for as long as the power stays 1.
+///
+/// Panics if n is zero, if `lambda` is not complete, or if a^λ ≢ 1 (mod n).
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::{multiplicative_order, FactorEffort};
+/// use num_bigint_dig::BigUint;
+///
+/// let p = BigUint::from(1_000_000_007u32);
+/// let lambda = (&p - 1u32).factorize(&FactorEffort::default());
+/// assert_eq!(
+///     multiplicative_order(&BigUint::from(2u32), &p, &lambda),
+///     Some(BigUint::from(500_000_003u32))
+/// );
+/// ```
+///
+/// [`Factorization::carmichael_lambda`]: struct.Factorization.html#method.carmichael_lambda
+pub fn multiplicative_order(a: &BigUint, n: &BigUint, lambda: &Factorization) -> Option<BigUint> {
+    assert!(!n.is_zero(), "divide by zero!");
+    assert!(
+        lambda.is_complete(),
+        "the factorization of λ must be complete"
+    );
+
+    if !a.gcd(n).is_one() {
+        return None;
+    }
+    let one = &*BIG_1 % n;
+    let mut order = lambda
+        .fold_prime_powers(BigUint::one(), |acc, p, e| acc * Pow::pow(p, e))
+        .unwrap();
+    assert!(
+        a.modpow(&order, n) == one,
+        "λ is not a multiple of the order"
+    );
+
+    for (p, &e) in lambda.primes() {
+        for _ in 0..e {
+            let candidate = &order / p;
+            if a.modpow(&candidate, n) != one {
+                break;
+            }
+            order = candidate;
+        }
+    }
+    Some(order)
+}
+
+/// Reports whether g generates the multiplicative group modulo the prime p, that is whether
+/// g^((p - 1) / q) ≢ 1 (mod p) for every prime factor q of p - 1.
+///
+/// `p_minus_1` is the factorization of p - 1.
+///
+/// Panics if the factorization is not complete.
+pub fn is_primitive_root(g: &BigUint, p: &BigUint, p_minus_1: &Factorization) -> bool {
+    assert!(
+        p_minus_1.is_complete(),
+        "the factorization of p - 1 must be complete"
+    );
+
+    let pm1 = p - &*BIG_1;
+    !(g % p).is_zero()
+        && p_minus_1
+            .primes()
+            .keys()
+            .all(|q| !g.modpow(&(&pm1 / q), p).is_one())
+}
+
+/// Returns a random primitive root modulo the prime p, a generator of its multiplicative
+/// group.
+///
+/// `p_minus_1` is the factorization of p - 1. Candidates are drawn from `rng` until one
+/// passes [`is_primitive_root`](fn.is_primitive_root.html). A fraction φ(p - 1) / (p - 1)
+/// of them do, so only a few draws are needed.
+///
+/// Panics if p is not prime or the factorization is not complete.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::{find_primitive_root, is_primitive_root, FactorEffort};
+/// use num_bigint_dig::BigUint;
+///
+/// let p = BigUint::from(1_000_000_007u32);
+/// let p_minus_1 = (&p - 1u32).factorize(&FactorEffort::default());
+/// let g = find_primitive_root(&p, &p_minus_1, &mut rand::thread_rng());
+/// assert!(is_primitive_root(&g, &p, &p_minus_1));
+/// ```
+pub fn find_primitive_root<R: Rng + ?Sized>(
+    p: &BigUint,
+    p_minus_1: &Factorization,
+    rng: &mut R,
+) -> BigUint {
+    assert!(probably_prime(p, 20), "p must be prime");
+    assert!(
+        p_minus_1.is_complete(),
+        "the factorization of p - 1 must be complete"
+    );
+
+    if p == &*BIG_2 {
+        return BIG_1.clone();
+    }
+    loop {
+        let g = rng.gen_biguint_range(&BIG_2, p);
+        if is_primitive_root(&g, p, p_minus_1) {
+            return g;
+        }
+    }
+}
+
+/// Returns x in [0, order) with g^x ≡ h (mod n), using Pollard's rho method for logarithms,
+/// or `None` if none was found within max_iterations steps.
+///
+/// `order` must be the multiplicative order of g modulo n, such as the result of
+/// [`multiplicative_order`](fn.multiplicative_order.html). The walk is Teske's r-adding
+/// walk with Brent's cycle detection, so it takes about √order steps and constant memory.
+/// Each collision gives a linear congruence for x, whose solutions are checked directly;
+/// collisions with too many solutions to check restart the walk from a new point.
+///
+/// Panics if n or `order` is zero.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::discrete_log_rho;
+/// use num_bigint_dig::BigUint;
+///
+/// // 2 has order 500000003 modulo 1000000007.
+/// let (g, n) = (BigUint::from(2u32), BigUint::from(1_000_000_007u32));
+/// let h = g.modpow(&BigUint::from(123_456_789u32), &n);
+/// let order = BigUint::from(500_000_003u32);
+/// assert_eq!(
+///     discrete_log_rho(&g, &h, &n, &order, 1 << 24),
+///     Some(BigUint::from(123_456_789u32))
+/// );
+/// ```
+pub fn discrete_log_rho(
+    g: &BigUint,
+    h: &BigUint,
+    n: &BigUint,
+    order: &BigUint,
+    max_iterations: u64,
+) -> Option<BigUint> {
+    // The number of multipliers in the walk. Teske found 20 enough to behave like a random
+    // walk.
+    const MULTIPLIERS: usize = 20;
+
+    assert!(!n.is_zero(), "divide by zero!");
+    assert!(!order.is_zero(), "the order must not be zero");
+
+    let h = h % n;
+    if h == &*BIG_1 % n {
+        return Some(BigUint::zero());
+    }
+
+    let mut rng = seeded_rng(&h);
+    let mut iterations = 0u64;
+    while iterations < max_iterations {
+        // Each element of the walk is kept as g^a h^b alongside its exponents.
+        let mut element = || {
+            let a = rng.gen_biguint_below(order);
+            let b = rng.gen_biguint_below(order);
+            let x = g.modpow(&a, n) * h.modpow(&b, n) % n;
+            (x, a, b)
+        };
+        let steps: Vec<_> = (0..MULTIPLIERS).map(|_| element()).collect();
+        let step = |(x, a, b): &(BigUint, BigUint, BigUint)| {
+            let (mx, ma, mb) = &steps[(x.to_u64_wrapping() % MULTIPLIERS as u64) as usize];
+            (x * mx % n, (a + ma) % order, (b + mb) % order)
+        };
+
+        let mut tortoise = element();
+        let mut hare = step(&tortoise);
+        let (mut power, mut lambda) = (1u64, 1u64);
+        while tortoise.0 != hare.0 && iterations < max_iterations {
+            if power == lambda {
+                tortoise = hare.clone();
+                power *= 2;
+                lambda = 0;
+            }
+            hare = step(&hare);
+            lambda += 1;
+            iterations += 1;
+        }
+
+        if tortoise.0 == hare.0 {
+            // g^a1 h^b1 = g^a2 h^b2, so (b1 - b2) x ≡ a2 - a1 (mod order).
+            let (_, a1, b1) = tortoise;
+            let (_, a2, b2) = hare;
+            let r = (b1 + order - b2) % order;
+            let s = (a2 + order - a1) % order;
+            if let Some(x) = solve_log_congruence(g, &h, n, order, &r, &s) {
+                return Some(x);
+            }
+        }
+    }
+    None
+}
+
+/// Returns the solution of r x ≡ s (mod order) that is the logarithm of h to the base g
+/// modulo n, if there are few enough solutions to check them all.
+fn solve_log_congruence(
+    g: &BigUint,
+    h: &BigUint,
+    n: &BigUint,
+    order: &BigUint,
+    r: &BigUint,
+    s: &BigUint,
+) -> Option<BigUint> {
+    const MAX_SOLUTIONS: u64 = 1 << 16;
+
+    let d = r.gcd(order);
+    if r.is_zero() || !(s % &d).is_zero() {
+        return None;
+    }
+    let solutions = d.to_u64().filter(|&d| d <= MAX_SOLUTIONS)?;
+    let m = order / &d;
+    let x0 = if m.is_one() {
+        BigUint::zero()
+    } else {
+        let inverse = into_magnitude((r / &d).mod_inverse(&m)?);
+        (s / &d) * inverse % &m
+    };
+    (0..solutions)
+        .map(|k| &x0 + &m * k)
+        .find(|x| g.modpow(x, n) == *h)
+}
+
+/// Returns x in [lo, hi] with g^x ≡ h (mod n), using Pollard's lambda (kangaroo) method, or
+/// `None` if none was found within max_iterations jumps.
+///
+/// A tame kangaroo jumps from g^hi and sets a trap where it stops, then a wild one jumps from
+/// h until it falls into the trap or passes it. The jumps are powers of two averaging about
+/// √(hi - lo) / 2, so this takes about 2√(hi - lo) multiplications and constant memory, and
+/// doesn't need the order of g. A wild kangaroo that misses the trap is retried with a
+/// different choice of jumps.
+///
+/// Panics if n is zero or lo > hi.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::discrete_log_kangaroo;
+/// use num_bigint_dig::BigUint;
+///
+/// let (g, n) = (BigUint::from(3u32), BigUint::from(1_000_000_007u32));
+/// let h = g.modpow(&BigUint::from(7_654_321u32), &n);
+/// let (lo, hi) = (BigUint::from(7_000_000u32), BigUint::from(8_000_000u32));
+/// assert_eq!(
+///     discrete_log_kangaroo(&g, &h, &n, &lo, &hi, 1 << 20),
+///     Some(BigUint::from(7_654_321u32))
+/// );
+/// ```
+pub fn discrete_log_kangaroo(
+    g: &BigUint,
+    h: &BigUint,
+    n: &BigUint,
+    lo: &BigUint,
+    hi: &BigUint,
+    max_iterations: u64,
+) -> Option<BigUint> {
+    assert!(!n.is_zero(), "divide by zero!");
+    assert!(lo <= hi, "the interval must not be empty");
+
+    let h = h % n;
+    let width = hi - lo;
+
+    // The jumps are 2^0 .. 2^(k - 1), whose mean (2^k - 1) / k should be about √width / 2.
+    let mean = cmp::max(width.sqrt() >> 1, BigUint::one());
+    let mut k = 1;
+    while ((BigUint::one() << k) - 1u32) / k < mean {
+        k += 1;
+    }
+    let mut jumps = Vec::with_capacity(k);
+    let mut jump = g % n;
+    for _ in 0..k {
+        let next = &jump * &jump % n;
+        jumps.push(jump);
+        jump = next;
+    }
+
+    // The tame kangaroo travels about width in total.
+    let tame_jumps = mean.to_u64().unwrap_or(u64::MAX).saturating_mul(4);
+    let mut iterations = 0u64;
+    let mut salt = 0u64;
+    while iterations < max_iterations {
+        let choose = |x: &BigUint| ((x.to_u64_wrapping() ^ salt) % k as u64) as usize;
+
+        let mut trap = g.modpow(hi, n);
+        let mut tame_distance = BigUint::zero();
+        for _ in 0..tame_jumps {
+            if iterations >= max_iterations {
+                return None;
+            }
+            let i = choose(&trap);
+            trap = trap * &jumps[i] % n;
+            tame_distance += BigUint::one() << i;
+            iterations += 1;
+        }
+
+        // The wild kangaroo starts at g^x, so passing the trap means x < lo.
+        let limit = &width + &tame_distance;
+        let mut wild = h.clone();
+        let mut wild_distance = BigUint::zero();
+        while wild_distance <= limit && iterations < max_iterations {
+            if wild == trap {
+                // g^(hi + tame_distance) = g^(x + wild_distance).
+                let x = hi + &tame_distance - &wild_distance;
+                if &x <= hi {
+                    return Some(x);
+                }
+                break;
+            }
+            let i = choose(&wild);
+            wild = wild * &jumps[i] % n;
+            wild_distance += BigUint::one() << i;
+            iterations += 1;
+        }
+
+        salt = salt.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    }
+    None
+}
+
+/// Lifts a simple root of the polynomial `f` modulo the prime `p` to a root modulo `p^k`.
+///
+/// `f` holds the coefficients, constant term first, and `root` must satisfy
+/// `f(root) = 0 (mod p)` with `f'(root) != 0 (mod p)`, in which case the lifted root is
+/// unique. Each Newton step `r = r - f(r) / f'(r)` doubles the power of `p` that `r` is a
+/// root modulo. Returns `None` if `root` is not a root of `f` modulo `p`, or is a repeated
+/// one.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::hensel_lift;
+/// use num_bigint_dig::{BigInt, BigUint};
+///
+/// // x^2 - 2 has the root 3 modulo 7.
+/// let f = [BigInt::from(-2), BigInt::from(0), BigInt::from(1)];
+/// let p = BigUint::from(7u32);
+/// let r = hensel_lift(&f, &BigUint::from(3u32), &p, 10).unwrap();
+///
+/// let m = BigUint::from(282_475_249u32); // 7^10
+/// assert_eq!(&r * &r % &m, BigUint::from(2u32));
+/// ```
+pub fn hensel_lift(f: &[BigInt], root: &BigUint, p: &BigUint, k: u32) -> Option<BigUint> {
+    assert!(!p.is_zero(), "divide by zero!");
+
+    let eval = |poly: &[BigInt], x: &BigInt, m: &BigInt| {
+        poly.iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, c| (acc * x + c).mod_floor(m))
+    };
+    let df: Vec<BigInt> = f
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * BigInt::from(i))
+        .collect();
+
+    let modulus = BigInt::from(p.clone());
+    let mut r = BigInt::from(root % p);
+    if !eval(f, &r, &modulus).is_zero() || eval(&df, &r, &modulus).is_zero() {
+        return None;
+    }
+    if k == 0 {
+        return Some(BigUint::zero());
+    }
+
+    let mut e = 1;
+    while e < k {
+        e = cmp::min(2 * e, k);
+        let modulus = BigInt::from(Pow::pow(p, e));
+        let inv = eval(&df, &r, &modulus).mod_inverse(&modulus)?;
+        r = (&r - eval(f, &r, &modulus) * inv).mod_floor(&modulus);
+    }
+    Some(into_magnitude(r))
+}
+
+/// Returns a square root of `x` modulo `p^k` for an odd prime `p`, or `None` if `x` is not a
+/// square modulo `p^k`.
+///
+/// A root modulo `p` is found with the Tonelli-Shanks algorithm and lifted with
+/// [`hensel_lift`]. Factors of `p` in `x` are first taken out in pairs, since `x^2 - a` has
+/// no simple roots modulo `p` when `p` divides `a`.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::prime::sqrt_mod_prime_power;
+/// use num_bigint_dig::BigUint;
+///
+/// let (p, m) = (BigUint::from(13u32), BigUint::from(28_561u32)); // m = 13^4
+/// let r = sqrt_mod_prime_power(&BigUint::from(10u32), &p, 4).unwrap();
+/// assert_eq!(&r * &r % &m, BigUint::from(10u32));
+///
+/// assert_eq!(sqrt_mod_prime_power(&BigUint::from(5u32), &p, 4), None);
+/// ```
+pub fn sqrt_mod_prime_power(x: &BigUint, p: &BigUint, k: u32) -> Option<BigUint> {
+    let root = p.sqrt();
+    assert!(
+        p.is_odd() && !p.is_one() && &root * &root != *p,
+        "p must be an odd prime"
+    );
+
+    let m = Pow::pow(p, k);
+    let mut a = x % &m;
+    if a.is_zero() {
+        return Some(BigUint::zero());
+    }
+    let mut v = 0;
+    while (&a % p).is_zero() {
+        a /= p;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return None;
+    }
+
+    let s = sqrt_mod_prime(&(&a % p), p)?;
+    let f = [-BigInt::from(a), BigInt::zero(), BigInt::one()];
+    let s = hensel_lift(&f, &s, p, k - v)?;
+    Some(Pow::pow(p, v / 2) * s % m)
+}
+
+/// Returns a square root of `a` modulo the odd prime `p` with the Tonelli-Shanks algorithm,
+/// or `None` if `a` is a non-residue.
+fn sqrt_mod_prime(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    if a.is_zero() {
+        return Some(BigUint::zero());
+    }
+    let p_int = BigInt::from(p.clone());
+    if jacobi(&BigInt::from(a.clone()), &p_int) != 1 {
+        return None;
+    }
+
+    // p - 1 = q * 2^s with q odd, and z is any non-residue.
+    let p1 = p - 1u32;
+    let s = p1.trailing_zeros().unwrap();
+    let q = &p1 >> s;
+    // An odd prime has a non-residue below sqrt(p) + 1, so the search only runs past that
+    // bound when p is not prime.
+    let mut z = 2u64;
+    while jacobi(&BigInt::from(z), &p_int) != -1 {
+        z += 1;
+        if p.cmp_u64((z - 1).saturating_mul(z - 1)) == cmp::Ordering::Less {
+            return None;
+        }
+    }
+
+    let mut c = BigUint::from(z).modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1u32) >> 1), p);
+    let mut m = s;
+    while !t.is_one() {
+        // The least i with t^(2^i) = 1, which is below m when p is prime.
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while !t2.is_one() {
+            t2 = &t2 * &t2 % p;
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        r = r * &b % p;
+        c = &b * &b % p;
+        t = t * &c % p;
+        m = i;
+    }
+    Some(r)
+}
+
+/// Returns a non-trivial factor of the composite n using Pollard's rho method, with Brent's
+/// cycle detection, or `None` if none was found within max_iterations.
+fn pollard_rho(n: &BigUint, max_iterations: u64) -> Option<BigUint> {
+    // Differences are multiplied together and only their product is checked with a gcd.
+    const BATCH: u64 = 128;
+
+    let mut c = BIG_1.clone();
+    let mut iterations = 0u64;
+    while iterations < max_iterations {
+        let f = |x: &BigUint| (x * x + &c) % n;
+        let mut y = BIG_2.clone();
+        let mut ys = y.clone();
+        let mut x = y.clone();
+        let mut q = BIG_1.clone();
+        let mut g = BIG_1.clone();
+        let mut r = 1u64;
+        while g.is_one() && iterations < max_iterations {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g.is_one() {
+                ys = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(&y);
+                    q = q * if x > y { &x - &y } else { &y - &x } % n;
+                }
+                g = q.gcd(n);
+                k += BATCH;
+            }
+            iterations = iterations.saturating_add(2 * r);
+            r *= 2;
+        }
+
+        if &g == n {
+            // The last batch overshot; redo it one step at a time.
+            loop {
+                ys = f(&ys);
+                g = (if x > ys { &x - &ys } else { &ys - &x }).gcd(n);
+                if !g.is_one() {
+                    break;
+                }
+            }
+        }
+        if !g.is_one() && &g != n {
+            return Some(g);
+        }
+        c += &*BIG_1;
+    }
+    None
+}
+
+/// Returns a non-trivial factor of the composite n using stage 1 of Lenstra's elliptic curve
+/// method, trying up to curves random curves, or `None` if none of them found one.
+///
+/// Points are kept in affine coordinates. When a slope's denominator is not invertible
+/// modulo n, its gcd with n is the factor.
+fn ecm(n: &BigUint, curves: usize, bound: u32) -> Option<BigUint> {
+    let mut rng = seeded_rng(n);
+    let primes = small_primes(bound);
+    for _ in 0..curves {
+        // y^2 = x^3 + ax + b through a random point, which determines b.
+        let a = rng.gen_biguint_below(n);
+        let mut point = Some((rng.gen_biguint_below(n), rng.gen_biguint_below(n)));
+        for &p in &primes {
+            let mut pk = u64::from(p);
+            while pk * u64::from(p) <= u64::from(bound) {
+                pk *= u64::from(p);
+            }
+            match ec_mul(&point, pk, &a, n) {
+                Ok(Some(q)) => point = Some(q),
+                // The point has order dividing the multiplier modulo every factor of n.
+                Ok(None) => break,
+                Err(d) if &d != n => return Some(d),
+                Err(_) => break,
+            }
+        }
+    }
+    None
+}
+
+/// A point on an elliptic curve modulo n in affine coordinates, or `None` at infinity.
+type EcPoint = Option<(BigUint, BigUint)>;
+
+/// Returns k * p on the curve y^2 = x^3 + ax + b modulo n, or `Err` with the gcd of n and a
+/// denominator that is not invertible.
+fn ec_mul(p: &EcPoint, k: u64, a: &BigUint, n: &BigUint) -> Result<EcPoint, BigUint> {
+    let mut acc = None;
+    for i in (0..64 - k.leading_zeros()).rev() {
+        acc = ec_add(&acc, &acc, a, n)?;
+        if k >> i & 1 == 1 {
+            acc = ec_add(&acc, p, a, n)?;
+        }
+    }
+    Ok(acc)
+}
+
+/// Returns p + q on the curve y^2 = x^3 + ax + b modulo n, or `Err` with the gcd of n and a
+/// denominator that is not invertible.
+fn ec_add(p: &EcPoint, q: &EcPoint, a: &BigUint, n: &BigUint) -> Result<EcPoint, BigUint> {
+    let ((x1, y1), (x2, y2)) = match (p, q) {
+        (None, _) => return Ok(q.clone()),
+        (_, None) => return Ok(p.clone()),
+        (Some(p), Some(q)) => (p, q),
+    };
+
+    let (num, den) = if x1 != x2 {
+        (y2.sub_mod(y1, n), x2.sub_mod(x1, n))
+    } else if y1 == y2 {
+        let x1_squared = x1.mul_mod(x1, n);
+        let num = (x1_squared * 3u32 + a) % n;
+        (num, y1.add_mod(y1, n))
+    } else if y1.add_mod(y2, n).is_zero() {
+        return Ok(None);
+    } else {
+        // y1^2 = y2^2 but y1 != ±y2, which can only happen modulo a composite.
+        return Err(y2.sub_mod(y1, n).gcd(n));
+    };
+
+    let g = den.gcd(n);
+    if !g.is_one() {
+        return Err(g);
+    }
+    let slope = num.mul_mod(&into_magnitude(den.mod_inverse(n).unwrap()), n);
+    let x3 = slope.mul_mod(&slope, n).sub_mod(x1, n).sub_mod(x2, n);
+    let y3 = slope.mul_mod(&x1.sub_mod(&x3, n), n).sub_mod(y1, n);
+    Ok(Some((x3, y3)))
+}
+
+/// Returns a generator seeded from the low digit of n, so that the bases tried for a given n
+/// are reproducible.
+fn seeded_rng(n: &BigUint) -> StdRng {
+    let mut seed_vec = vec![0u8; 8];
+    BigEndian::write_uint(
+        seed_vec.as_mut_slice(),
+        u64::from(n.data.first().copied().unwrap_or(0)),
+        big_digit::BITS / 8,
+    );
+    let mut seed = [0u8; 32];
+    seed[0..8].copy_from_slice(&seed_vec[..]);
+    StdRng::from_seed(seed)
+}
+
+/// Checks if the i-th bit is set
+#[inline]
+fn is_bit_set(x: &BigUint, i: usize) -> bool {
+    get_bit(x, i) == 1
+}
+
+/// Returns the i-th bit.
+#[inline]
+fn get_bit(x: &BigUint, i: usize) -> u8 {
+    let j = i / big_digit::BITS;
+    // if is out of range of the set words, it is always false.
+    if i >= x.bits() {
+        return 0;
+    }
+
+    (x.get_limb(j) >> (i % big_digit::BITS) & 1) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    // use RandBigInt;
+
+    use crate::biguint::ToBigUint;
+
+    lazy_static! {
+        static ref PRIMES: Vec<&'static str> = vec![
+        "2",
+        "3",
+        "5",
+        "7",
+        "11",
+
+        "13756265695458089029",
+        "13496181268022124907",
+        "10953742525620032441",
+        "17908251027575790097",
+
+        // https://golang.org/issue/638
+        "18699199384836356663",
+
+        "98920366548084643601728869055592650835572950932266967461790948584315647051443",
+        "94560208308847015747498523884063394671606671904944666360068158221458669711639",
+
+        // http://primes.utm.edu/lists/small/small3.html
+        "449417999055441493994709297093108513015373787049558499205492347871729927573118262811508386655998299074566974373711472560655026288668094291699357843464363003144674940345912431129144354948751003607115263071543163",
+        "230975859993204150666423538988557839555560243929065415434980904258310530753006723857139742334640122533598517597674807096648905501653461687601339782814316124971547968912893214002992086353183070342498989426570593",
+        "5521712099665906221540423207019333379125265462121169655563495403888449493493629943498064604536961775110765377745550377067893607246020694972959780839151452457728855382113555867743022746090187341871655890805971735385789993",
+        "203956878356401977405765866929034577280193993314348263094772646453283062722701277632936616063144088173312372882677123879538709400158306567338328279154499698366071906766440037074217117805690872792848149112022286332144876183376326512083574821647933992961249917319836219304274280243803104015000563790123",
+        // ECC primes: http://tools.ietf.org/html/draft-ladd-safecurves-02
+        "3618502788666131106986593281521497120414687020801267626233049500247285301239",                                                                                  // Curve1174: 2^251-9
+        "57896044618658097711785492504343953926634992332820282019728792003956564819949",                                                                                 // Curve25519: 2^255-19
+        "9850501549098619803069760025035903451269934817616361666987073351061430442874302652853566563721228910201656997576599",                                           // E-382: 2^382-105
+        "42307582002575910332922579714097346549017899709713998034217522897561970639123926132812109468141778230245837569601494931472367",                                 // Curve41417: 2^414-17
+        "6864797660130609714981900799081393217269435300143305409394463459185543183397656052122559640661454554977296311391480858037121987999716643812574028291115057151", // E-521: 2^521-1
+        ];
+
+        static ref COMPOSITES: Vec<&'static str> = vec![
+            "0",
+            "1",
+
+            "21284175091214687912771199898307297748211672914763848041968395774954376176754",
             "6084766654921918907427900243509372380954290099172559290432744450051395395951",
             "84594350493221918389213352992032324280367711247940675652888030554255915464401",
             "82793403787388584738507275144194252681",
@@ -576,6 +2106,489 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_solovay_strassen() {
+        for prime in PRIMES.iter() {
+            let p = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            assert!(
+                probably_prime_solovay_strassen(&p, 20),
+                "{} is a prime",
+                prime
+            );
+        }
+        for comp in COMPOSITES.iter() {
+            let p = BigUint::parse_bytes(comp.as_bytes(), 10).unwrap();
+            assert!(
+                !probably_prime_solovay_strassen(&p, 20),
+                "{} is a composite",
+                comp
+            );
+        }
+
+        // Carmichael numbers pass Fermat's test for every coprime base, but not Euler's.
+        for &c in &[561u32, 1105, 1729, 2465, 2821, 6601, 8911, 41041, 825265] {
+            let n = BigUint::from(c);
+            assert!(
+                !probably_prime_solovay_strassen(&n, 20),
+                "{} is a composite",
+                c
+            );
+        }
+
+        for i in 0..2000u32 {
+            let n = BigUint::from(i);
+            assert_eq!(
+                probably_prime_solovay_strassen(&n, 20),
+                probably_prime(&n, 20),
+                "{}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_frobenius() {
+        for prime in PRIMES.iter() {
+            let p = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            assert!(probably_prime_frobenius(&p, 2), "{} is a prime", prime);
+        }
+        for comp in COMPOSITES.iter() {
+            let p = BigUint::parse_bytes(comp.as_bytes(), 10).unwrap();
+            assert!(!probably_prime_frobenius(&p, 2), "{} is a composite", comp);
+        }
+
+        for i in 0..5000u32 {
+            let n = BigUint::from(i);
+            assert_eq!(
+                probably_prime_frobenius(&n, 1),
+                probably_prime(&n, 20),
+                "{}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_prove_prime() {
+        for prime in PRIMES.iter().take(10) {
+            let p = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            let cert = prove_prime(&p).unwrap();
+            assert_eq!(cert.prime(), &p);
+            assert!(cert.verify(), "{}", prime);
+        }
+        for comp in COMPOSITES.iter() {
+            let p = BigUint::parse_bytes(comp.as_bytes(), 10).unwrap();
+            assert!(prove_prime(&p).is_none(), "{} is a composite", comp);
+        }
+
+        // 2^127 - 1, where 2^127 - 2 has twelve distinct prime factors.
+        let m127 = (BigUint::one() << 127) - 1u32;
+        let cert = prove_prime(&m127).unwrap();
+        assert_eq!(cert.factors().len(), 12);
+        assert!(cert.verify());
+    }
+
+    #[test]
+    fn test_factorize() {
+        let effort = FactorEffort::default();
+        assert_eq!(BigUint::one().factorize(&effort), Factorization::default());
+
+        let factors = BigUint::from(360u32).factorize(&effort);
+        let expected = [(2u32, 3), (3, 2), (5, 1)]
+            .iter()
+            .map(|&(p, e)| (BigUint::from(p), e))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(factors.primes(), &expected);
+        assert!(factors.is_complete());
+
+        // A cube of a prime above the trial division bound, times small factors.
+        let p = BigUint::from(1_000_000_007u32);
+        let factors = (Pow::pow(&p, 3u32) * 12u32).factorize(&effort);
+        assert_eq!(factors.primes().len(), 3);
+        assert_eq!(factors.primes()[&p], 3);
+
+        let n = (BigUint::from(1_000_000_007u32) * 998_244_353u32 * 1_000_000_009u32) << 5;
+        let factors = n.factorize(&effort);
+        assert!(factors.is_complete());
+        let product = factors
+            .primes()
+            .iter()
+            .fold(BigUint::one(), |acc, (p, &e)| acc * Pow::pow(p, e));
+        assert_eq!(product, n);
+        assert_eq!(factors.primes().len(), 4);
+    }
+
+    #[test]
+    fn test_factorize_effort() {
+        // (2^61 - 1) * (2^89 - 1) is out of reach of a few hundred rho iterations.
+        let m61 = (BigUint::one() << 61) - 1u32;
+        let m89 = (BigUint::one() << 89) - 1u32;
+        let n = &m61 * &m89;
+        let effort = FactorEffort {
+            rho_iterations: 256,
+            ..FactorEffort::default()
+        };
+        let factors = n.factorize(&effort);
+        assert!(!factors.is_complete());
+        assert!(factors.primes().is_empty());
+        assert_eq!(factors.composites(), &[n][..]);
+
+        // With rho disabled, ECM has to find the factors.
+        let n = BigUint::from(1_000_003u32) * 1_000_033u32 * 1_000_037u32;
+        let effort = FactorEffort {
+            trial_division_bound: 1000,
+            rho_iterations: 0,
+            ecm_curves: 100,
+            ecm_bound: 2000,
+        };
+        let factors = n.factorize(&effort);
+        assert!(factors.is_complete());
+        assert_eq!(factors.primes().len(), 3);
+    }
+
+    #[test]
+    fn test_arithmetic_functions() {
+        let effort = FactorEffort::default();
+        for n in 1..200u64 {
+            let factors = BigUint::from(n).factorize(&effort);
+            let coprime = (1..=n).filter(|a| a.gcd(&n) == 1).collect::<Vec<_>>();
+            let divisors = (1..=n).filter(|d| n % d == 0).collect::<Vec<_>>();
+
+            let totient = coprime.len() as u64;
+            assert_eq!(factors.totient(), Some(BigUint::from(totient)), "φ({})", n);
+
+            let order = |a: u64| {
+                let (mut x, mut m) = (a % n, 1);
+                while x != 1 % n {
+                    x = x * a % n;
+                    m += 1;
+                }
+                m
+            };
+            let lambda = coprime.iter().fold(1, |acc: u64, &a| acc.lcm(&order(a)));
+            assert_eq!(
+                factors.carmichael_lambda(),
+                Some(BigUint::from(lambda)),
+                "λ({})",
+                n
+            );
+
+            for k in 0..3u32 {
+                let sigma = divisors.iter().map(|&d| Pow::pow(d, k)).sum::<u64>();
+                assert_eq!(
+                    factors.sigma(k),
+                    Some(BigUint::from(sigma)),
+                    "σ{}({})",
+                    k,
+                    n
+                );
+            }
+            let count = divisors.len() as u64;
+            assert_eq!(factors.num_divisors(), Some(BigUint::from(count)));
+        }
+
+        // Incomplete factorizations have no answer.
+        let n = ((BigUint::one() << 61) - 1u32) * ((BigUint::one() << 89) - 1u32);
+        let effort = FactorEffort {
+            rho_iterations: 0,
+            ..FactorEffort::default()
+        };
+        let factors = n.factorize(&effort);
+        assert_eq!(factors.totient(), None);
+        assert_eq!(factors.carmichael_lambda(), None);
+        assert_eq!(factors.sigma(1), None);
+        assert_eq!(factors.num_divisors(), None);
+    }
+
+    #[test]
+    fn test_multiplicative_order() {
+        let effort = FactorEffort::default();
+        for n in 1..120u64 {
+            let factors = BigUint::from(n).factorize(&effort);
+            let lambda = factors.carmichael_lambda().unwrap().factorize(&effort);
+            let totient = factors.totient().unwrap().factorize(&effort);
+            for a in 0..n {
+                let expected = if a.gcd(&n) == 1 {
+                    let (mut x, mut k) = (a % n, 1u64);
+                    while x != 1 % n {
+                        x = x * a % n;
+                        k += 1;
+                    }
+                    Some(BigUint::from(k))
+                } else {
+                    None
+                };
+                let a = BigUint::from(a);
+                let n = BigUint::from(n);
+                assert_eq!(multiplicative_order(&a, &n, &lambda), expected);
+                assert_eq!(multiplicative_order(&a, &n, &totient), expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "λ is not a multiple of the order")]
+    fn test_multiplicative_order_wrong_lambda() {
+        let lambda = BigUint::from(5u32).factorize(&FactorEffort::default());
+        multiplicative_order(&BigUint::from(3u32), &BigUint::from(7u32), &lambda);
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        let effort = FactorEffort::default();
+        let mut rng = StdRng::from_seed([7; 32]);
+        for p in primes_in_range(&BigUint::zero(), &BigUint::from(200u32)) {
+            let p_minus_1 = (&p - 1u32).factorize(&effort);
+            let order = p_minus_1
+                .fold_prime_powers(BigUint::one(), |acc, q, e| acc * Pow::pow(q, e))
+                .unwrap();
+            for g in 0..p.to_u32().unwrap() {
+                let g = BigUint::from(g);
+                let expected = multiplicative_order(&g, &p, &p_minus_1) == Some(order.clone());
+                assert_eq!(is_primitive_root(&g, &p, &p_minus_1), expected);
+            }
+
+            let g = find_primitive_root(&p, &p_minus_1, &mut rng);
+            assert!(g < p);
+            assert!(is_primitive_root(&g, &p, &p_minus_1));
+        }
+
+        let p = BigUint::from(1_000_000_007u32);
+        let p_minus_1 = (&p - 1u32).factorize(&effort);
+        assert!(!is_primitive_root(&BigUint::from(2u32), &p, &p_minus_1));
+        assert!(is_primitive_root(&BigUint::from(5u32), &p, &p_minus_1));
+    }
+
+    #[test]
+    #[should_panic(expected = "p must be prime")]
+    fn test_find_primitive_root_composite() {
+        let n = BigUint::from(15u32);
+        let n_minus_1 = (&n - 1u32).factorize(&FactorEffort::default());
+        find_primitive_root(&n, &n_minus_1, &mut StdRng::from_seed([7; 32]));
+    }
+
+    #[test]
+    fn test_discrete_log_rho() {
+        let effort = FactorEffort::default();
+        for &(n, g) in &[
+            (1_000_003u64, 2u64),
+            (1_000_000_007, 5),
+            (7 * 11 * 13 * 17, 3),
+            (2, 1),
+        ] {
+            let (n, g) = (BigUint::from(n), BigUint::from(g));
+            let lambda = n.factorize(&effort).carmichael_lambda().unwrap();
+            let lambda = lambda.factorize(&effort);
+            let order = multiplicative_order(&g, &n, &lambda).unwrap();
+            let mut rng = StdRng::from_seed([3; 32]);
+            for _ in 0..10 {
+                let x = rng.gen_biguint_below(&order);
+                let h = g.modpow(&x, &n);
+                assert_eq!(discrete_log_rho(&g, &h, &n, &order, 1 << 20), Some(x));
+            }
+        }
+
+        // 3 is not a power of 4 modulo 7.
+        let (g, n) = (BigUint::from(4u32), BigUint::from(7u32));
+        let three = BigUint::from(3u32);
+        assert_eq!(discrete_log_rho(&g, &three, &n, &three, 1000), None);
+    }
+
+    #[test]
+    fn test_discrete_log_kangaroo() {
+        let (g, n) = (BigUint::from(5u32), BigUint::from(1_000_000_007u32));
+        let mut rng = StdRng::from_seed([5; 32]);
+        for &width in &[0u32, 1, 5, 100, 1 << 16, 1 << 24] {
+            let width = BigUint::from(width);
+            for _ in 0..5 {
+                let lo = rng.gen_biguint(20);
+                let hi = &lo + &width;
+                for x in &[
+                    lo.clone(),
+                    hi.clone(),
+                    rng.gen_biguint_range(&lo, &(&hi + 1u32)),
+                ] {
+                    let h = g.modpow(x, &n);
+                    let found = discrete_log_kangaroo(&g, &h, &n, &lo, &hi, 1 << 20);
+                    assert_eq!(found.as_ref(), Some(x));
+                }
+            }
+        }
+
+        // The logarithm of 5^1000 is outside [0, 999].
+        let h = g.modpow(&BigUint::from(1000u32), &n);
+        let hi = BigUint::from(999u32);
+        assert_eq!(
+            discrete_log_kangaroo(&g, &h, &n, &BigUint::zero(), &hi, 1000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hensel_lift() {
+        // x^3 - x - 1 has the simple root 2 modulo 5.
+        let f = [
+            BigInt::from(-1),
+            BigInt::from(-1),
+            BigInt::zero(),
+            BigInt::one(),
+        ];
+        let p = BigUint::from(5u32);
+        for k in 0..40 {
+            let r = hensel_lift(&f, &BigUint::from(2u32), &p, k).unwrap();
+            let m = Pow::pow(&p, k);
+            assert!(r < m);
+            let r = BigInt::from(r);
+            let value = &r * &r * &r - &r - 1u32;
+            assert!(value.mod_floor(&BigInt::from(m)).is_zero());
+        }
+
+        // 3 is not a root, and x^2 has only the repeated root 0.
+        assert_eq!(hensel_lift(&f, &BigUint::from(3u32), &p, 3), None);
+        let square = [BigInt::zero(), BigInt::zero(), BigInt::one()];
+        assert_eq!(hensel_lift(&square, &BigUint::zero(), &p, 3), None);
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime_power() {
+        for &(p, k) in &[
+            (3u32, 1u32),
+            (3, 5),
+            (5, 3),
+            (7, 3),
+            (13, 2),
+            (17, 2),
+            (41, 2),
+        ] {
+            let m = p.pow(k);
+            let big_p = BigUint::from(p);
+            let mut squares = vec![false; m as usize];
+            for y in 0..m as u64 {
+                squares[(y * y % m as u64) as usize] = true;
+            }
+            for x in 0..m {
+                let root = sqrt_mod_prime_power(&BigUint::from(x), &big_p, k);
+                assert_eq!(root.is_some(), squares[x as usize], "{} mod {}", x, m);
+                if let Some(r) = root {
+                    assert_eq!(&r * &r % m, BigUint::from(x));
+                }
+            }
+        }
+
+        let p = BigUint::from(1_000_000_007u32);
+        let m = Pow::pow(&p, 7u32);
+        let y = BigUint::from(123_456_789u32) * &p * &p + 42u32;
+        let x = &y * &y % &m;
+        let r = sqrt_mod_prime_power(&x, &p, 7).unwrap();
+        assert_eq!(&r * &r % &m, x);
+        let x = &x * &p * &p % &m;
+        let r = sqrt_mod_prime_power(&x, &p, 7).unwrap();
+        assert_eq!(&r * &r % &m, x);
+    }
+
+    #[test]
+    #[should_panic(expected = "p must be an odd prime")]
+    fn test_sqrt_mod_prime_power_square_modulus() {
+        sqrt_mod_prime_power(&BigUint::from(2u32), &BigUint::from(9u32), 1);
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime_composite() {
+        // Neither modulus has a z < sqrt(m) + 1 with Jacobi symbol -1, so the search gives up.
+        assert!(sqrt_mod_prime(&BigUint::from(2u32), &BigUint::from(15u32)).is_none());
+        assert!(sqrt_mod_prime(&BigUint::from(4u32), &BigUint::from(225u32)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "zero has no factorization")]
+    fn test_factorize_zero() {
+        BigUint::zero().factorize(&FactorEffort::default());
+    }
+
+    #[test]
+    fn test_pratt_certificate_verify() {
+        let cert = prove_prime(&BigUint::from(1_000_000_007u32)).unwrap();
+        assert!(cert.verify());
+
+        // A witness that is not a generator.
+        let mut bad = cert.clone();
+        bad.witness = BigUint::one();
+        assert!(!bad.verify());
+
+        // A missing factor of p - 1.
+        let mut bad = cert.clone();
+        bad.factors.pop();
+        assert!(!bad.verify());
+
+        // A repeated factor.
+        let mut bad = cert.clone();
+        let first = bad.factors[0].clone();
+        bad.factors.push(first);
+        assert!(!bad.verify());
+
+        // A composite passed off as prime.
+        let mut bad = cert.clone();
+        bad.factors[1].prime = BigUint::from(9u32);
+        assert!(!bad.verify());
+
+        // A certificate for a composite built by hand: 561 = 3 * 11 * 17 is a Carmichael
+        // number, so every coprime witness satisfies Fermat's condition.
+        let fake = PrattCertificate {
+            prime: BigUint::from(561u32),
+            witness: BigUint::from(2u32),
+            factors: [2u32, 5, 7]
+                .iter()
+                .map(|&q| prove_prime(&BigUint::from(q)).unwrap())
+                .collect(),
+        };
+        assert!(!fake.verify());
+    }
+
+    #[test]
+    fn test_is_prime_with() {
+        let configs = [
+            PrimalityConfig::default(),
+            PrimalityConfig {
+                trial_division_bound: 1000,
+                mr_rounds: 0,
+                ..PrimalityConfig::default()
+            },
+            PrimalityConfig {
+                trial_division_bound: 2,
+                mr_rounds: 10,
+                use_bpsw: false,
+                rng_policy: RngPolicy::Random,
+                solovay_strassen_rounds: 5,
+                frobenius_rounds: 1,
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for config in &configs {
+            for prime in PRIMES.iter() {
+                let p = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+                assert!(p.is_prime_with(config, &mut rng), "{} is a prime", prime);
+            }
+            for comp in COMPOSITES.iter() {
+                let p = BigUint::parse_bytes(comp.as_bytes(), 10).unwrap();
+                assert!(
+                    !p.is_prime_with(config, &mut rng),
+                    "{} is a composite",
+                    comp
+                );
+            }
+            for i in 0..3000u32 {
+                let n = BigUint::from(i);
+                assert_eq!(
+                    n.is_prime_with(config, &mut rng),
+                    probably_prime(&n, 20),
+                    "{}",
+                    i
+                );
+            }
+        }
+    }
+
     macro_rules! test_pseudo_primes {
         ($name:ident, $cond:expr, $want:expr) => {
             #[test]
@@ -651,4 +2664,113 @@ mod tests {
             assert!(probably_prime(p1, 25));
         }
     }
+
+    #[test]
+    fn test_primes_in_range() {
+        let count = primes_in_range(&BigUint::zero(), &BigUint::from(100_000u32)).count();
+        assert_eq!(count, 9592);
+
+        for &(lo, hi) in &[
+            (0u32, 2u32),
+            (0, 3),
+            (1, 60),
+            (17, 18),
+            (18, 17),
+            (32_000, 33_000),
+        ] {
+            let (lo, hi) = (BigUint::from(lo), BigUint::from(hi));
+            let expected = crate::range::range(lo.clone(), hi.clone())
+                .filter(|n| probably_prime(n, 20))
+                .collect::<Vec<_>>();
+            assert_eq!(primes_in_range(&lo, &hi).collect::<Vec<_>>(), expected);
+        }
+
+        let lo = (BigUint::one() << 64) - 500u32;
+        let hi = &lo + 1000u32;
+        let expected = crate::range::range(lo.clone(), hi.clone())
+            .filter(|n| probably_prime(n, 20))
+            .collect::<Vec<_>>();
+        assert_eq!(primes_in_range(&lo, &hi).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_prime_pi_approx() {
+        assert_eq!(prime_pi_approx(&BigUint::zero()), BigUint::zero());
+        assert_eq!(prime_pi_approx(&BigUint::from(2u32)), BigUint::one());
+        assert_eq!(prime_pi_approx(&BigUint::from(63u32)), BigUint::from(18u32));
+
+        let pi = prime_pi_approx(&BigUint::from(1_000_000u32))
+            .to_u64()
+            .unwrap();
+        assert!(77_713 < pi && pi < 79_283);
+
+        // π(2^64)
+        let pi = prime_pi_approx(&(BigUint::one() << 64)).to_u64().unwrap();
+        assert!(425_230_627_751_182_526 < pi && pi < 426_081_940_319_252_960);
+    }
+
+    #[test]
+    fn test_nth_prime_approx() {
+        let primes = primes_in_range(&BigUint::zero(), &BigUint::from(64u32));
+        for (n, p) in (1u32..).zip(primes) {
+            assert_eq!(nth_prime_approx(&BigUint::from(n)), p);
+        }
+
+        let p = nth_prime_approx(&BigUint::from(1_000_000u32))
+            .to_u64()
+            .unwrap();
+        assert!(15_331_004 < p && p < 15_640_722);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no zeroth prime")]
+    fn test_nth_prime_approx_zero() {
+        nth_prime_approx(&BigUint::zero());
+    }
+
+    #[test]
+    fn test_lucas_sequence() {
+        fn naive(p: i64, q: i64, k: usize) -> (BigInt, BigInt) {
+            let (p, q) = (BigInt::from(p), BigInt::from(q));
+            let (mut u, mut u1) = (BigInt::zero(), BigInt::one());
+            let (mut v, mut v1) = (BigInt::from(2), p.clone());
+            for _ in 0..k {
+                let u2 = &p * &u1 - &q * &u;
+                let v2 = &p * &v1 - &q * &v;
+                u = core::mem::replace(&mut u1, u2);
+                v = core::mem::replace(&mut v1, v2);
+            }
+            (u, v)
+        }
+
+        let moduli = [1u32, 2, 12, 97, 1 << 20, 1_000_000_007];
+        for &(p, q) in &[(1, -1), (2, -1), (3, 2), (-4, 7), (5, 0), (0, 3)] {
+            let (pb, qb) = (BigInt::from(p), BigInt::from(q));
+            for k in 0..80 {
+                let kb = BigUint::from(k as u32);
+                let (u, v) = naive(p, q, k);
+                assert_eq!(lucas_sequence(&pb, &qb, &kb, None), (u.clone(), v.clone()));
+                for &m in &moduli {
+                    let mb = BigInt::from(m);
+                    assert_eq!(
+                        lucas_sequence(&pb, &qb, &kb, Some(&BigUint::from(m))),
+                        (u.mod_floor(&mb), v.mod_floor(&mb)),
+                        "P={} Q={} k={} m={}",
+                        p,
+                        q,
+                        k,
+                        m
+                    );
+                }
+            }
+        }
+
+        // U(k)(3, 2) = 2^k - 1 and V(k)(3, 2) = 2^k + 1.
+        let m = BigUint::from(1_000_000_007u32);
+        let k = BigUint::from(10u32).pow(30u32);
+        let (u, v) = lucas_sequence(&BigInt::from(3), &BigInt::from(2), &k, Some(&m));
+        let pow = BIG_2.modpow(&k, &m);
+        assert_eq!(u, BigInt::from((&pow + &m - 1u32) % &m));
+        assert_eq!(v, BigInt::from((&pow + 1u32) % &m));
+    }
 }
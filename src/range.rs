@@ -0,0 +1,165 @@
+//! Iterators over ranges of `BigUint` and `BigInt` values.
+//!
+//! The standard `a..b` syntax needs the unstable `Step` trait to iterate, so
+//! these helpers provide the same loops without the manual increments.
+//!
+//! # Example
+//!
+//! ```
+//! extern crate num_bigint_dig as num_bigint;
+//!
+//! use num_bigint::range::range_step;
+//! use num_bigint::BigUint;
+//!
+//! let start = BigUint::from(10u32);
+//! let stop = BigUint::from(20u32);
+//! let odd: Vec<BigUint> = range_step(start + 1u32, stop, BigUint::from(2u32)).collect();
+//! assert_eq!(odd.len(), 5);
+//! ```
+
+use core::ops::{AddAssign, SubAssign};
+
+use num_traits::Zero;
+
+/// An iterator over the half-open range `[start, stop)`, counting up by one.
+#[derive(Clone, Debug)]
+pub struct Range<T> {
+    state: T,
+    stop: T,
+}
+
+/// Returns an iterator over `[start, stop)`, counting up by one.
+#[inline]
+pub fn range<T>(start: T, stop: T) -> Range<T>
+where
+    T: Clone + PartialOrd + AddAssign<u32>,
+{
+    Range { state: start, stop }
+}
+
+impl<T> Iterator for Range<T>
+where
+    T: Clone + PartialOrd + AddAssign<u32>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.state < self.stop {
+            let result = self.state.clone();
+            self.state += 1u32;
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> DoubleEndedIterator for Range<T>
+where
+    T: Clone + PartialOrd + AddAssign<u32> + SubAssign<u32>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.state < self.stop {
+            self.stop -= 1u32;
+            Some(self.stop.clone())
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator over the closed range `[start, stop]`, counting up by one.
+#[derive(Clone, Debug)]
+pub struct RangeInclusive<T> {
+    range: Range<T>,
+    done: bool,
+}
+
+/// Returns an iterator over `[start, stop]`, counting up by one.
+#[inline]
+pub fn range_inclusive<T>(start: T, stop: T) -> RangeInclusive<T>
+where
+    T: Clone + PartialOrd + AddAssign<u32>,
+{
+    RangeInclusive {
+        range: range(start, stop),
+        done: false,
+    }
+}
+
+impl<T> Iterator for RangeInclusive<T>
+where
+    T: Clone + PartialOrd + AddAssign<u32>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        match self.range.next() {
+            Some(x) => Some(x),
+            None => {
+                if !self.done && self.range.state == self.range.stop {
+                    self.done = true;
+                    Some(self.range.stop.clone())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// An iterator from `start` towards `stop` (exclusive) by a fixed `step`.
+#[derive(Clone, Debug)]
+pub struct RangeStep<T> {
+    state: T,
+    stop: T,
+    step: T,
+    rev: bool,
+}
+
+/// Returns an iterator from `start` towards `stop` (exclusive) by `step`.
+///
+/// A negative `step` counts down, in which case the iterator stops once the
+/// value is no longer greater than `stop`.
+///
+/// Panics if `step` is zero.
+#[inline]
+pub fn range_step<T>(start: T, stop: T, step: T) -> RangeStep<T>
+where
+    T: Clone + PartialOrd + Zero + for<'a> AddAssign<&'a T>,
+{
+    assert!(!step.is_zero(), "range_step: step cannot be zero");
+    let rev = step < T::zero();
+    RangeStep {
+        state: start,
+        stop,
+        step,
+        rev,
+    }
+}
+
+impl<T> Iterator for RangeStep<T>
+where
+    T: Clone + PartialOrd + Zero + for<'a> AddAssign<&'a T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let in_range = if self.rev {
+            self.state > self.stop
+        } else {
+            self.state < self.stop
+        };
+        if in_range {
+            let result = self.state.clone();
+            self.state += &self.step;
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
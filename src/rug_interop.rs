@@ -0,0 +1,86 @@
+//! Conversions between `BigUint`/`BigInt` and `rug::Integer`.
+//!
+//! The digits are copied directly in both directions, without going through a string.
+
+use core::convert::TryFrom;
+
+use rug::integer::Order;
+use rug::Integer;
+use smallvec::SmallVec;
+
+use crate::big_digit::BigDigit;
+use crate::{BigInt, BigUint, Sign, TryFromBigIntError};
+
+/// Copies the magnitude of `n`.
+fn magnitude_of(n: &Integer) -> BigUint {
+    let digits = n.to_digits::<BigDigit>(Order::Lsf);
+    BigUint::new_native(SmallVec::from_vec(digits))
+}
+
+impl From<&BigUint> for Integer {
+    fn from(n: &BigUint) -> Self {
+        Integer::from_digits(&n.data[..], Order::Lsf)
+    }
+}
+
+impl From<BigUint> for Integer {
+    fn from(n: BigUint) -> Self {
+        Integer::from(&n)
+    }
+}
+
+impl From<&BigInt> for Integer {
+    fn from(n: &BigInt) -> Self {
+        let magnitude = Integer::from(&n.data);
+        if n.sign == Sign::Minus {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl From<BigInt> for Integer {
+    fn from(n: BigInt) -> Self {
+        Integer::from(&n)
+    }
+}
+
+impl TryFrom<&Integer> for BigUint {
+    type Error = TryFromBigIntError<()>;
+
+    /// Fails if `n` is negative.
+    fn try_from(n: &Integer) -> Result<Self, Self::Error> {
+        if n.is_negative() {
+            Err(TryFromBigIntError { original: () })
+        } else {
+            Ok(magnitude_of(n))
+        }
+    }
+}
+
+impl TryFrom<Integer> for BigUint {
+    type Error = TryFromBigIntError<Integer>;
+
+    /// Fails if `n` is negative.
+    fn try_from(n: Integer) -> Result<Self, Self::Error> {
+        BigUint::try_from(&n).map_err(|_| TryFromBigIntError { original: n })
+    }
+}
+
+impl From<&Integer> for BigInt {
+    fn from(n: &Integer) -> Self {
+        let sign = if n.is_negative() {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        BigInt::from_biguint(sign, magnitude_of(n))
+    }
+}
+
+impl From<Integer> for BigInt {
+    fn from(n: Integer) -> Self {
+        BigInt::from(&n)
+    }
+}
@@ -0,0 +1,67 @@
+//! `JsonSchema` implementations for `BigUint` and `BigInt`.
+//!
+//! The schemas describe the decimal strings written and accepted by
+//! [`serde_string`](../serde_string/index.html), so generated OpenAPI documents match fields
+//! declared with `#[serde(with = "num_bigint_dig::serde_string")]`. Fields using the default
+//! digit sequence representation need their own `#[schemars(with = "...")]` instead.
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Metadata, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
+
+use crate::{BigInt, BigUint};
+
+/// Decimal digits without leading zeros.
+const BIGUINT_PATTERN: &str = "^(0|[1-9][0-9]*)$";
+
+/// Decimal digits without leading zeros, with a `-` on nonzero values only.
+const BIGINT_PATTERN: &str = "^(0|-?[1-9][0-9]*)$";
+
+/// Builds a string schema restricted to `pattern`.
+fn decimal_string(pattern: &str, description: &str) -> Schema {
+    SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_owned()),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(pattern.to_owned()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl JsonSchema for BigUint {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "BigUint".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        decimal_string(BIGUINT_PATTERN, "An arbitrary precision unsigned integer")
+    }
+}
+
+impl JsonSchema for BigInt {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "BigInt".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        decimal_string(BIGINT_PATTERN, "An arbitrary precision signed integer")
+    }
+}
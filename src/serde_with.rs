@@ -0,0 +1,253 @@
+//! Helpers for `#[serde(with = "...")]` that represent `BigUint` and `BigInt` as strings.
+//!
+//! The default `Serialize` implementations write the internal digits as a sequence of
+//! `u32`, which is compact but unreadable outside of Rust. These modules write the value as
+//! a string instead, and only accept strings in exactly that form when deserializing.
+
+use alloc::string::String;
+use core::fmt;
+
+use num_traits::{Num, Zero};
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+
+use crate::{BigInt, BigUint, Sign};
+
+/// Integer types that `serde_string` and `serde_hex` can write as strings: `BigUint` and
+/// `BigInt`.
+///
+/// Generic code can use it to bound the type it passes to those modules:
+///
+/// ```
+/// use num_bigint_dig::{serde_string, StringInteger};
+/// use serde::Serializer;
+///
+/// fn write_decimal<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     T: StringInteger,
+///     S: Serializer,
+/// {
+///     serde_string::serialize(value, serializer)
+/// }
+/// ```
+pub trait StringInteger: Sized {
+    #[doc(hidden)]
+    fn to_string_radix(&self, radix: u32) -> String;
+
+    #[doc(hidden)]
+    fn from_magnitude(negative: bool, magnitude: BigUint) -> Option<Self>;
+}
+
+impl StringInteger for BigUint {
+    fn to_string_radix(&self, radix: u32) -> String {
+        if radix == 16 {
+            let mut s = String::from("0x");
+            s.push_str(&self.to_str_radix(16));
+            s
+        } else {
+            self.to_str_radix(radix)
+        }
+    }
+
+    fn from_magnitude(negative: bool, magnitude: BigUint) -> Option<Self> {
+        if negative {
+            None
+        } else {
+            Some(magnitude)
+        }
+    }
+}
+
+impl StringInteger for BigInt {
+    fn to_string_radix(&self, radix: u32) -> String {
+        let mut s = String::new();
+        if self.sign() == Sign::Minus {
+            s.push('-');
+        }
+        s.push_str(&self.data.to_string_radix(radix));
+        s
+    }
+
+    fn from_magnitude(negative: bool, magnitude: BigUint) -> Option<Self> {
+        let sign = if negative { Sign::Minus } else { Sign::Plus };
+        Some(BigInt::from_biguint(sign, magnitude))
+    }
+}
+
+/// Parses `s` in the strict format written by `StringInteger::to_string_radix`.
+fn parse_strict<T: StringInteger>(s: &str, radix: u32) -> Option<T> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let digits = if radix == 16 {
+        digits.strip_prefix("0x")?
+    } else {
+        digits
+    };
+
+    // from_str_radix also allows signs and underscores, which are rejected here.
+    if digits.is_empty() || !digits.bytes().all(|b| (b as char).is_digit(radix)) {
+        return None;
+    }
+    if radix == 10 && digits.len() > 1 && digits.starts_with('0') {
+        return None;
+    }
+
+    let magnitude = BigUint::from_str_radix(digits, radix).ok()?;
+    if negative && magnitude.is_zero() {
+        return None;
+    }
+    T::from_magnitude(negative, magnitude)
+}
+
+struct StrictVisitor<T> {
+    radix: u32,
+    marker: core::marker::PhantomData<T>,
+}
+
+impl<'de, T: StringInteger> Visitor<'de> for StrictVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.radix == 16 {
+            formatter.write_str("a hexadecimal integer string starting with 0x")
+        } else {
+            formatter.write_str("a decimal integer string without leading zeros")
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        parse_strict(v, self.radix).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+fn deserialize_radix<'de, D, T>(deserializer: D, radix: u32) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: StringInteger,
+{
+    deserializer.deserialize_str(StrictVisitor {
+        radix,
+        marker: core::marker::PhantomData,
+    })
+}
+
+/// Represents a `BigUint` or `BigInt` as a decimal string, such as `"-1234"`.
+///
+/// Deserializing accepts an optional `-` for `BigInt`, followed by decimal digits without
+/// leading zeros. Signs on `BigUint`, `+`, `-0`, underscores and whitespace are rejected.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::BigUint;
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_test::{assert_tokens, Token};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Account {
+///     #[serde(with = "num_bigint_dig::serde_string")]
+///     balance: BigUint,
+/// }
+///
+/// let account = Account {
+///     balance: BigUint::from(1234u32),
+/// };
+/// assert_tokens(
+///     &account,
+///     &[
+///         Token::Struct { name: "Account", len: 1 },
+///         Token::Str("balance"),
+///         Token::Str("1234"),
+///         Token::StructEnd,
+///     ],
+/// );
+/// ```
+pub mod serde_string {
+    use super::{deserialize_radix, StringInteger};
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes `value` as a decimal string.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: StringInteger,
+        S: Serializer,
+    {
+        super::serialize_radix(value, serializer, 10)
+    }
+
+    /// Deserializes a decimal string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: StringInteger,
+        D: Deserializer<'de>,
+    {
+        deserialize_radix(deserializer, 10)
+    }
+}
+
+/// Represents a `BigUint` or `BigInt` as a hexadecimal string with a `0x` prefix, such as
+/// `"-0x4d2"`.
+///
+/// Serializing writes lowercase digits without leading zeros. Deserializing accepts an
+/// optional `-` for `BigInt`, then `0x` and hexadecimal digits of either case, which may
+/// have leading zeros. Signs on `BigUint`, `+`, `-0x0`, a missing prefix, underscores and
+/// whitespace are rejected.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::BigInt;
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_test::{assert_tokens, Token};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Offset {
+///     #[serde(with = "num_bigint_dig::serde_hex")]
+///     delta: BigInt,
+/// }
+///
+/// let offset = Offset {
+///     delta: BigInt::from(-1234),
+/// };
+/// assert_tokens(
+///     &offset,
+///     &[
+///         Token::Struct { name: "Offset", len: 1 },
+///         Token::Str("delta"),
+///         Token::Str("-0x4d2"),
+///         Token::StructEnd,
+///     ],
+/// );
+/// ```
+pub mod serde_hex {
+    use super::{deserialize_radix, StringInteger};
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes `value` as a `0x`-prefixed hexadecimal string.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: StringInteger,
+        S: Serializer,
+    {
+        super::serialize_radix(value, serializer, 16)
+    }
+
+    /// Deserializes a `0x`-prefixed hexadecimal string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: StringInteger,
+        D: Deserializer<'de>,
+    {
+        deserialize_radix(deserializer, 16)
+    }
+}
+
+fn serialize_radix<T, S>(value: &T, serializer: S, radix: u32) -> Result<S::Ok, S::Error>
+where
+    T: StringInteger,
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_string_radix(radix))
+}
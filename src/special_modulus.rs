@@ -0,0 +1,133 @@
+//! Reduction modulo numbers of the form `2^k - c` with a small `c`.
+
+use core::ops::{Rem, RemAssign};
+
+use num_traits::{One, Zero};
+
+use crate::BigUint;
+
+/// Precomputed state for reducing values modulo a pseudo-Mersenne number
+/// `m = 2^k - c`.
+///
+/// Since `2^k` is congruent to `c` modulo `m`, the bits of `x` above `k` can
+/// be folded back in as `(x mod 2^k) + (x >> k) * c`. With a small `c` every
+/// fold removes about `k - bits(c)` bits, so reduction takes a few shifts and
+/// one short multiplication instead of a long division. Mersenne numbers
+/// (`c = 1`), moduli like `2^255 - 19` and powers of two (`c = 0`) all
+/// qualify.
+///
+/// A reducer can be used directly through [`rem`](#method.rem), or as the
+/// right hand side of `%` and `%=`.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, SpecialModulus};
+///
+/// let p = (BigUint::from(1u32) << 255) - 19u32;
+/// let reducer = SpecialModulus::new(&p).unwrap();
+///
+/// let x = &p * &p + 123u32;
+/// assert_eq!(reducer.rem(&x), BigUint::from(123u32));
+/// assert_eq!(&x % &reducer, &x % &p);
+///
+/// assert!(SpecialModulus::new(&BigUint::from(1_000_003u32)).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpecialModulus {
+    m: BigUint,
+    k: usize,
+    c: BigUint,
+}
+
+impl SpecialModulus {
+    /// Returns a reducer for `m` if it is `2^k - c` with `c` at most `k / 2`
+    /// bits long, or `None` if `m` doesn't have that form or is less than 2.
+    pub fn new(m: &BigUint) -> Option<SpecialModulus> {
+        if *m <= BigUint::one() {
+            return None;
+        }
+
+        let bits = m.bits();
+        let (k, c) = if m.trailing_zeros() == Some(bits - 1) {
+            (bits - 1, BigUint::zero())
+        } else {
+            (bits, (BigUint::one() << bits) - m)
+        };
+        if c.bits() > k / 2 {
+            return None;
+        }
+
+        Some(SpecialModulus { m: m.clone(), k, c })
+    }
+
+    /// Returns a reducer for `2^k - c`, without the bound on the length of `c`
+    /// that [`new`](#method.new) enforces. `c` must be below `2^(k - 1)`.
+    #[cfg(feature = "known-primes")]
+    pub(crate) fn from_form(k: usize, c: BigUint) -> SpecialModulus {
+        let m = (BigUint::one() << k) - &c;
+        SpecialModulus { m, k, c }
+    }
+
+    /// Returns the modulus.
+    #[inline]
+    pub fn modulus(&self) -> &BigUint {
+        &self.m
+    }
+
+    /// Returns `k` and `c` such that the modulus is `2^k - c`.
+    #[inline]
+    pub fn form(&self) -> (usize, &BigUint) {
+        (self.k, &self.c)
+    }
+
+    /// Returns `x % m`.
+    pub fn rem(&self, x: &BigUint) -> BigUint {
+        let mut r = x.clone();
+        self.reduce(&mut r);
+        r
+    }
+
+    fn reduce(&self, x: &mut BigUint) {
+        while x.bits() > self.k {
+            let hi = &*x >> self.k;
+            x.keep_low_bits_assign(self.k);
+            if self.c.is_one() {
+                *x += hi;
+            } else {
+                x.add_mul_assign(&hi, &self.c);
+            }
+        }
+
+        // Now x < 2^k = m + c, and c < m.
+        if *x >= self.m {
+            *x -= &self.m;
+        }
+    }
+}
+
+impl<'a, 'b> Rem<&'b SpecialModulus> for &'a BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn rem(self, reducer: &SpecialModulus) -> BigUint {
+        reducer.rem(self)
+    }
+}
+
+impl<'a> Rem<&'a SpecialModulus> for BigUint {
+    type Output = BigUint;
+
+    #[inline]
+    fn rem(mut self, reducer: &SpecialModulus) -> BigUint {
+        reducer.reduce(&mut self);
+        self
+    }
+}
+
+impl<'a> RemAssign<&'a SpecialModulus> for BigUint {
+    #[inline]
+    fn rem_assign(&mut self, reducer: &SpecialModulus) {
+        reducer.reduce(self);
+    }
+}
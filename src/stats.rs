@@ -0,0 +1,121 @@
+//! Per-thread counters of the work done by the arithmetic routines, behind
+//! the `stats` feature.
+//!
+//! The counters make it possible to compare algorithm variants by the
+//! number of digit operations they do rather than by wall clock time.
+
+#[cfg(feature = "stats")]
+use core::cell::Cell;
+#[cfg(feature = "stats")]
+use core::ops::Sub;
+
+#[cfg(feature = "stats")]
+use crate::VEC_SIZE;
+
+/// Counts of the operations done on the current thread.
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// Digit by digit multiplications done by the multiply-accumulate kernel,
+    /// which does the bulk of multiplication, squaring and Montgomery
+    /// reduction.
+    pub limb_muls: u64,
+    /// Double digit by digit divisions, done once per quotient digit of long
+    /// division and once per digit of division by a single digit.
+    pub limb_divs: u64,
+    /// Digit buffers allocated on the heap for products, quotients and left
+    /// shifts. Other allocations, such as clones, are not counted.
+    pub allocations: u64,
+}
+
+#[cfg(feature = "stats")]
+impl Sub for Stats {
+    type Output = Stats;
+
+    fn sub(self, other: Stats) -> Stats {
+        Stats {
+            limb_muls: self.limb_muls.wrapping_sub(other.limb_muls),
+            limb_divs: self.limb_divs.wrapping_sub(other.limb_divs),
+            allocations: self.allocations.wrapping_sub(other.allocations),
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+std::thread_local! {
+    static STATS: Cell<Stats> = Cell::new(Stats::default());
+}
+
+/// Returns the counts for the current thread since it started, or since the
+/// last call to [`reset`].
+///
+/// Subtracting two snapshots gives the work done in between.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint_dig::{stats, BigUint};
+///
+/// let x = (BigUint::from(1u32) << 4096) - 1u32;
+/// let before = stats::snapshot();
+/// let _ = &x * &x;
+/// let used = stats::snapshot() - before;
+/// assert!(used.limb_muls > 0);
+/// assert_eq!(used.limb_divs, 0);
+/// ```
+#[cfg(feature = "stats")]
+pub fn snapshot() -> Stats {
+    STATS.with(Cell::get)
+}
+
+/// Sets the counts for the current thread back to zero.
+#[cfg(feature = "stats")]
+pub fn reset() {
+    STATS.with(|stats| stats.set(Stats::default()));
+}
+
+#[cfg(feature = "stats")]
+#[inline]
+fn update<F: FnOnce(&mut Stats)>(f: F) {
+    STATS.with(|stats| {
+        let mut s = stats.get();
+        f(&mut s);
+        stats.set(s);
+    });
+}
+
+/// Counts `n` digit multiplications.
+#[cfg(feature = "stats")]
+#[inline]
+pub(crate) fn record_muls(n: usize) {
+    update(|s| s.limb_muls += n as u64);
+}
+
+/// Counts one digit division.
+#[cfg(feature = "stats")]
+#[inline]
+pub(crate) fn record_div() {
+    update(|s| s.limb_divs += 1);
+}
+
+/// Counts an allocation of `len` digits, if it is too long to be stored
+/// inline.
+#[cfg(feature = "stats")]
+#[inline]
+pub(crate) fn record_alloc(len: usize) {
+    if len > VEC_SIZE {
+        update(|s| s.allocations += 1);
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+#[inline]
+pub(crate) fn record_muls(_n: usize) {}
+
+#[cfg(not(feature = "stats"))]
+#[inline]
+pub(crate) fn record_div() {}
+
+#[cfg(not(feature = "stats"))]
+#[inline]
+pub(crate) fn record_alloc(_len: usize) {}
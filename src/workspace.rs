@@ -0,0 +1,143 @@
+//! Reusable scratch storage for repeated arithmetic.
+
+use core::mem;
+
+use num_traits::Zero;
+
+use crate::algorithms::div_rem_core;
+use crate::big_digit::{self, BigDigit};
+#[cfg(feature = "known-primes")]
+use crate::known_primes::KnownPrime;
+use crate::BigUint;
+
+/// Scratch storage that can be shared across many operations.
+///
+/// Products, quotients and remainders are normally returned as freshly
+/// allocated values. The `_with_workspace` methods on [`BigUint`] instead
+/// write their intermediates into a `Workspace`, so a loop that performs
+/// the same kind of operation many times only allocates until the buffers
+/// have grown to the size it needs.
+///
+/// # Example
+///
+/// ```
+/// use num_bigint_dig::{BigUint, Workspace};
+///
+/// let mut ws = Workspace::new();
+/// let m = BigUint::from(1_000_000_007u32);
+/// let mut x = BigUint::from(3u32);
+/// for _ in 0..10 {
+///     let y = x.clone();
+///     x.mul_mod_with_workspace(&y, &m, &mut ws);
+/// }
+/// assert_eq!(x, BigUint::from(3u32).modpow(&BigUint::from(1024u32), &m));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Workspace {
+    prod: BigUint,
+    quot: BigUint,
+    rem: BigUint,
+    divisor: BigUint,
+    a0: BigUint,
+    a0_prod: BigUint,
+}
+
+impl Workspace {
+    /// Creates an empty workspace. Nothing is allocated until first use.
+    pub fn new() -> Workspace {
+        Workspace::default()
+    }
+
+    /// Sets `x` to `x * y`.
+    pub(crate) fn mul(&mut self, x: &mut BigUint, y: &BigUint) {
+        self.prod.mul_assign_reuse(x, y);
+        mem::swap(x, &mut self.prod);
+    }
+
+    /// Sets `x` to `x / d` and `rem` to `x % d`.
+    pub(crate) fn div_rem(&mut self, x: &mut BigUint, d: &BigUint, rem: &mut BigUint) {
+        assert!(!d.is_zero(), "attempt to divide by zero");
+        if d.data.len() == 1 || *x < *d {
+            x.div_rem_assign(d, rem);
+            return;
+        }
+
+        // Same normalization as `div_rem`, done in place on copies we own.
+        let shift = d.data.last().unwrap().leading_zeros() as usize;
+        copy_shl(&mut self.divisor, d, shift);
+        copy_shl(rem, x, shift);
+
+        div_rem_core(
+            rem,
+            &self.divisor,
+            &mut self.quot,
+            &mut self.a0,
+            &mut self.a0_prod,
+        );
+        mem::swap(x, &mut self.quot);
+        shr_in_place(rem, shift);
+    }
+
+    /// Sets `x` to `x * y % m`.
+    pub(crate) fn mul_mod(&mut self, x: &mut BigUint, y: &BigUint, m: &BigUint) {
+        self.mul(x, y);
+        self.reduce(x, m);
+    }
+
+    /// Sets `x` to `x * x % m`.
+    pub(crate) fn sqr_mod(&mut self, x: &mut BigUint, m: &BigUint) {
+        self.prod.mul_assign_reuse(x, x);
+        mem::swap(x, &mut self.prod);
+        self.reduce(x, m);
+    }
+
+    /// Sets `x` to `x % m`.
+    pub(crate) fn reduce(&mut self, x: &mut BigUint, m: &BigUint) {
+        #[cfg(feature = "known-primes")]
+        {
+            if let Some(prime) = KnownPrime::detect(m) {
+                *x = prime.rem(x, m);
+                return;
+            }
+        }
+
+        let mut rem = mem::take(&mut self.rem);
+        self.div_rem(x, m, &mut rem);
+        mem::swap(x, &mut rem);
+        self.rem = rem;
+    }
+}
+
+/// Sets `dst` to `src << shift`, for `shift` less than one digit.
+fn copy_shl(dst: &mut BigUint, src: &BigUint, shift: usize) {
+    dst.data.clear();
+    dst.data.extend_from_slice(&src.data);
+    if shift == 0 {
+        return;
+    }
+
+    let mut carry: BigDigit = 0;
+    for d in dst.data.iter_mut() {
+        let next = *d >> (big_digit::BITS - shift);
+        *d = (*d << shift) | carry;
+        carry = next;
+    }
+    if carry != 0 {
+        dst.data.push(carry);
+    }
+}
+
+/// Sets `x` to `x >> shift`, for `shift` less than one digit.
+fn shr_in_place(x: &mut BigUint, shift: usize) {
+    if shift == 0 || x.is_zero() {
+        return;
+    }
+
+    let mut borrow: BigDigit = 0;
+    for d in x.data.iter_mut().rev() {
+        let next = *d << (big_digit::BITS - shift);
+        *d = (*d >> shift) | borrow;
+        borrow = next;
+    }
+    x.normalize();
+}
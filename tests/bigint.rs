@@ -183,6 +183,45 @@ fn test_from_signed_bytes_be() {
     check(&[0xff, 0x40], "-192");
 }
 
+#[test]
+fn test_signed_bytes_be_sized() {
+    let check = |n: i64, width: usize, bytes: &[u8]| {
+        let n = BigInt::from(n);
+        assert_eq!(n.to_signed_bytes_be_sized(width).as_deref(), Ok(bytes));
+        assert_eq!(BigInt::from_signed_bytes_be_sized(bytes, width), Ok(n));
+    };
+
+    check(0, 0, &[]);
+    check(0, 3, &[0, 0, 0]);
+    check(1, 3, &[0, 0, 1]);
+    check(-1, 1, &[0xff]);
+    check(-1, 3, &[0xff, 0xff, 0xff]);
+    check(127, 1, &[0x7f]);
+    check(-128, 1, &[0x80]);
+    check(128, 2, &[0, 0x80]);
+    check(-129, 2, &[0xff, 0x7f]);
+    check(-192, 4, &[0xff, 0xff, 0xff, 0x40]);
+    check(i64::MIN, 8, &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+
+    assert!(BigInt::from(128).to_signed_bytes_be_sized(1).is_err());
+    assert!(BigInt::from(-129).to_signed_bytes_be_sized(1).is_err());
+    assert!(BigInt::from(1).to_signed_bytes_be_sized(0).is_err());
+    assert!(BigInt::from_signed_bytes_be_sized(&[0, 1], 3).is_err());
+    assert!(BigInt::from_signed_bytes_be_sized(&[0, 0, 0, 1], 3).is_err());
+
+    // The full range of an EVM int256.
+    let max = (BigInt::one() << 255) - 1i32;
+    let min = -(BigInt::one() << 255);
+    let mut bytes = vec![0xff; 32];
+    bytes[0] = 0x7f;
+    assert_eq!(max.to_signed_bytes_be_sized(32).unwrap(), bytes);
+    let mut bytes = vec![0; 32];
+    bytes[0] = 0x80;
+    assert_eq!(min.to_signed_bytes_be_sized(32).unwrap(), bytes);
+    assert!((max + 1i32).to_signed_bytes_be_sized(32).is_err());
+    assert!((min - 1i32).to_signed_bytes_be_sized(32).is_err());
+}
+
 #[test]
 fn test_signed_bytes_be_round_trip() {
     for i in -0x1FFFF..0x20000 {
@@ -242,6 +281,44 @@ fn test_cmp() {
     }
 }
 
+#[test]
+fn test_cmp_primitive() {
+    let values = [i64::MIN, i64::MIN + 1, -256, -1, 0, 1, 255, i64::MAX];
+    for &a in &values {
+        let n = BigInt::from(a);
+        for &b in &values {
+            assert_eq!(n.cmp_i64(b), a.cmp(&b));
+            assert_eq!(n.eq_i64(b), a == b);
+        }
+    }
+    let big = BigInt::from(u64::MAX);
+    assert_eq!(big.cmp_i64(i64::MAX), Greater);
+    assert_eq!((-big).cmp_i64(i64::MIN), Less);
+}
+
+#[test]
+#[cfg(has_i128)]
+fn test_cmp_i128() {
+    let values = [
+        i128::MIN,
+        i64::MIN as i128 - 1,
+        -1,
+        0,
+        1,
+        u64::MAX as i128,
+        i128::MAX,
+    ];
+    for &a in &values {
+        let n = BigInt::from(a);
+        for &b in &values {
+            assert_eq!(n.cmp_i128(b), a.cmp(&b));
+        }
+    }
+    let big = BigInt::one() << 128;
+    assert_eq!(big.cmp_i128(i128::MAX), Greater);
+    assert_eq!((-big).cmp_i128(i128::MIN), Less);
+}
+
 fn hash<T: Hash>(x: &T) -> u64 {
     let mut hasher = <RandomState as BuildHasher>::Hasher::new();
     x.hash(&mut hasher);
@@ -263,6 +340,22 @@ fn test_hash() {
     assert!(hash(&c) != hash(&f));
 }
 
+#[test]
+fn test_hash_primitive() {
+    for &i in &[0i64, 1, -1, i64::MAX, i64::MIN] {
+        assert_eq!(hash(&BigInt::from(i)), hash(&i));
+    }
+    for &i in &[i128::from(i64::MIN) - 1, i128::MIN] {
+        assert_eq!(hash(&BigInt::from(i)), hash(&i));
+    }
+    assert_eq!(hash(&BigInt::from(u64::MAX)), hash(&u64::MAX));
+    assert_eq!(hash(&BigInt::from(u128::MAX)), hash(&u128::MAX));
+
+    let big = BigUint::from(u128::MAX) << 1;
+    assert_eq!(hash(&BigInt::from(big.clone())), hash(&big));
+    assert!(hash(&-BigInt::from(big.clone())) != hash(&big));
+}
+
 #[test]
 fn test_convert_i64() {
     fn check(b1: BigInt, i: i64) {
@@ -379,6 +472,28 @@ fn test_convert_u128() {
     );
 }
 
+#[test]
+fn test_wrapping_conversions() {
+    for &i in &[0i64, 1, -1, 12345, -12345, i64::MIN, i64::MAX] {
+        let n = BigInt::from(i);
+        assert_eq!(n.to_u64_wrapping(), i as u64);
+        #[cfg(has_i128)]
+        assert_eq!(n.to_u128_wrapping(), i as i128 as u128);
+        for bits in 0..64 {
+            let mask = (1u64 << bits) - 1;
+            assert_eq!(n.truncate_to_bits(bits), BigUint::from(i as u64 & mask));
+        }
+    }
+
+    let n = -(BigInt::one() << 100) - 7u32;
+    assert_eq!(n.to_u64_wrapping(), (-7i64) as u64);
+    for bits in 0..200 {
+        let modulus = BigInt::one() << bits;
+        let expected = n.mod_floor(&modulus).to_biguint().unwrap();
+        assert_eq!(n.truncate_to_bits(bits), expected);
+    }
+}
+
 #[test]
 fn test_convert_f32() {
     fn check(b1: &BigInt, f: f32) {
@@ -564,6 +679,21 @@ fn test_convert_to_biguint() {
     assert_eq!(negative.to_biguint(), None);
 }
 
+#[test]
+fn test_into_parts() {
+    let magnitude = BigUint::new(vec![1, 2, 3]);
+    for &sign in &[Plus, Minus] {
+        let n = BigInt::from_parts(sign, magnitude.clone());
+        assert_eq!(n.magnitude(), &magnitude);
+        assert_eq!(n.into_parts(), (sign, magnitude.clone()));
+    }
+
+    // Zero always has `NoSign`, whichever sign it's built with.
+    assert_eq!(BigInt::zero().into_parts(), (NoSign, BigUint::zero()));
+    assert_eq!(BigInt::from_parts(Minus, BigUint::zero()).sign(), NoSign);
+    assert!(BigInt::from_parts(NoSign, magnitude).is_zero());
+}
+
 #[test]
 fn test_convert_from_uint() {
     macro_rules! check {
@@ -767,6 +897,41 @@ fn test_mul() {
     }
 }
 
+#[test]
+fn test_mixed_ops() {
+    for elm in MUL_TRIPLES.iter() {
+        let (a_vec, b_vec, c_vec) = *elm;
+        let a = BigInt::from_slice(Plus, a_vec);
+        let b = BigUint::from_slice(b_vec);
+        let c = BigInt::from_slice(Plus, c_vec);
+        let (na, nc) = (-&a, -&c);
+        let ib = BigInt::from(b.clone());
+
+        assert_op!(na + b == &na + &ib);
+        assert_op!(b + na == &na + &ib);
+        assert_op!(na - b == &na - &ib);
+        assert_op!(b - na == &ib - &na);
+        assert_op!(na * b == nc);
+        assert_op!(b * na == nc);
+
+        assert_assign_op!(na += b == &na + &ib);
+        assert_assign_op!(na -= b == &na - &ib);
+        assert_assign_op!(na *= b == nc);
+
+        if !b.is_zero() {
+            assert_op!(nc / b == na);
+            assert_op!(nc % b == BigInt::zero());
+            assert_assign_op!(nc /= b == na);
+            assert_assign_op!(nc %= b == BigInt::zero());
+        }
+        if !a.is_zero() {
+            let c = c.to_biguint().unwrap();
+            assert_op!(c / na == -&ib);
+            assert_op!(c % na == BigInt::zero());
+        }
+    }
+}
+
 #[test]
 fn test_div_mod_floor() {
     fn check_sub(a: &BigInt, b: &BigInt, ans_d: &BigInt, ans_m: &BigInt) {
@@ -982,6 +1147,36 @@ fn test_gcd() {
     check(-4, -2, 2);
 }
 
+#[test]
+fn test_integer_extended_gcd() {
+    fn check(a: &BigInt, b: &BigInt) {
+        let e = Integer::extended_gcd(a, b);
+        assert_eq!(e.gcd, a.gcd(b), "gcd({}, {})", a, b);
+        assert_eq!(a * &e.x + b * &e.y, e.gcd, "bezout({}, {})", a, b);
+    }
+
+    let big = BigInt::from(3i32).pow(200u32) + 7i32;
+    let values = [
+        BigInt::zero(),
+        BigInt::one(),
+        BigInt::from(-1),
+        BigInt::from(56),
+        BigInt::from(-42),
+        BigInt::from(1i64 << 40) * 15,
+        big.clone(),
+        -big.clone() * 35,
+    ];
+    for a in &values {
+        for b in &values {
+            check(a, b);
+        }
+    }
+
+    let e = Integer::extended_gcd_lcm(&BigInt::from(-6), &BigInt::from(4));
+    assert_eq!(e.0.gcd, BigInt::from(2));
+    assert_eq!(e.1, BigInt::from(12));
+}
+
 #[test]
 fn test_lcm() {
     fn check(a: isize, b: isize, c: isize) {
@@ -1206,6 +1401,22 @@ fn test_iter_product_generic() {
     assert_eq!(result, data.into_iter().product());
 }
 
+#[test]
+fn test_iter_product_many() {
+    let data: Vec<BigInt> = (-500i32..=500)
+        .filter(|&x| x != 0)
+        .map(BigInt::from)
+        .collect();
+    let mut expected = BigInt::one();
+    for x in &data {
+        expected *= x;
+    }
+
+    assert_eq!(expected, data.iter().product());
+    assert_eq!(expected, data[1..].iter().product::<BigInt>() * &data[0]);
+    assert_eq!(BigInt::one(), data[..0].iter().product());
+}
+
 #[test]
 fn test_pow() {
     let one = BigInt::from(1i32);
@@ -1232,3 +1443,59 @@ fn test_pow() {
     check!(u64);
     check!(usize);
 }
+
+#[test]
+fn test_midpoint() {
+    fn check(a: &BigInt, b: &BigInt) {
+        let expected = (a + b).div_floor(&BigInt::from(2));
+        assert_eq!(a.midpoint(b), expected, "midpoint({}, {})", a, b);
+        assert_eq!(b.midpoint(a), expected, "midpoint({}, {})", b, a);
+    }
+
+    let big = BigInt::one() << 1000;
+    let values = [
+        BigInt::zero(),
+        BigInt::one(),
+        -BigInt::one(),
+        BigInt::from(7),
+        BigInt::from(-10),
+        big.clone(),
+        -&big,
+        &big - 1,
+        -&big + 3,
+    ];
+    for a in &values {
+        for b in &values {
+            check(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_pow_biguint_exponent() {
+    let minus_two = BigInt::from(-2);
+    let odd = BigUint::from(101u32);
+    let even = BigUint::from(100u32);
+    assert_eq!(minus_two.pow(&odd), -(BigInt::one() << 101));
+    assert_eq!(minus_two.pow(even.clone()), BigInt::one() << 100);
+
+    assert_eq!(
+        minus_two.checked_pow_bits(&odd, 102),
+        Some(-(BigInt::one() << 101))
+    );
+    assert_eq!(minus_two.checked_pow_bits(&odd, 101), None);
+}
+
+#[test]
+fn test_checked_shift() {
+    let x = BigInt::from(-5);
+    assert_eq!(x.checked_shl(5, 8), Some(BigInt::from(-160)));
+    assert_eq!(x.checked_shl(6, 8), None);
+    assert_eq!(x.checked_shl(usize::MAX, usize::MAX), None);
+
+    // Shifting right rounds down, which can need an extra bit.
+    assert_eq!(x.checked_shr(1, 2), Some(BigInt::from(-3)));
+    assert_eq!(x.checked_shr(1, 1), None);
+    assert_eq!(x.checked_shr(100, 1), Some(BigInt::from(-1)));
+    assert_eq!(BigInt::from(5).checked_shr(100, 0), Some(BigInt::zero()));
+}
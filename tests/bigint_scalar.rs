@@ -143,3 +143,24 @@ fn test_scalar_div_rem() {
         }
     }
 }
+
+#[test]
+fn test_scalar_rem_signed_64() {
+    let x = BigInt::from(-100);
+    assert_eq!(&x % 7i64, BigInt::from(-2));
+    assert_eq!(&x % -7i64, BigInt::from(-2));
+    let mut y = x.clone();
+    y %= 30i64;
+    assert_eq!(y, BigInt::from(-10));
+}
+
+#[test]
+fn test_scalar_ref_assign() {
+    let mut x = BigInt::from(-100);
+    x += &7u8;
+    x -= &3i64;
+    x *= &-2i32;
+    x /= &4usize;
+    x %= &30u128;
+    assert_eq!(x, BigInt::from(18));
+}
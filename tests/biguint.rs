@@ -3,9 +3,15 @@ extern crate num_integer;
 extern crate num_traits;
 extern crate smallvec;
 
+use crate::num_bigint::BarrettReducer;
+use crate::num_bigint::BigUintRef;
+use crate::num_bigint::DivStrategy;
 use crate::num_bigint::Sign::Plus;
+use crate::num_bigint::SpecialModulus;
+use crate::num_bigint::Workspace;
 use crate::num_bigint::{BigInt, ToBigInt};
 use crate::num_bigint::{BigUint, ToBigUint};
+use crate::num_bigint::{Budget, BudgetExceeded};
 use num_integer::Integer;
 
 use std::cmp::Ordering::{Equal, Greater, Less};
@@ -14,14 +20,18 @@ use std::hash::{BuildHasher, Hash, Hasher};
 use std::i64;
 use std::iter::repeat;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use std::{f32, f64};
 #[cfg(has_i128)]
 use std::{i128, u128};
 use std::{u16, u32, u64, u8, usize};
 
 use num_traits::{
-    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, Num, One, Pow,
-    ToPrimitive, Zero,
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, MulAdd, MulAddAssign, Num, One,
+    Pow, ToPrimitive, Zero,
 };
 
 use num_traits::float::FloatCore;
@@ -135,6 +145,41 @@ fn test_cmp() {
     }
 }
 
+#[test]
+fn test_cmp_primitive() {
+    let values = [0u64, 1, 2, 255, 256, u64::MAX - 1, u64::MAX];
+    for &a in &values {
+        let n = BigUint::from(a);
+        for &b in &values {
+            assert_eq!(n.cmp_u64(b), a.cmp(&b));
+            assert_eq!(n.eq_u64(b), a == b);
+        }
+    }
+    let big = BigUint::one() << 64;
+    assert_eq!(big.cmp_u64(u64::MAX), Greater);
+    assert!(!big.eq_u64(0));
+}
+
+#[test]
+#[cfg(has_i128)]
+fn test_cmp_u128() {
+    let values = [
+        0u128,
+        1,
+        u64::MAX as u128,
+        1 << 64,
+        u128::MAX - 1,
+        u128::MAX,
+    ];
+    for &a in &values {
+        let n = BigUint::from(a);
+        for &b in &values {
+            assert_eq!(n.cmp_u128(b), a.cmp(&b));
+        }
+    }
+    assert_eq!((BigUint::one() << 128).cmp_u128(u128::MAX), Greater);
+}
+
 fn hash<T: Hash>(x: &T) -> u64 {
     let mut hasher = <RandomState as BuildHasher>::Hasher::new();
     x.hash(&mut hasher);
@@ -156,6 +201,25 @@ fn test_hash() {
     assert!(hash(&d) != hash(&e));
 }
 
+#[test]
+fn test_hash_primitive() {
+    use std::collections::HashSet;
+
+    for &i in &[0u64, 1, u64::from(u32::MAX), u64::MAX] {
+        assert_eq!(hash(&BigUint::from(i)), hash(&i));
+    }
+    for &i in &[u128::from(u64::MAX) + 1, u128::MAX] {
+        assert_eq!(hash(&BigUint::from(i)), hash(&i));
+    }
+
+    let big = BigUint::from(u128::MAX) << 1;
+    assert_eq!(hash(&big.as_ref_view()), hash(&big));
+
+    let set: HashSet<BigUint> = (0..100u32).map(BigUint::from).collect();
+    assert!(set.contains(&BigUint::from(42u32)));
+    assert!(!set.contains(&big));
+}
+
 // LEFT, RIGHT, AND, OR, XOR
 const BIT_TESTS: &'static [(
     &'static [u32],
@@ -376,6 +440,27 @@ fn test_shl() {
     );
 }
 
+#[test]
+fn test_checked_shift() {
+    let x = BigUint::from_str_radix("123456789abcdef0123456789", 16).unwrap();
+    let bits = x.bits();
+    for shift in [0, 1, 63, 64, 65, 200].iter().cloned() {
+        assert_eq!(x.checked_shl(shift, bits + shift), Some(&x << shift));
+        assert_eq!(x.checked_shl(shift, bits + shift - 1), None);
+        assert_eq!(x.checked_shr(shift, bits), Some(&x >> shift));
+    }
+    assert_eq!(x.checked_shr(8, bits - 9), None);
+    assert_eq!(x.checked_shr(8, bits - 8), Some(&x >> 8));
+
+    assert_eq!(x.checked_shl(usize::MAX, usize::MAX), None);
+    assert_eq!(x.checked_shl(usize::MAX - bits, 1 << 20), None);
+    assert_eq!(
+        BigUint::zero().checked_shl(usize::MAX, 0),
+        Some(BigUint::zero())
+    );
+    assert_eq!(x.checked_shr(usize::MAX, 0), Some(BigUint::zero()));
+}
+
 #[test]
 fn test_shr() {
     fn check(s: &str, shift: usize, ans: &str) {
@@ -617,6 +702,40 @@ fn test_convert_u128() {
     assert_eq!(BigUint::new(vec![N1, N1, N1, N1, N1]).to_u128(), None);
 }
 
+#[test]
+fn test_wrapping_conversions() {
+    let n = BigUint::new(vec![1, 2, 3, 4, 5]);
+    assert_eq!(n.to_u64_wrapping(), 2 << 32 | 1);
+    #[cfg(has_i128)]
+    assert_eq!(n.to_u128_wrapping(), 4 << 96 | 3 << 64 | 2 << 32 | 1);
+    assert_eq!(BigUint::zero().to_u64_wrapping(), 0);
+
+    assert_eq!(n.truncate_to_bits(0), BigUint::zero());
+    assert_eq!(n.truncate_to_bits(33), BigUint::new(vec![1]));
+    assert_eq!(n.truncate_to_bits(34), BigUint::new(vec![1, 2]));
+    assert_eq!(n.truncate_to_bits(96), BigUint::new(vec![1, 2, 3]));
+    assert_eq!(n.truncate_to_bits(1000), n);
+    assert_eq!(n.truncate_to_bits(usize::MAX), n);
+    for bits in 0..200 {
+        let mask = (BigUint::one() << bits) - 1u32;
+        assert_eq!(n.truncate_to_bits(bits), &n & &mask);
+    }
+}
+
+#[test]
+fn test_keep_low_bits_assign() {
+    let n = BigUint::new(vec![N1, 2, N1, 4, 5]);
+    for bits in 0..200 {
+        let mut m = n.clone();
+        m.keep_low_bits_assign(bits);
+        assert_eq!(m, &n % (BigUint::one() << bits));
+    }
+
+    let mut zero = BigUint::zero();
+    zero.keep_low_bits_assign(7);
+    assert!(zero.is_zero());
+}
+
 #[test]
 fn test_convert_f32() {
     fn check(b1: &BigUint, f: f32) {
@@ -839,6 +958,16 @@ fn test_sub_fail_on_underflow() {
     let _ = a - b;
 }
 
+#[test]
+fn test_sub_owned_larger_rhs() {
+    // The right operand's buffer is the larger one, so it holds the result.
+    let mut b = BigUint::one() << 1000;
+    b -= (BigUint::one() << 1000) - (BigUint::one() << 100);
+    let a = BigUint::one() << 200;
+    let expected = (BigUint::one() << 200) - (BigUint::one() << 100);
+    assert_eq!(a - b, expected);
+}
+
 #[test]
 fn test_mul() {
     for elm in MUL_TRIPLES.iter() {
@@ -865,6 +994,54 @@ fn test_mul() {
     }
 }
 
+#[test]
+fn test_mul_add() {
+    for elm in DIV_REM_QUADRUPLES.iter() {
+        let (a_vec, b_vec, c_vec, d_vec) = *elm;
+        let a = BigUint::from_slice(a_vec);
+        let b = BigUint::from_slice(b_vec);
+        let c = BigUint::from_slice(c_vec);
+        let d = BigUint::from_slice(d_vec);
+
+        assert_eq!((&b).mul_add(&c, &d), a);
+        assert_eq!((&c).mul_add(&b, &d), a);
+        assert_eq!(b.clone().mul_add(c.clone(), d.clone()), a);
+
+        let mut x = b.clone();
+        x.mul_add_assign(&c, &d);
+        assert_eq!(x, a);
+        let mut x = c.clone();
+        x.mul_add_assign(b.clone(), d.clone());
+        assert_eq!(x, a);
+    }
+}
+
+#[test]
+fn test_add_mul_assign() {
+    for elm in DIV_REM_QUADRUPLES.iter() {
+        let (a_vec, b_vec, c_vec, d_vec) = *elm;
+        let a = BigUint::from_slice(a_vec);
+        let b = BigUint::from_slice(b_vec);
+        let c = BigUint::from_slice(c_vec);
+        let d = BigUint::from_slice(d_vec);
+
+        let mut acc = d.clone();
+        acc.add_mul_assign(&b, &c);
+        assert_eq!(acc, a);
+    }
+
+    // Horner evaluation of 3x^3 + 2x^2 + x + 5 at a large x.
+    let x = BigUint::from(u64::MAX) << 100;
+    let mut acc = BigUint::zero();
+    for &k in &[3u32, 2, 1, 5] {
+        let prev = acc;
+        acc = BigUint::from(k);
+        acc.add_mul_assign(&prev, &x);
+    }
+    let expected = 3u32 * x.pow(3u32) + 2u32 * x.pow(2u32) + &x + 5u32;
+    assert_eq!(acc, expected);
+}
+
 #[test]
 fn test_div_rem() {
     for elm in MUL_TRIPLES.iter() {
@@ -1026,6 +1203,51 @@ fn test_lcm() {
     check(99, 17, 1683);
 }
 
+#[test]
+fn test_gcd_lcm_of() {
+    use crate::num_bigint::algorithms::{gcd_of, lcm_of};
+
+    let big = |v: &[u64]| -> Vec<BigUint> { v.iter().map(|&x| BigUint::from(x)).collect() };
+    assert_eq!(gcd_of(&[]), BigUint::zero());
+    assert_eq!(lcm_of(&[]), BigUint::one());
+    assert_eq!(gcd_of(&big(&[42])), BigUint::from(42u32));
+    assert_eq!(lcm_of(&big(&[42])), BigUint::from(42u32));
+    assert_eq!(gcd_of(&big(&[0, 0])), BigUint::zero());
+    assert_eq!(gcd_of(&big(&[0, 12, 0, 18])), BigUint::from(6u32));
+    assert_eq!(lcm_of(&big(&[4, 6, 0, 10])), BigUint::zero());
+    assert_eq!(gcd_of(&big(&[6, 10, 15, 30])), BigUint::one());
+
+    // Compare with a sequential fold over slices of every length up to 40.
+    let values: Vec<BigUint> = (1..=40u32)
+        .map(|i| BigUint::from(210 * i * i) << (i as usize % 7))
+        .collect();
+    for n in 0..=values.len() {
+        let values = &values[..n];
+        let gcd = values.iter().fold(BigUint::zero(), |acc, v| acc.gcd(v));
+        let lcm = values.iter().fold(BigUint::one(), |acc, v| acc.lcm(v));
+        assert_eq!(gcd_of(values), gcd);
+        assert_eq!(lcm_of(values), lcm);
+    }
+}
+
+#[test]
+fn test_product_of() {
+    use crate::num_bigint::algorithms::product_of;
+
+    assert_eq!(product_of(&[]), BigUint::one());
+    let values: Vec<BigUint> = (1..=300u32)
+        .map(|i| (BigUint::from(i) << (i as usize * 3)) + 1u32)
+        .collect();
+    for n in 0..=values.len() {
+        let values = &values[..n];
+        let product = values.iter().fold(BigUint::one(), |acc, v| acc * v);
+        assert_eq!(product_of(values), product);
+    }
+
+    let with_zero = [BigUint::from(5u32), BigUint::zero(), BigUint::from(7u32)];
+    assert_eq!(product_of(&with_zero), BigUint::zero());
+}
+
 #[test]
 fn test_is_even() {
     let one: BigUint = FromStr::from_str("1").unwrap();
@@ -1515,6 +1737,42 @@ fn test_from_str_radix() {
     assert_eq!(ff, Some(BigUint::from_slice(&[0xff])));
 }
 
+#[test]
+fn test_from_str_radix_bounded() {
+    let parse = |s: &str, radix, max_bits| BigUint::from_str_radix_bounded(s, radix, max_bits).ok();
+    let big = |v: u32| Some(BigUint::from(v));
+
+    for max_bits in 0..40 {
+        let max = (BigUint::one() << max_bits) - 1u32;
+        for radix in &[2, 3, 10, 16, 36] {
+            let s = max.to_str_radix(*radix);
+            assert_eq!(parse(&s, *radix, max_bits), Some(max.clone()));
+            let s = (&max + 1u32).to_str_radix(*radix);
+            assert_eq!(parse(&s, *radix, max_bits), None, "{} {}", s, max_bits);
+        }
+    }
+
+    // Leading zeros, separators and signs don't count towards the bound.
+    assert_eq!(parse("0000000000000000255", 10, 8), big(255));
+    assert_eq!(parse("+0_0_255", 10, 8), big(255));
+    assert_eq!(parse("0", 10, 0), big(0));
+    assert_eq!(parse("000", 10, 0), big(0));
+    assert_eq!(parse("1", 10, 0), None);
+
+    // Errors other than size are still reported as such.
+    assert_eq!(parse("", 10, 8), None);
+    assert_eq!(parse("12z", 10, 8), None);
+    assert_eq!(parse("++1", 10, 8), None);
+
+    let huge = "7".repeat(100_000);
+    assert_eq!(parse(&huge, 10, 1000), None);
+    assert!(parse(&huge, 10, 400_000).is_some());
+
+    let neg = BigInt::from_str_radix_bounded("-255", 10, 8);
+    assert_eq!(neg, Ok(BigInt::from(-255)));
+    assert!(BigInt::from_str_radix_bounded("-256", 10, 8).is_err());
+}
+
 #[test]
 fn test_all_str_radix() {
     #[allow(deprecated, unused_imports)]
@@ -1532,6 +1790,38 @@ fn test_all_str_radix() {
     }
 }
 
+#[test]
+fn test_pow2_str_radix() {
+    let mut n = BigUint::from(0x9e37_79b9_7f4a_7c15u64);
+    for _ in 0..12 {
+        for &radix in &[2u32, 4, 8, 16, 32] {
+            let expected = n
+                .to_radix_be(radix)
+                .iter()
+                .map(|&d| std::char::from_digit(u32::from(d), radix).unwrap())
+                .collect::<String>();
+            let s = n.to_str_radix(radix);
+            assert_eq!(s, expected);
+            assert_eq!(BigUint::from_str_radix(&s, radix).unwrap(), n);
+
+            let separated = s
+                .chars()
+                .enumerate()
+                .flat_map(|(i, c)| if i % 3 == 2 { vec![c, '_'] } else { vec![c] })
+                .collect::<String>();
+            assert_eq!(BigUint::from_str_radix(&separated, radix).unwrap(), n);
+
+            let bad = format!("{}{}", s, std::char::from_digit(radix, 36).unwrap());
+            assert!(BigUint::from_str_radix(&bad, radix).is_err());
+        }
+        n = &n * &n * 3u32 + 1u32;
+    }
+
+    let n = BigInt::from(-0x1234_5678_9abc_def0_i64);
+    assert_eq!(n.to_str_radix(16), "-123456789abcdef0");
+    assert_eq!(format!("{:o}", BigUint::zero()), "0");
+}
+
 #[test]
 fn test_lower_hex() {
     let a = BigUint::parse_bytes(b"A", 16).unwrap();
@@ -1628,6 +1918,123 @@ fn test_bits() {
     assert_eq!((one << 426).bits(), 427);
 }
 
+#[test]
+fn test_conditional_swap_select() {
+    let values = [
+        BigUint::zero(),
+        BigUint::one(),
+        BigUint::from(u64::MAX),
+        BigUint::one() << 64,
+        (BigUint::one() << 300) - 1u32,
+    ];
+    for a in &values {
+        for b in &values {
+            for &choice in &[false, true] {
+                let (mut x, mut y) = (a.clone(), b.clone());
+                BigUint::conditional_swap(&mut x, &mut y, choice);
+                let (ea, eb) = if choice { (b, a) } else { (a, b) };
+                assert_eq!((&x, &y), (ea, eb));
+                // `bits` reads the top digit, so this catches unnormalized results.
+                assert_eq!((x.bits(), y.bits()), (ea.bits(), eb.bits()));
+
+                let z = BigUint::conditional_select(a, b, choice);
+                assert_eq!(z, *ea);
+                assert_eq!(z.bits(), ea.bits());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_power_of_two() {
+    let big = |v: u32| BigUint::from(v);
+    let pow2 = |k: usize| BigUint::one() << k;
+
+    assert!(!big(0).is_power_of_two());
+    assert_eq!(big(0).next_power_of_two(), big(1));
+    assert_eq!(big(0).prev_power_of_two(), big(0));
+
+    for k in 0..300 {
+        let p = pow2(k);
+        assert!(p.is_power_of_two());
+        assert_eq!(p.next_power_of_two(), p);
+        assert_eq!(p.prev_power_of_two(), p);
+
+        let above = &p + 1u32;
+        let below = &p * 2u32 - 1u32;
+        for n in &[above, below] {
+            if *n != big(1) && *n != big(2) {
+                assert!(!n.is_power_of_two(), "{}", n);
+                assert_eq!(n.next_power_of_two(), pow2(k + 1), "{}", n);
+                assert_eq!(n.prev_power_of_two(), p, "{}", n);
+            }
+        }
+    }
+
+    // Low digits that are nonzero beneath a power of two top digit.
+    let n = pow2(200) + pow2(3);
+    assert!(!n.is_power_of_two());
+    assert_eq!(n.next_power_of_two(), pow2(201));
+}
+
+#[test]
+fn test_is_power_of() {
+    let big = |v: u64| BigUint::from(v);
+    for base in &[2u64, 3, 7, 10, 16, 255, 256, 257, (1 << 32) - 1, 1 << 32] {
+        let base = big(*base);
+        let mut n = BigUint::one();
+        for k in 0..300u64 {
+            assert_eq!(n.is_power_of(&base), Some(k), "{}^{}", base, k);
+            if k > 1 {
+                assert_eq!((&n - 1u32).is_power_of(&base), None);
+                assert_eq!((&n + 1u32).is_power_of(&base), None);
+                if base != big(2) {
+                    assert_eq!((&n * 2u32).is_power_of(&base), None);
+                }
+            }
+            n *= &base;
+        }
+    }
+
+    // Not a power of the base, but a power of a related one.
+    assert_eq!(big(64).is_power_of(&big(4)), Some(3));
+    assert_eq!(big(32).is_power_of(&big(4)), None);
+    assert_eq!(big(4).is_power_of(&big(8)), None);
+    assert_eq!(big(6).is_power_of(&big(36)), None);
+
+    assert_eq!(big(0).is_power_of(&big(0)), Some(1));
+    assert_eq!(big(1).is_power_of(&big(0)), Some(0));
+    assert_eq!(big(5).is_power_of(&big(0)), None);
+    assert_eq!(big(1).is_power_of(&big(1)), Some(0));
+    assert_eq!(big(5).is_power_of(&big(1)), None);
+    assert_eq!(big(0).is_power_of(&big(5)), None);
+    assert_eq!(big(5).is_power_of(&big(5)), Some(1));
+}
+
+#[test]
+fn test_log2_f64() {
+    let one: BigUint = One::one();
+    for k in &[0usize, 1, 63, 64, 65, 127, 128, 129, 5000] {
+        assert_eq!((&one << *k).log2_f64(), *k as f64);
+    }
+
+    let close = |a: f64, b: f64| (a - b).abs() <= b * 1e-15;
+    let mut n = one.clone();
+    for k in 1..400 {
+        n *= 10u32;
+        assert!(close(n.log2_f64(), k as f64 * 10f64.log2()), "10^{}", k);
+        assert!(close(n.ln_f64(), k as f64 * 10f64.ln()), "10^{}", k);
+    }
+
+    // All ones: the bits below the leading 64 only nudge the result.
+    let n = (&one << 1000) - 1u32;
+    assert_eq!(n.log2_f64(), 1000.0);
+
+    assert_eq!(BigUint::zero().log2_f64(), f64::NEG_INFINITY);
+    assert_eq!(BigUint::zero().ln_f64(), f64::NEG_INFINITY);
+    assert_eq!(one.ln_f64(), 0.0);
+}
+
 #[test]
 fn test_iter_sum() {
     let result: BigUint = FromPrimitive::from_isize(1234567).unwrap();
@@ -1686,6 +2093,573 @@ fn test_iter_product_generic() {
     assert_eq!(result, data.into_iter().product());
 }
 
+#[test]
+fn test_iter_product_many() {
+    let data: Vec<BigUint> = (1u32..=1000).map(BigUint::from).collect();
+    let mut expected = BigUint::one();
+    for x in &data {
+        expected *= x;
+    }
+
+    assert_eq!(expected, data.iter().product());
+    assert_eq!(expected, (1u32..=1000).product());
+    assert_eq!(BigUint::one(), data[..0].iter().product());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_mul_parallel() {
+    // Large enough for Toom-3 to split its products across threads.
+    let x = BigUint::one() << 200_000;
+    let y = BigUint::one() << 150_000;
+    let a = &x - 12345u32;
+    let b = &y - 678u32;
+
+    // (x - 12345) * (y - 678) = x * y - 678 * x - 12345 * y + 12345 * 678
+    let expected = (BigUint::one() << 350_000) + 12345u32 * 678u32 - &x * 678u32 - &y * 12345u32;
+
+    assert!(num_bigint::is_parallel());
+    assert_eq!(&a * &b, expected);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_radix_parallel() {
+    // Big enough to be split around a power of the radix.
+    let ten = BigUint::from(10u32);
+    let s = ten.pow(40_000u32).to_str_radix(10);
+    assert_eq!(s.len(), 40_001);
+    assert!(s.starts_with('1') && s[1..].bytes().all(|b| b == b'0'));
+    assert_eq!(BigUint::from_str_radix(&s, 10).unwrap(), ten.pow(40_000u32));
+
+    let n = BigUint::from(3u32).pow(200_000u32) - 1u32;
+    for &radix in &[3, 10, 36] {
+        let s = n.to_str_radix(radix);
+        assert_eq!(BigUint::from_str_radix(&s, radix).unwrap(), n);
+        let low = &n % BigUint::from(radix).pow(20u32);
+        assert!(s.ends_with(&format!("{:0>20}", low.to_str_radix(radix))));
+    }
+}
+
+#[test]
+fn test_in_place_ops() {
+    let a = BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap();
+    let b = BigUint::from_str_radix("98765432109876543210987654321", 10).unwrap();
+
+    let mut x = a.clone();
+    x.add_assign_ref(&b);
+    assert_eq!(x, &a + &b);
+
+    x.mul_assign_reuse(&a, &b);
+    assert_eq!(x, &a * &b);
+    x.mul_assign_reuse(&a, &BigUint::zero());
+    assert!(x.is_zero());
+
+    let divisors = [
+        b.clone(),
+        BigUint::from(10u32),
+        &a * 2u32,
+        BigUint::one(),
+        a.clone(),
+    ];
+    let mut rem = BigUint::from(7u32);
+    for d in &divisors {
+        let mut q = a.clone();
+        q.div_rem_assign(d, &mut rem);
+        assert_eq!((q, rem.clone()), a.div_rem(d));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_div_rem_assign_zero() {
+    let mut rem = BigUint::zero();
+    BigUint::one().div_rem_assign(&BigUint::zero(), &mut rem);
+}
+
+#[test]
+fn test_div_rem_u64() {
+    let a = BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap();
+    let divisors = [1u64, 7, 10, u32::MAX as u64, u32::MAX as u64 + 1, u64::MAX];
+    for &d in &divisors {
+        let (q, r) = a.div_rem(&BigUint::from(d));
+        assert_eq!(a.div_rem_u64(d), (q, r.to_u64().unwrap()));
+        assert_eq!(a.rem_u64(d), r.to_u64().unwrap());
+    }
+    assert_eq!(BigUint::zero().div_rem_u64(3), (BigUint::zero(), 0));
+    assert_eq!(BigUint::zero().rem_u64(3), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_rem_u64_zero() {
+    BigUint::zero().rem_u64(0);
+}
+
+#[test]
+fn test_inv_mod_2k() {
+    let values = [
+        BigUint::one(),
+        BigUint::from(3u32),
+        BigUint::from(u64::MAX),
+        BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap(),
+        (BigUint::one() << 300) - 1u32,
+    ];
+    for a in &values {
+        for &k in &[1, 2, 3, 31, 32, 33, 63, 64, 65, 128, 200, 1000] {
+            let inv = a.inv_mod_2k(k).unwrap();
+            assert!(inv.bits() <= k);
+            assert_eq!(
+                (a * &inv).truncate_to_bits(k),
+                BigUint::one(),
+                "{} {}",
+                a,
+                k
+            );
+        }
+        assert_eq!(a.inv_mod_2k(0), Some(BigUint::zero()));
+    }
+    assert_eq!(BigUint::zero().inv_mod_2k(10), None);
+    assert_eq!(BigUint::from(10u32).inv_mod_2k(10), None);
+    assert_eq!(BigUint::from(10u32).inv_mod_2k(0), Some(BigUint::zero()));
+}
+
+#[test]
+fn test_divisible_by() {
+    let base = BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap();
+    let values = [
+        BigUint::zero(),
+        BigUint::one(),
+        base.clone(),
+        &base * 3u32 * 5u32 * 17u32 * 641u32,
+        &base * 7u32 * 11u32 * 1024u32,
+        &base * u64::MAX * 6700417u32,
+        &base * (u64::MAX - 58),
+        BigUint::one() << 200,
+    ];
+    let divisors = [
+        1u64,
+        2,
+        3,
+        5,
+        7,
+        12,
+        15,
+        17,
+        96,
+        255,
+        641,
+        6700417,
+        u32::MAX as u64,
+        1 << 40,
+        u64::MAX - 58,
+        u64::MAX,
+    ];
+    for n in &values {
+        for &d in &divisors {
+            let expected = (n % d).is_zero();
+            assert_eq!(n.divisible_by_u64(d), expected, "{} % {}", n, d);
+            assert_eq!(n.is_multiple_of(&BigUint::from(d)), expected);
+        }
+        assert_eq!(n.divisible_by_u64(0), n.is_zero());
+        assert_eq!(n.is_multiple_of(&BigUint::zero()), n.is_zero());
+
+        for m in &values[1..] {
+            assert_eq!(n.is_multiple_of(m), (n % m).is_zero());
+        }
+    }
+}
+
+#[test]
+fn test_from_limbs_const() {
+    const ZERO: BigUint = BigUint::from_limbs_const([0, 0]);
+    const LIMBS: BigUint = BigUint::from_limbs_const([1, 2, 3, 0]);
+    static LIMBS_STATIC: BigUint = BigUint::from_limbs_const([1, 2, 3]);
+
+    assert!(ZERO.is_zero());
+    assert_eq!(ZERO, BigUint::zero());
+    assert_eq!(LIMBS, BigUint::from_slice_native(&[1, 2, 3]));
+    assert_eq!(LIMBS_STATIC, LIMBS);
+    assert_eq!(&LIMBS_STATIC + 1u32, BigUint::from_slice_native(&[2, 2, 3]));
+}
+
+#[test]
+fn test_ref_view() {
+    for elm in DIV_REM_QUADRUPLES.iter() {
+        let (a_vec, b_vec, c_vec, d_vec) = *elm;
+        let a = BigUint::from_slice(a_vec);
+        let b = BigUint::from_slice(b_vec);
+        let c = BigUint::from_slice(c_vec);
+        let d = BigUint::from_slice(d_vec);
+        let (av, bv, cv, dv) = (
+            a.as_ref_view(),
+            b.as_ref_view(),
+            c.as_ref_view(),
+            d.as_ref_view(),
+        );
+
+        assert_eq!(bv * cv + d.clone(), a);
+        assert_eq!((bv * cv).as_ref_view() + dv, a);
+        assert_eq!(dv + (bv * cv).as_ref_view(), a);
+        assert_eq!(av - dv, &b * &c);
+        if !b.is_zero() {
+            assert_eq!(av / bv, a.div_rem(&b).0);
+            assert_eq!(av % bv, a.div_rem(&b).1);
+        }
+
+        assert_eq!(av.cmp(&bv), a.cmp(&b));
+        assert_eq!(av.bits(), a.bits());
+        assert_eq!(av.to_biguint(), a);
+        assert_eq!(
+            format!("{} {:x} {:b}", av, av, av),
+            format!("{} {:x} {:b}", a, a, a)
+        );
+        assert_eq!(hash(&av), hash(&a));
+    }
+
+    // High zero digits in a borrowed buffer are ignored.
+    let digits = [5, 0, 0];
+    let view = BigUintRef::new(&digits);
+    assert_eq!(view.digits(), &[5]);
+    assert_eq!(view.to_biguint(), BigUint::from(5u32));
+    assert!(BigUintRef::new(&[0, 0]).is_zero());
+}
+
+#[test]
+fn test_workspace() {
+    let a = BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap();
+    let b = BigUint::from_str_radix("98765432109876543210987654321", 10).unwrap();
+    let mut ws = Workspace::new();
+
+    let mut x = a.clone();
+    x.mul_with_workspace(&b, &mut ws);
+    assert_eq!(x, &a * &b);
+
+    let divisors = [
+        b.clone(),
+        BigUint::from(10u32),
+        &a * 2u32,
+        a.clone(),
+        &b << 61,
+    ];
+    let mut rem = BigUint::zero();
+    for d in &divisors {
+        let mut q = a.clone();
+        q.div_rem_with_workspace(d, &mut rem, &mut ws);
+        assert_eq!((q, rem.clone()), a.div_rem(d));
+
+        let mut x = a.clone();
+        x.mul_mod_with_workspace(&b, d, &mut ws);
+        assert_eq!(x, &a * &b % d);
+    }
+
+    for m in &[b.clone(), &b + 1u32, BigUint::one(), BigUint::from(1024u32)] {
+        for e in &[BigUint::zero(), BigUint::one(), a.clone()] {
+            assert_eq!(a.modpow_with_workspace(e, m, &mut ws), a.modpow(e, m));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_workspace_div_rem_zero() {
+    let mut x = BigUint::from(u64::MAX) << 100;
+    let mut rem = BigUint::zero();
+    x.div_rem_with_workspace(&BigUint::zero(), &mut rem, &mut Workspace::new());
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_workspace_mul_mod_zero() {
+    let mut x = BigUint::from(u64::MAX) << 100;
+    x.mul_mod_with_workspace(&BigUint::one(), &BigUint::zero(), &mut Workspace::new());
+}
+
+#[test]
+fn test_mul_div() {
+    let values = [
+        BigUint::zero(),
+        BigUint::one(),
+        BigUint::from(7u32),
+        BigUint::from(u64::MAX),
+        (BigUint::one() << 150) - 1u32,
+        BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap(),
+    ];
+    for a in &values {
+        for b in &values {
+            for c in values.iter().filter(|c| !c.is_zero()) {
+                let (q, r) = (a * b).div_rem(c);
+                assert_eq!(a.mul_div_rem(b, c), (q.clone(), r.clone()));
+                assert_eq!(a.mul_div_floor(b, c), q);
+                let exact = if r.is_zero() { Some(q) } else { None };
+                assert_eq!(a.mul_div_exact(b, c), exact);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_mul_div_zero() {
+    BigUint::one().mul_div_floor(&BigUint::one(), &BigUint::zero());
+}
+
+#[test]
+fn test_low_high_bits() {
+    let n = BigUint::from_str_radix("123456789abcdef0fedcba9876543210aaaa5555", 16).unwrap();
+    for bits in 0..200 {
+        let low = &n % (BigUint::one() << bits);
+        let high = &n >> bits;
+        assert_eq!(n.low_bits(bits), low);
+        assert_eq!(n.high_bits(bits), high);
+
+        let mut m = n.clone();
+        m.high_bits_assign(bits);
+        assert_eq!(m, high, "{}", bits);
+        // A normalized result has a nonzero top digit.
+        assert_eq!(m.bits(), n.bits().saturating_sub(bits));
+    }
+
+    let mut zero = BigUint::zero();
+    zero.high_bits_assign(10);
+    assert!(zero.is_zero());
+    assert!(BigUint::zero().high_bits(0).is_zero());
+}
+
+#[test]
+fn test_bit_reverse() {
+    fn naive(n: &BigUint, width: usize) -> BigUint {
+        let mut ret = BigUint::zero();
+        for i in 0..width {
+            if (n >> i) & BigUint::one() == BigUint::one() {
+                ret += BigUint::one() << (width - 1 - i);
+            }
+        }
+        ret
+    }
+
+    let n = BigUint::from_str_radix("123456789abcdef0fedcba9876543210aaaa5555", 16).unwrap();
+    for width in 0..200 {
+        assert_eq!(n.bit_reverse(width), naive(&n, width), "{}", width);
+    }
+    assert!(BigUint::zero().bit_reverse(100).is_zero());
+    assert!(BigUint::zero().bit_reverse(usize::MAX).is_zero());
+    assert_eq!(BigUint::one().bit_reverse(130), BigUint::one() << 129);
+
+    // Reversing twice within the width gives back the low bits.
+    let m = Pow::pow(&BigUint::from(3u32), 200u32);
+    for width in &[1, 63, 64, 65, 128, 317, 400] {
+        assert_eq!(
+            m.bit_reverse(*width).bit_reverse(*width),
+            m.low_bits(*width)
+        );
+    }
+}
+
+#[test]
+fn test_split_concat_bits() {
+    let n = BigUint::from_str_radix("123456789abcdef0fedcba9876543210aaaa5555", 16).unwrap();
+    for at in 0..200 {
+        let (high, low) = n.split_bits(at);
+        assert_eq!(high, &n >> at);
+        assert!(low.bits() <= at);
+        assert_eq!(BigUint::concat_bits(&high, &low, at), n);
+        // Wider low parts leave a gap of zeros.
+        let wide = BigUint::concat_bits(&high, &low, at + 70);
+        assert_eq!(wide, (&high << (at + 70)) + &low);
+    }
+
+    let zero = BigUint::zero();
+    assert_eq!(zero.split_bits(5), (BigUint::zero(), BigUint::zero()));
+    let low = BigUint::from(7u32);
+    assert_eq!(BigUint::concat_bits(&zero, &low, 3), low);
+    assert_eq!(BigUint::concat_bits(&low, &zero, 100), &low << 100);
+}
+
+#[test]
+#[should_panic(expected = "low part does not fit")]
+fn test_concat_bits_overlap() {
+    BigUint::concat_bits(&BigUint::one(), &BigUint::from(8u32), 3);
+}
+
+#[test]
+fn test_mul_shr() {
+    let one = BigUint::one();
+    let mut values = vec![BigUint::zero(), one.clone(), BigUint::from(u64::MAX)];
+    for bits in &[100, 500, 1000, 2100, 2500] {
+        let ones = (&one << *bits) - 1u32;
+        // Products close to a power of two carry across every digit.
+        values.push(ones.clone());
+        values.push(&ones + 2u32);
+        values.push(&one << *bits);
+        values.push(Pow::pow(&BigUint::from(3u32), *bits * 5 / 8));
+    }
+
+    for a in &values {
+        for b in &values {
+            for k in &[0, 1, 64, 127, 192, 200, 500, 1000, 1024, 1999, 3000, 5000] {
+                assert_eq!(a.mul_shr(b, *k), (a * b) >> *k, "{} {} {}", a, b, k);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_mul_low_high_bits() {
+    let one = BigUint::one();
+    let mut values = vec![BigUint::zero(), one.clone(), BigUint::from(u64::MAX)];
+    for bits in &[100, 500, 1000, 2500] {
+        let ones = (&one << *bits) - 1u32;
+        values.push(ones.clone());
+        values.push(&ones + 2u32);
+        values.push(Pow::pow(&BigUint::from(3u32), *bits * 5 / 8));
+    }
+
+    for a in &values {
+        for b in &values {
+            let product = a * b;
+            for n in &[0, 1, 64, 127, 192, 200, 500, 1000, 1999, 3000, 5000] {
+                assert_eq!(
+                    a.mul_low_bits(b, *n),
+                    product.truncate_to_bits(*n),
+                    "{} {} {}",
+                    a,
+                    b,
+                    n
+                );
+
+                let exact = &product >> *n;
+                let approx = a.mul_high_bits(b, *n);
+                let bound = BigUint::from(*n / 32);
+                assert!(approx <= exact, "{} {} {}", a, b, n);
+                assert!(exact - approx <= bound, "{} {} {}", a, b, n);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_multinomial() {
+    fn factorial(n: u64) -> BigUint {
+        (1..=n).fold(BigUint::one(), |f, i| f * i)
+    }
+
+    assert_eq!(BigUint::multinomial(&[]), BigUint::one());
+    assert_eq!(BigUint::multinomial(&[0, 0]), BigUint::one());
+    assert_eq!(BigUint::multinomial(&[7]), BigUint::one());
+
+    let cases: &[&[u64]] = &[
+        &[1, 1],
+        &[3, 5],
+        &[0, 4, 2],
+        &[10, 20, 30, 40],
+        &[1; 12],
+        &[150, 2, 77],
+    ];
+    for ns in cases {
+        let n = ns.iter().sum();
+        let denominator = ns.iter().map(|&k| factorial(k)).product::<BigUint>();
+        assert_eq!(
+            BigUint::multinomial(ns),
+            factorial(n) / denominator,
+            "{:?}",
+            ns
+        );
+    }
+
+    let big = BigUint::from(1000u32);
+    assert_eq!(
+        BigUint::multinomial(&[300, 700]),
+        num_integer::binomial(big, BigUint::from(300u32))
+    );
+}
+
+#[test]
+fn test_budget() {
+    let a = BigUint::from_str_radix("123456789012345678901234567890123456789", 10).unwrap();
+    let b = BigUint::from_str_radix("98765432109876543210987654321", 10).unwrap();
+    let long = Pow::pow(&a, 40u32) + 12345u32;
+    let mut budget = Budget::unlimited();
+
+    for x in &[BigUint::zero(), a.clone(), long.clone()] {
+        for y in &[BigUint::zero(), BigUint::one(), b.clone(), long.clone()] {
+            assert_eq!(x.mul_with_budget(y, &mut budget), Ok(x * y));
+            if !y.is_zero() {
+                assert_eq!(x.div_rem_with_budget(y, &mut budget), Ok(x.div_rem(y)));
+            }
+        }
+        for radix in &[2, 10, 16, 36] {
+            let s = x.to_str_radix_with_budget(*radix, &mut budget);
+            assert_eq!(s, Ok(x.to_str_radix(*radix)));
+        }
+    }
+    for m in &[b.clone(), &b + 1u32, BigUint::one(), BigUint::from(1024u32)] {
+        for e in &[BigUint::zero(), BigUint::one(), a.clone()] {
+            assert_eq!(a.modpow_with_budget(e, m, &mut budget), Ok(a.modpow(e, m)));
+        }
+    }
+    assert_eq!(budget.remaining_work(), None);
+
+    // Work is deducted across calls until it runs out.
+    let mut budget = Budget::unlimited().with_work_limit(1_000);
+    assert!(a.mul_with_budget(&b, &mut budget).is_ok());
+    assert!(budget.remaining_work().unwrap() < 1_000);
+    let ops: [&dyn Fn(&mut Budget) -> bool; 4] = [
+        &|budget| long.mul_with_budget(&long, budget).is_ok(),
+        &|budget| long.div_rem_with_budget(&b, budget).is_ok(),
+        &|budget| long.to_str_radix_with_budget(10, budget).is_ok(),
+        &|budget| a.modpow_with_budget(&long, &b, budget).is_ok(),
+    ];
+    for op in &ops {
+        let mut budget = Budget::unlimited().with_work_limit(50);
+        assert!(!op(&mut budget));
+    }
+
+    // A cancellation flag stops work whatever the limit.
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut budget = Budget::unlimited().with_cancel_flag(flag.clone());
+    assert!(a.mul_with_budget(&b, &mut budget).is_ok());
+    flag.store(true, AtomicOrdering::Relaxed);
+    assert_eq!(
+        a.mul_with_budget(&b, &mut budget),
+        Err(BudgetExceeded::Cancelled)
+    );
+
+    let past = Instant::now();
+    let mut budget = Budget::unlimited().with_deadline(past);
+    assert_eq!(
+        a.modpow_with_budget(&long, &(&b + 1u32), &mut budget),
+        Err(BudgetExceeded::Deadline)
+    );
+}
+
+#[test]
+fn test_budget_large_mul() {
+    // Operands this long are multiplied in several budgeted blocks.
+    let x = Pow::pow(&BigUint::from(3u32), 150_000u32);
+    let y = (&x >> 70_000) + 7u32;
+    let ones = (BigUint::one() << 200_000) - 1u32;
+    let mut budget = Budget::unlimited();
+    for (a, b) in &[(&x, &x), (&x, &y), (&y, &ones), (&ones, &ones)] {
+        assert_eq!(a.mul_with_budget(b, &mut budget), Ok(*a * *b));
+    }
+
+    // The cancellation flag is seen in the middle of one large product of
+    // similar sized operands.
+    let big = (BigUint::one() << (1 << 26)) - 1u32;
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut budget = Budget::unlimited().with_cancel_flag(flag.clone());
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        flag.store(true, AtomicOrdering::Relaxed);
+    });
+    assert_eq!(
+        big.mul_with_budget(&big, &mut budget),
+        Err(BudgetExceeded::Cancelled)
+    );
+    canceller.join().unwrap();
+}
+
 #[test]
 fn test_pow() {
     let one = BigUint::from(1u32);
@@ -1713,3 +2687,402 @@ fn test_pow() {
     #[cfg(has_i128)]
     check!(u128);
 }
+
+#[test]
+fn test_midpoint() {
+    fn check(a: &BigUint, b: &BigUint) {
+        let expected = (a + b) >> 1;
+        assert_eq!(a.midpoint(b), expected);
+        assert_eq!(b.midpoint(a), expected);
+    }
+
+    let big = BigUint::one() << 1000;
+    check(&BigUint::zero(), &BigUint::zero());
+    check(&BigUint::zero(), &BigUint::one());
+    check(&BigUint::from(7u32), &BigUint::from(10u32));
+    check(&big, &BigUint::from(3u32));
+    check(&(&big - 1u32), &big);
+    check(&(&big * &big), &(&big + 12345u32));
+}
+
+#[test]
+fn test_pow_biguint_exponent() {
+    let two = BigUint::from(2u32);
+    let three = BigUint::from(3u32);
+    let exp = BigUint::from(100u32);
+    assert_eq!(two.pow(&exp), BigUint::one() << 100);
+    assert_eq!(three.pow(exp.clone()), three.pow(100u32));
+    assert_eq!(BigUint::zero().pow(&BigUint::zero()), BigUint::one());
+    assert_eq!(BigUint::zero().pow(&exp), BigUint::zero());
+
+    // Trivial bases never overflow, no matter how large the exponent.
+    let huge = BigUint::one() << 200;
+    assert_eq!(BigUint::one().pow(&huge), BigUint::one());
+    assert_eq!(BigUint::zero().pow(&huge), BigUint::zero());
+
+    assert_eq!(two.checked_pow_bits(&exp, 101), Some(BigUint::one() << 100));
+    assert_eq!(two.checked_pow_bits(&exp, 100), None);
+    assert_eq!(three.checked_pow_bits(&huge, usize::MAX), None);
+}
+
+#[test]
+#[should_panic(expected = "pow result exceeds")]
+fn test_pow_biguint_exponent_too_large() {
+    let _ = BigUint::from(2u32).pow(&(BigUint::one() << 64));
+}
+
+#[test]
+fn test_mod_ops() {
+    let moduli = [
+        BigUint::one(),
+        BigUint::from(10u32),
+        BigUint::from(u64::MAX),
+        (BigUint::one() << 130) + 3u32,
+    ];
+
+    for m in &moduli {
+        let xs = [
+            BigUint::zero(),
+            BigUint::one(),
+            m - 1u32,
+            m.clone(),
+            m >> 1,
+            m * 3u32 + 2u32,
+        ];
+        let mut ws = Workspace::new();
+
+        for x in &xs {
+            assert_eq!(x.neg_mod(m), (m - x % m) % m);
+
+            for y in &xs {
+                let sum = (x + y) % m;
+                let diff = (x % m + m - y % m) % m;
+                let prod = x * y % m;
+
+                assert_eq!(x.add_mod(y, m), sum);
+                assert_eq!(x.sub_mod(y, m), diff);
+                assert_eq!(x.mul_mod(y, m), prod);
+
+                let mut z = x.clone();
+                z.add_mod_assign(y, m);
+                assert_eq!(z, sum);
+                z.sub_mod_assign(y, m);
+                assert_eq!(z, x % m);
+                z.neg_mod_assign(m);
+                assert_eq!(z, x.neg_mod(m));
+                z.neg_mod_assign(m);
+                z.mul_mod_with_workspace(y, m, &mut ws);
+                assert_eq!(z, prod);
+
+                let mut z = x.clone();
+                z.mul_mod_assign(y, m);
+                assert_eq!(z, prod);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_add_mod_zero() {
+    BigUint::one().add_mod(&BigUint::one(), &BigUint::zero());
+}
+
+#[test]
+fn test_to_wnaf() {
+    fn check(n: &BigUint, w: usize, naf: &[i8]) {
+        // The digits add up to n.
+        let (mut pos, mut neg) = (BigUint::zero(), BigUint::zero());
+        for (i, &d) in naf.iter().enumerate() {
+            if d > 0 {
+                pos += BigUint::from(d as u8) << i;
+            } else if d < 0 {
+                neg += BigUint::from(d.unsigned_abs()) << i;
+            }
+        }
+        assert_eq!(&(pos - neg), n);
+
+        // Digits are odd, bounded and separated.
+        assert_ne!(naf.last(), Some(&0));
+        for (i, &d) in naf.iter().enumerate() {
+            if d != 0 {
+                assert_eq!(d % 2, d.signum());
+                assert!(i16::from(d).abs() < 1 << (w - 1));
+                assert!(naf[i + 1..].iter().take(w - 1).all(|&e| e == 0));
+            }
+        }
+        assert!(naf.len() <= n.bits() + 1);
+    }
+
+    assert!(BigUint::zero().to_naf().is_empty());
+    let naf_255 = vec![-1, 0, 0, 0, 0, 0, 0, 0, 1];
+    assert_eq!(BigUint::from(255u32).to_naf(), naf_255);
+    assert_eq!(BigUint::from(255u32).to_wnaf(8), naf_255);
+    assert_eq!(BigUint::from(127u32).to_wnaf(8), vec![127]);
+
+    let mut n = BigUint::from(0x1234_5678_9abc_def0u64);
+    for _ in 0..20 {
+        for w in 2..=8 {
+            check(&n, w, &n.to_wnaf(w));
+        }
+        assert_eq!(n.to_naf(), n.to_wnaf(2));
+        n = &n * &n + 0xfedcu32;
+        let shift = n.bits() / 3;
+        n >>= shift;
+    }
+}
+
+#[test]
+#[should_panic(expected = "w must be between 2 and 8")]
+fn test_to_wnaf_window_too_large() {
+    BigUint::from(1u32).to_wnaf(9);
+}
+
+#[test]
+fn test_trial_divide() {
+    fn smallest_factor(n: u64, bound: u64) -> Option<u64> {
+        (2..bound).take_while(|d| d * d <= n).find(|d| n % d == 0)
+    }
+
+    for n in 0..3000u64 {
+        for &bound in &[0, 2, 3, 10, 31, 100, 1 << 20] {
+            assert_eq!(
+                BigUint::from(n).trial_divide(bound),
+                smallest_factor(n, bound),
+                "{} {}",
+                n,
+                bound
+            );
+        }
+    }
+
+    // 2^64 + 13 is prime; multiply by primes on either side of a wheel turn.
+    let big = (BigUint::one() << 64) + 13u32;
+    for &p in &[2u64, 3, 5, 7, 29, 31, 37, 997, 65521] {
+        let n = &big * p;
+        assert_eq!(n.trial_divide(p), None);
+        assert_eq!(n.trial_divide(p + 1), Some(p));
+        assert_eq!((&n * &big).trial_divide(1 << 20), Some(p));
+    }
+    assert_eq!(big.trial_divide(1 << 16), None);
+    assert_eq!((&big * &big).trial_divide(1 << 16), None);
+}
+
+#[test]
+fn test_barrett_reducer() {
+    let moduli = [
+        BigUint::one(),
+        BigUint::from(10u32),
+        BigUint::from(u32::MAX),
+        BigUint::from(u64::MAX) - 1u32,
+        BigUint::one() << 64,
+        (BigUint::one() << 200) - 14u32,
+        BigUint::from_str_radix("d3b6a5f3_00c1_0f52_9e64_1d2a_f0a7_3b33_c8e1_5d09_7a11", 16)
+            .unwrap(),
+    ];
+
+    for m in &moduli {
+        let reducer = BarrettReducer::new(m);
+        assert_eq!(reducer.modulus(), m);
+
+        let mut xs = vec![BigUint::zero(), m - 1u32, m.clone(), m + 1u32];
+        xs.push(m * m - 1u32);
+        xs.push(m * m);
+        xs.push(m * m * m + 7u32);
+        xs.push((BigUint::one() << (2 * m.bits() + 3)) - 1u32);
+        xs.push(BigUint::from(u64::MAX) * 12345u32);
+
+        for x in &xs {
+            let r = x % m;
+            assert_eq!(reducer.rem(x), r);
+            assert_eq!(x % &reducer, r);
+            assert_eq!(x.clone() % &reducer, r);
+
+            let mut y = x.clone();
+            y %= &reducer;
+            assert_eq!(y, r);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "known-primes")]
+fn test_known_prime_rem() {
+    let p256 = BigUint::parse_bytes(
+        b"ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+        16,
+    )
+    .unwrap();
+    let x = BigUint::parse_bytes(b"123456789abcdef0fedcba9876543210", 16).unwrap() << 380;
+    let y = &p256 - 5u32;
+
+    assert_eq!(&x % &p256, x.div_rem(&p256).1);
+    assert_eq!(x.mul_mod(&y, &p256), (&x * &y).div_rem(&p256).1);
+}
+
+#[test]
+#[should_panic]
+fn test_barrett_reducer_zero() {
+    BarrettReducer::new(&BigUint::zero());
+}
+
+#[test]
+fn test_special_modulus() {
+    let moduli = [
+        (BigUint::from(3u32), 2, 1u32),
+        (BigUint::from(4u32), 2, 0),
+        ((BigUint::one() << 61) - 1u32, 61, 1),
+        ((BigUint::one() << 64) - 59u32, 64, 59),
+        (BigUint::one() << 100, 100, 0),
+        ((BigUint::one() << 127) - 1u32, 127, 1),
+        ((BigUint::one() << 255) - 19u32, 255, 19),
+        ((BigUint::one() << 521) - 1u32, 521, 1),
+    ];
+
+    for (m, k, c) in &moduli {
+        let reducer = SpecialModulus::new(m).unwrap();
+        assert_eq!(reducer.modulus(), m);
+        assert_eq!(reducer.form(), (*k, &BigUint::from(*c)));
+
+        let mut xs = vec![BigUint::zero(), m - 1u32, m.clone(), m + 1u32];
+        xs.push(m * m - 1u32);
+        xs.push(m * m);
+        xs.push(m * m * m + 7u32);
+        xs.push((BigUint::one() << (2 * m.bits() + 3)) - 1u32);
+        xs.push(BigUint::from(u64::MAX) * 12345u32);
+
+        for x in &xs {
+            let r = x % m;
+            assert_eq!(reducer.rem(x), r);
+            assert_eq!(x % &reducer, r);
+            assert_eq!(x.clone() % &reducer, r);
+
+            let mut y = x.clone();
+            y %= &reducer;
+            assert_eq!(y, r);
+        }
+    }
+
+    let others = [
+        BigUint::zero(),
+        BigUint::one(),
+        BigUint::from(1_000_003u32),
+        (BigUint::one() << 100) - (BigUint::one() << 60),
+        (BigUint::one() << 100) + 1u32,
+    ];
+    for m in &others {
+        assert!(SpecialModulus::new(m).is_none());
+    }
+}
+
+#[test]
+fn test_debug_limbs() {
+    let n = (BigUint::one() << 64) + 5u32;
+    let s = format!("{:?}", n.debug_limbs());
+    assert!(s.starts_with("DebugLimbs { sign: Plus, bits: 65, limbs: [0x"));
+    assert!(s.ends_with("] }"));
+
+    // Every limb is padded to the full digit width, least significant first.
+    let limbs: Vec<&str> = s[s.find('[').unwrap() + 1..s.find(']').unwrap()]
+        .split(", ")
+        .collect();
+    assert!(limbs.iter().all(|l| l.len() == limbs[0].len()));
+    assert!(limbs[0].ends_with('5'));
+    assert!(limbs[limbs.len() - 1].ends_with('1'));
+    assert_eq!(limbs[0].len() - 2, 64 / (limbs.len() - 1) / 4);
+
+    let pretty = format!("{:#?}", n.debug_limbs());
+    assert_eq!(pretty.lines().count(), 6 + limbs.len());
+
+    assert_eq!(
+        format!("{:?}", BigUint::zero().debug_limbs()),
+        "DebugLimbs { sign: NoSign, bits: 0, limbs: [] }"
+    );
+}
+
+#[test]
+fn test_div_rem_with_strategy() {
+    let strategies = [
+        DivStrategy::Auto,
+        DivStrategy::Knuth,
+        DivStrategy::Montgomery,
+        DivStrategy::Binary,
+    ];
+    let three = BigUint::from(3u32);
+    let big = three.pow(300u32) + 12345u32;
+    let dividends = [
+        BigUint::zero(),
+        BigUint::from(7u32),
+        BigUint::from(u64::MAX),
+        big.clone(),
+        big.clone() << 100,
+    ];
+    let divisors = [
+        BigUint::one(),
+        BigUint::from(2u32),
+        BigUint::from(7u32),
+        BigUint::from(u64::MAX),
+        (BigUint::one() << 64) + 1u32,
+        (BigUint::one() << 130) * 6u32,
+        three.pow(150u32),
+        big.clone(),
+    ];
+    for u in &dividends {
+        for d in &divisors {
+            let expected = u.div_rem(d);
+            for &s in &strategies {
+                assert_eq!(
+                    u.div_rem_with_strategy(d, s),
+                    expected,
+                    "{} / {} with {:?}",
+                    u,
+                    d,
+                    s
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_div_rem_with_strategy_zero() {
+    BigUint::one().div_rem_with_strategy(&BigUint::zero(), DivStrategy::Montgomery);
+}
+
+#[test]
+fn test_remove_factor() {
+    let three = BigUint::from(3u32);
+    let rest = BigUint::from(1_000_001u32) * 7u32;
+    for v in [0u32, 1, 2, 3, 7, 8, 63, 64, 100, 255] {
+        let mut n = three.pow(v) * &rest;
+        assert_eq!(n.remove_factor(&three), u64::from(v));
+        assert_eq!(n, rest);
+    }
+
+    // Composite and large factors.
+    let p = (BigUint::one() << 89) - 1u32;
+    let mut n = p.pow(5u32) * 12u32;
+    assert_eq!(n.remove_factor(&p), 5);
+    assert_eq!(n, BigUint::from(12u32));
+    let mut n = BigUint::from(6u32).pow(10u32);
+    assert_eq!(n.remove_factor(&BigUint::from(36u32)), 5);
+    assert!(n.is_one());
+
+    // Powers of two.
+    let mut n = BigUint::from(5u32) << 130;
+    assert_eq!(n.remove_factor(&BigUint::from(8u32)), 43);
+    assert_eq!(n, BigUint::from(10u32));
+
+    let mut n = BigUint::zero();
+    assert_eq!(n.remove_factor(&three), 0);
+    let mut n = BigUint::one();
+    assert_eq!(n.remove_factor(&three), 0);
+    assert!(n.is_one());
+}
+
+#[test]
+#[should_panic]
+fn test_remove_factor_one() {
+    BigUint::from(5u32).remove_factor(&BigUint::one());
+}
@@ -101,3 +101,54 @@ fn test_scalar_div_rem() {
         }
     }
 }
+
+#[test]
+fn test_scalar_div_rem_assign() {
+    for elm in DIV_REM_QUADRUPLES.iter() {
+        let (a_vec, b_vec, c_vec, d_vec) = *elm;
+        let a = BigUint::from_slice(a_vec);
+        let b = BigUint::from_slice(b_vec);
+        let c = BigUint::from_slice(c_vec);
+        let d = BigUint::from_slice(d_vec);
+
+        if let Some(b) = b.to_u64() {
+            let mut q = a.clone();
+            q /= b;
+            assert_eq!(q, c);
+            let mut r = a.clone();
+            r %= b;
+            assert_eq!(r, d);
+        }
+        if let Some(b) = b.to_u128() {
+            let mut q = a.clone();
+            q /= b;
+            assert_eq!(q, c);
+            let mut r = a.clone();
+            r %= b;
+            assert_eq!(r, d);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_scalar_div_zero_by_zero() {
+    let _ = BigUint::zero() / 0u64;
+}
+
+#[test]
+#[should_panic]
+fn test_scalar_rem_zero_by_zero() {
+    let _ = &BigUint::zero() % 0u32;
+}
+
+#[test]
+fn test_scalar_ref_assign() {
+    let mut x = BigUint::from(100u32);
+    x += &7u8;
+    x -= &3u64;
+    x *= &2u32;
+    x /= &4usize;
+    x %= &30u128;
+    assert_eq!(x, BigUint::from(22u32));
+}
@@ -0,0 +1,141 @@
+//! Test the C interface, calling it the way C code would.
+
+#![cfg(feature = "capi")]
+
+extern crate num_bigint_dig as num_bigint;
+
+use crate::num_bigint::capi::*;
+use crate::num_bigint::BigUint;
+use std::ptr;
+
+unsafe fn from_bytes(bytes: &[u8]) -> *mut BigUint {
+    let mut n = ptr::null_mut();
+    assert_eq!(
+        num_bigint_from_bytes_be(bytes.as_ptr(), bytes.len(), &mut n),
+        NumBigintStatus::Ok
+    );
+    n
+}
+
+unsafe fn to_bytes(n: *const BigUint) -> Vec<u8> {
+    let mut len = 0;
+    assert_eq!(
+        num_bigint_to_bytes_be(n, ptr::null_mut(), 0, &mut len),
+        NumBigintStatus::BufferTooSmall
+    );
+    let mut buf = vec![0; len];
+    assert_eq!(
+        num_bigint_to_bytes_be(n, buf.as_mut_ptr(), buf.len(), &mut len),
+        NumBigintStatus::Ok
+    );
+    assert_eq!(len, buf.len());
+    buf
+}
+
+#[test]
+fn test_round_trip() {
+    unsafe {
+        let zero = num_bigint_new();
+        assert_eq!(to_bytes(zero), [0]);
+        num_bigint_free(zero);
+
+        let empty = from_bytes(&[]);
+        assert_eq!(to_bytes(empty), [0]);
+        num_bigint_free(empty);
+
+        let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let n = from_bytes(&bytes);
+        assert_eq!(*n, BigUint::from_bytes_be(&bytes));
+        assert_eq!(to_bytes(n), bytes);
+
+        // A larger buffer is fine, and only the leading bytes are written.
+        let mut buf = [0xff; 16];
+        let mut len = 0;
+        assert_eq!(
+            num_bigint_to_bytes_be(n, buf.as_mut_ptr(), buf.len(), &mut len),
+            NumBigintStatus::Ok
+        );
+        assert_eq!(len, 12);
+        assert_eq!(buf[..12], bytes);
+        assert_eq!(buf[12..], [0xff; 4]);
+        num_bigint_free(n);
+
+        num_bigint_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    unsafe {
+        let a = from_bytes(&[0xff; 20]);
+        let b = from_bytes(&[0x12, 0x34, 0x56, 0x78, 0x9a]);
+        let m = from_bytes(&[0x01, 0x00, 0x00, 0x00, 0x0f]);
+        let (ra, rb, rm) = ((*a).clone(), (*b).clone(), (*m).clone());
+
+        let mut out = ptr::null_mut();
+        assert_eq!(num_bigint_add(a, b, &mut out), NumBigintStatus::Ok);
+        assert_eq!(*out, &ra + &rb);
+        num_bigint_free(out);
+
+        assert_eq!(num_bigint_mul(a, a, &mut out), NumBigintStatus::Ok);
+        assert_eq!(*out, &ra * &ra);
+        num_bigint_free(out);
+
+        assert_eq!(num_bigint_div(a, b, &mut out), NumBigintStatus::Ok);
+        assert_eq!(*out, &ra / &rb);
+        num_bigint_free(out);
+
+        assert_eq!(num_bigint_modpow(b, a, m, &mut out), NumBigintStatus::Ok);
+        assert_eq!(*out, rb.modpow(&ra, &rm));
+        num_bigint_free(out);
+
+        for &n in &[a, b, m] {
+            num_bigint_free(n);
+        }
+    }
+}
+
+#[test]
+fn test_errors() {
+    unsafe {
+        let a = from_bytes(&[7]);
+        let zero = num_bigint_new();
+        let sentinel = 1 as *mut BigUint;
+        let mut out = sentinel;
+
+        assert_eq!(
+            num_bigint_div(a, zero, &mut out),
+            NumBigintStatus::DivideByZero
+        );
+        assert_eq!(
+            num_bigint_modpow(a, a, zero, &mut out),
+            NumBigintStatus::DivideByZero
+        );
+        assert_eq!(
+            num_bigint_add(a, ptr::null(), &mut out),
+            NumBigintStatus::NullPointer
+        );
+        assert_eq!(
+            num_bigint_mul(a, a, ptr::null_mut()),
+            NumBigintStatus::NullPointer
+        );
+        assert_eq!(
+            num_bigint_from_bytes_be(ptr::null(), 1, &mut out),
+            NumBigintStatus::NullPointer
+        );
+        assert_eq!(out, sentinel);
+
+        let mut buf = [0; 4];
+        assert_eq!(
+            num_bigint_to_bytes_be(a, buf.as_mut_ptr(), 4, ptr::null_mut()),
+            NumBigintStatus::NullPointer
+        );
+        assert_eq!(
+            num_bigint_to_bytes_be(a, ptr::null_mut(), 4, &mut 0),
+            NumBigintStatus::NullPointer
+        );
+
+        num_bigint_free(a);
+        num_bigint_free(zero);
+    }
+}
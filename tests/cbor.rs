@@ -0,0 +1,89 @@
+//! Test CBOR encoding and decoding of `BigUint` and `BigInt` bignums.
+
+#![cfg(feature = "cbor")]
+
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_rfc8949_examples() {
+    // RFC 8949 appendix A
+    let big = BigUint::one() << 64;
+    assert_eq!(big.to_cbor(), hex("c249010000000000000000"));
+    assert_eq!(BigUint::from_cbor(&hex("c249010000000000000000")), Ok(big));
+
+    let neg = -(BigInt::one() << 64) - 1u32;
+    assert_eq!(neg.to_cbor(), hex("c349010000000000000000"));
+    assert_eq!(BigInt::from_cbor(&hex("c349010000000000000000")), Ok(neg));
+
+    // Plain integers are accepted too.
+    let max = BigUint::from(u64::MAX);
+    assert_eq!(BigUint::from_cbor(&hex("1bffffffffffffffff")), Ok(max));
+    let min = -(BigInt::one() << 64);
+    assert_eq!(BigInt::from_cbor(&hex("3bffffffffffffffff")), Ok(min));
+    assert_eq!(BigInt::from_cbor(&hex("17")), Ok(BigInt::from(23)));
+    assert_eq!(BigInt::from_cbor(&hex("20")), Ok(BigInt::from(-1)));
+}
+
+#[test]
+fn test_zero_and_minus_one() {
+    assert_eq!(BigUint::zero().to_cbor(), hex("c240"));
+    assert_eq!(BigInt::zero().to_cbor(), hex("c240"));
+    assert_eq!(BigInt::from(-1).to_cbor(), hex("c340"));
+    assert_eq!(BigUint::from_cbor(&hex("c240")), Ok(BigUint::zero()));
+    assert_eq!(BigInt::from_cbor(&hex("c340")), Ok(BigInt::from(-1)));
+
+    // Leading zero bytes are allowed when decoding.
+    assert_eq!(BigUint::from_cbor(&hex("c243000001")), Ok(BigUint::one()));
+}
+
+#[test]
+fn test_indefinite_length() {
+    let n = BigUint::from(0x01_0000u32);
+    assert_eq!(BigUint::from_cbor(&hex("c25f4101420000ff")), Ok(n));
+    assert_eq!(BigInt::from_cbor(&hex("c35fff")), Ok(BigInt::from(-1)));
+}
+
+#[test]
+fn test_round_trip() {
+    let mut n = BigInt::from(0x1234_5678i64);
+    for _ in 0..10 {
+        for m in &[n.clone(), -n.clone(), n.clone() - 1u32, -n.clone() - 1u32] {
+            assert_eq!(BigInt::from_cbor(&m.to_cbor()).as_ref(), Ok(m));
+        }
+        let u = n.to_biguint().unwrap();
+        assert_eq!(BigUint::from_cbor(&u.to_cbor()), Ok(u));
+        n = &n * &n + 7u32;
+    }
+}
+
+#[test]
+fn test_errors() {
+    for input in &["", "c2", "c249", "c24901", "c25f", "c25f41", "1b00"] {
+        let err = BigInt::from_cbor(&hex(input)).unwrap_err();
+        assert_eq!(err.to_string(), "CBOR input ended in the middle of an item");
+    }
+    for input in &["c24000", "0000"] {
+        let err = BigInt::from_cbor(&hex(input)).unwrap_err();
+        assert_eq!(err.to_string(), "CBOR input has bytes after the bignum");
+    }
+    // text string, tag 4, tag 2 on an integer, nested indefinite chunk, reserved length
+    for input in &["6161", "c440", "c201", "c25f5fffff", "1c"] {
+        let err = BigInt::from_cbor(&hex(input)).unwrap_err();
+        assert_eq!(err.to_string(), "CBOR item is not an integer or bignum");
+    }
+    for input in &["c340", "20"] {
+        let err = BigUint::from_cbor(&hex(input)).unwrap_err();
+        assert_eq!(err.to_string(), "CBOR integer is negative");
+    }
+}
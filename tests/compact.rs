@@ -0,0 +1,78 @@
+//! Test the compact binary encoding of `BigUint` and `BigInt`.
+
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::num_bigint::{BigInt, BigUint};
+use num_traits::{One, Pow, Zero};
+
+#[test]
+fn test_compact_biguint_roundtrip() {
+    let big = BigUint::from(3u32).pow(1000u32);
+    for n in [BigUint::zero(), BigUint::one(), BigUint::from(0xffu32), big] {
+        let bytes = n.to_compact_bytes();
+        assert_eq!(BigUint::from_compact_bytes(&bytes), Ok((n, &[][..])));
+    }
+
+    // A 200 byte magnitude needs a two byte length prefix.
+    let n = (BigUint::one() << 1600) - 1u32;
+    let bytes = n.to_compact_bytes();
+    assert_eq!(&bytes[..2], [0xc8, 0x01]);
+    assert_eq!(bytes.len(), 202);
+}
+
+#[test]
+fn test_compact_bigint_roundtrip() {
+    let big = BigInt::from(-7).pow(300u32);
+    for n in [
+        BigInt::zero(),
+        BigInt::one(),
+        -BigInt::one(),
+        big.clone(),
+        -big,
+    ] {
+        let bytes = n.to_compact_bytes();
+        assert_eq!(BigInt::from_compact_bytes(&bytes), Ok((n, &[][..])));
+    }
+    assert_eq!(BigInt::zero().to_compact_bytes(), [0]);
+    assert_eq!((-BigInt::one()).to_compact_bytes(), [3, 1]);
+}
+
+#[test]
+fn test_compact_self_delimiting() {
+    let a = BigInt::from(-1000);
+    let b = BigInt::from(u64::MAX);
+    let mut bytes = a.to_compact_bytes();
+    bytes.extend(b.to_compact_bytes());
+    bytes.push(0xaa);
+
+    let (x, rest) = BigInt::from_compact_bytes(&bytes).unwrap();
+    let (y, rest) = BigInt::from_compact_bytes(rest).unwrap();
+    assert_eq!((x, y, rest), (a, b, &[0xaa][..]));
+}
+
+#[test]
+fn test_compact_rejects() {
+    // Truncated prefix and body.
+    assert!(BigUint::from_compact_bytes(&[]).is_err());
+    assert!(BigUint::from_compact_bytes(&[0x80]).is_err());
+    assert!(BigUint::from_compact_bytes(&[2, 1]).is_err());
+    // Overlong prefix.
+    assert!(BigUint::from_compact_bytes(&[0x81, 0x00, 1]).is_err());
+    // Trailing zero byte in the magnitude.
+    assert!(BigUint::from_compact_bytes(&[2, 1, 0]).is_err());
+    assert!(BigUint::from_compact_bytes(&[1, 0]).is_err());
+    // Negative zero.
+    assert!(BigInt::from_compact_bytes(&[1]).is_err());
+    // Prefixes that overflow a u64.
+    assert!(BigUint::from_compact_bytes(&[0xff; 11]).is_err());
+    assert!(BigUint::from_compact_bytes(&[
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02
+    ])
+    .is_err());
+    // A length larger than the input.
+    assert!(BigUint::from_compact_bytes(&[
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01
+    ])
+    .is_err());
+}
@@ -0,0 +1,66 @@
+//! Test conversions between `BigUint` and `crypto-bigint` types.
+
+#![cfg(feature = "crypto-bigint")]
+
+extern crate crypto_bigint;
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crate::crypto_bigint::{U128, U256};
+use crate::num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::convert::TryFrom;
+
+#[test]
+fn test_uint_round_trip() {
+    let values = [
+        BigUint::zero(),
+        BigUint::one(),
+        BigUint::from(0x0123_4567_89ab_cdefu64),
+        (BigUint::one() << 128) - 1u32,
+    ];
+    for n in &values {
+        let u = U256::try_from(n).unwrap();
+        assert_eq!(BigUint::from(&u), *n);
+        assert_eq!(BigUint::from(U256::try_from(n.clone()).unwrap()), *n);
+    }
+
+    let u = U128::from_be_hex("0123456789abcdeffedcba9876543210");
+    let n = BigUint::parse_bytes(b"0123456789abcdeffedcba9876543210", 16).unwrap();
+    assert_eq!(BigUint::from(&u), n);
+    assert_eq!(U128::try_from(&n).unwrap(), u);
+}
+
+#[test]
+fn test_uint_out_of_range() {
+    let max = (BigUint::one() << 128) - 1u32;
+    assert_eq!(U128::try_from(&max).unwrap(), U128::MAX);
+
+    let too_big = BigUint::one() << 128;
+    assert!(U128::try_from(&too_big).is_err());
+    let err = U128::try_from(too_big.clone()).unwrap_err();
+    assert_eq!(err.into_original(), too_big);
+}
+
+#[test]
+fn test_dyn_residue() {
+    let modulus = BigUint::parse_bytes(
+        b"ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+        16,
+    )
+    .unwrap();
+    let params = DynResidueParams::<4>::try_from(&modulus).unwrap();
+    assert_eq!(BigUint::from(&params), modulus);
+
+    let x = BigUint::from(0xdead_beefu32) << 200;
+    let y = BigUint::from(0x1234_5678u32) << 100;
+    let rx = DynResidue::new(&U256::try_from(&x).unwrap(), params);
+    let ry = DynResidue::new(&U256::try_from(&y).unwrap(), params);
+    assert_eq!(BigUint::from(&(rx * ry)), (&x * &y) % &modulus);
+
+    let even = DynResidueParams::<4>::try_from(&(&modulus + 1u32)).unwrap_err();
+    assert_eq!(even.to_string(), "modulus must be odd");
+    let too_big = DynResidueParams::<2>::try_from(&modulus).unwrap_err();
+    assert_ne!(too_big, even);
+}
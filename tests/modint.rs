@@ -0,0 +1,105 @@
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::num_bigint::{from_montgomery, to_montgomery, BigUint, ModInt, ModulusParams};
+
+use num_traits::{Num, One, Pow, Zero};
+
+fn moduli() -> Vec<BigUint> {
+    vec![
+        BigUint::one(),
+        BigUint::from(3u32),
+        BigUint::from(1_000_000_007u32),
+        BigUint::from(u64::MAX),
+        (BigUint::one() << 127) - 1u32,
+        BigUint::from_str_radix("d3b6a5f3_00c1_0f52_9e64_1d2a_f0a7_3b33_c8e1_5d09_7a11", 16)
+            .unwrap(),
+    ]
+}
+
+fn values(m: &BigUint) -> Vec<BigUint> {
+    vec![
+        BigUint::zero(),
+        BigUint::one(),
+        BigUint::from(2u32),
+        m - 1u32,
+        m >> 1,
+        m * 3u32 + 5u32,
+    ]
+}
+
+#[test]
+fn test_modint_ops() {
+    for m in &moduli() {
+        let params = ModulusParams::new(m);
+        for x in &values(m) {
+            let a = ModInt::new(x, &params);
+            assert_eq!(a.to_biguint(), x % m);
+            assert_eq!((-&a).to_biguint(), (m - x % m) % m);
+
+            for y in &values(m) {
+                let b = ModInt::new(y, &params);
+                assert_eq!((&a + &b).to_biguint(), (x + y) % m);
+                assert_eq!((&a - &b).to_biguint(), (x % m + m - y % m) % m);
+                assert_eq!((&a * &b).to_biguint(), x * y % m);
+
+                let mut c = a.clone();
+                c += &b;
+                c *= b.clone();
+                c -= &a;
+                assert_eq!(c.to_biguint(), ((x + y) * y % m + m - x % m) % m);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_modint_pow_inverse() {
+    for m in &moduli() {
+        let params = ModulusParams::new(m);
+        for x in &values(m) {
+            let a = ModInt::new(x, &params);
+            for e in &[BigUint::zero(), BigUint::one(), m.clone(), m * m + 3u32] {
+                assert_eq!((&a).pow(e).to_biguint(), x.modpow(e, m));
+            }
+
+            match a.inverse() {
+                Some(inv) => assert_eq!((inv * &a).to_biguint(), BigUint::one() % m),
+                None => assert!(!m.is_one()),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_montgomery_batch() {
+    for m in &moduli() {
+        let params = ModulusParams::new(m);
+        let xs = values(m);
+
+        let residues = to_montgomery(&xs, &params);
+        assert_eq!(residues.len(), xs.len());
+        for (a, x) in residues.iter().zip(&xs) {
+            assert_eq!(*a, ModInt::new(x, &params));
+        }
+
+        let back = from_montgomery(&residues);
+        let expected: Vec<BigUint> = xs.iter().map(|x| x % m).collect();
+        assert_eq!(back, expected);
+    }
+    assert!(to_montgomery(&[], &ModulusParams::new(&BigUint::from(7u32))).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "different moduli")]
+fn test_modint_mixed_moduli() {
+    let a = ModInt::one(&ModulusParams::new(&BigUint::from(7u32)));
+    let b = ModInt::one(&ModulusParams::new(&BigUint::from(11u32)));
+    let _ = a + b;
+}
+
+#[test]
+#[should_panic(expected = "odd")]
+fn test_modulus_params_even() {
+    ModulusParams::new(&BigUint::from(10u32));
+}
@@ -57,9 +57,9 @@ static BIG_R: &'static str = "\
                               109c4735_6e7db425_7b5d74c7_0b709508";
 
 mod biguint {
-    use crate::num_bigint::BigUint;
+    use crate::num_bigint::{BigUint, FixedBasePow, ModInverse, ModPowOptions, ModulusParams};
     use num_integer::Integer;
-    use num_traits::Num;
+    use num_traits::{Num, One, Pow, Zero};
 
     fn check_modpow<T: Into<BigUint>>(b: T, e: T, m: T, r: T) {
         let b: BigUint = b.into();
@@ -68,6 +68,9 @@ mod biguint {
         let r: BigUint = r.into();
 
         assert_eq!(b.modpow(&e, &m), r);
+        if m.is_odd() {
+            assert_eq!(b.modpow_with_params(&e, &ModulusParams::new(&m)), r);
+        }
 
         let even_m = &m << 1;
         let even_modpow = b.modpow(&e, &even_m);
@@ -97,6 +100,148 @@ mod biguint {
         assert!(even_modpow < even_m);
         assert_eq!(even_modpow % m, r);
     }
+
+    #[test]
+    fn test_modpow_with_params() {
+        let m = BigUint::from_str_radix(super::BIG_M, 16).unwrap();
+        let params = ModulusParams::new(&m);
+        let e = BigUint::from_str_radix(super::BIG_E, 16).unwrap();
+
+        let mut b = BigUint::from_str_radix(super::BIG_B, 16).unwrap();
+        for _ in 0..8 {
+            assert_eq!(b.modpow_with_params(&e, &params), b.modpow(&e, &m));
+            b = &b * 3u32 + 1u32;
+        }
+    }
+
+    #[test]
+    fn test_modpow_with_options() {
+        let b = BigUint::from_str_radix(super::BIG_B, 16).unwrap();
+        let e = BigUint::from_str_radix(super::BIG_E, 16).unwrap();
+        let m = BigUint::from_str_radix(super::BIG_M, 16).unwrap();
+
+        let moduli = [
+            BigUint::one(),
+            BigUint::from(2u32),
+            BigUint::from(12u32),
+            BigUint::one() << 70,
+            BigUint::from(1_000_000_007u32),
+            &m << 3,
+            m,
+        ];
+        let exponents = [
+            BigUint::zero(),
+            BigUint::one(),
+            BigUint::from(65537u32),
+            e >> 1800,
+        ];
+
+        for m in &moduli {
+            for e in &exponents {
+                let expected = b.modpow(e, m);
+                for window_bits in 1..=8 {
+                    for &constant_time in &[false, true] {
+                        for &use_crt_for_even_modulus in &[false, true] {
+                            let options = ModPowOptions {
+                                window_bits,
+                                constant_time,
+                                use_crt_for_even_modulus,
+                            };
+                            assert_eq!(b.modpow_with_options(e, m, &options), expected);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "window_bits")]
+    fn test_modpow_with_options_window_too_large() {
+        let options = ModPowOptions {
+            window_bits: 9,
+            ..ModPowOptions::default()
+        };
+        BigUint::from(3u32).modpow_with_options(
+            &BigUint::from(5u32),
+            &BigUint::from(7u32),
+            &options,
+        );
+    }
+
+    #[test]
+    fn test_modpow_crt() {
+        let p = BigUint::from_str_radix(super::BIG_M, 16).unwrap();
+        let q = BigUint::from(1_000_000_007u32);
+        let n = &p * &q;
+        let qinv = q.clone().mod_inverse(&p).unwrap().to_biguint().unwrap();
+
+        let b = BigUint::from_str_radix(super::BIG_B, 16).unwrap();
+        let d = BigUint::from_str_radix(super::BIG_E, 16).unwrap();
+        let dp = &d % (&p - 1u32);
+        let dq = &d % (&q - 1u32);
+
+        for x in &[
+            BigUint::zero(),
+            BigUint::one(),
+            q.clone(),
+            &b % &n,
+            &n - 1u32,
+        ] {
+            let m = x.modpow_crt(&dp, &dq, &p, &q, &qinv);
+            assert!(m < n);
+            assert_eq!(&m % &p, x.modpow(&dp, &p));
+            assert_eq!(&m % &q, x.modpow(&dq, &q));
+        }
+    }
+
+    #[test]
+    fn test_modpow_batch() {
+        let odd = BigUint::from(3u32).pow(80u32) + 2u32;
+        let even = &odd + 1u32;
+        let big = BigUint::from(7u32).pow(100u32);
+        let bases = vec![
+            BigUint::zero(),
+            BigUint::one(),
+            BigUint::from(2u32),
+            odd.clone() - 1u32,
+            big.clone(),
+        ];
+        let exps = [BigUint::zero(), BigUint::one(), big.clone()];
+        for m in [BigUint::one(), odd, even] {
+            for e in &exps {
+                let expected: Vec<BigUint> = bases.iter().map(|b| b.modpow(e, &m)).collect();
+                assert_eq!(BigUint::modpow_batch(&bases, e, &m), expected);
+            }
+        }
+        assert!(BigUint::modpow_batch(&[], &big, &big).is_empty());
+    }
+
+    #[test]
+    fn test_fixed_base_pow() {
+        let odd = BigUint::from(3u32).pow(80u32) + 2u32;
+        let even = &odd + 1u32;
+        let g = BigUint::from(7u32).pow(60u32);
+        let exps = [
+            BigUint::zero(),
+            BigUint::one(),
+            BigUint::from(0xf0f0u32),
+            (BigUint::one() << 127) - 1u32,
+            BigUint::one() << 128,
+            // Longer than the table, so computed by modpow.
+            BigUint::one() << 300,
+        ];
+        for m in [BigUint::one(), BigUint::from(2u32), odd, even] {
+            for w in [1, 3, 4, 8] {
+                let table = FixedBasePow::with_window(&g, &m, 129, w);
+                assert_eq!(table.base(), &g);
+                assert_eq!(table.modulus(), &m);
+                for e in &exps {
+                    assert_eq!(table.pow(e), g.modpow(e, &m), "{}^{} mod {}", g, e, m);
+                }
+            }
+        }
+    }
 }
 
 mod bigint {
@@ -157,4 +302,28 @@ mod bigint {
 
         check_modpow(b, e, m, r);
     }
+
+    #[test]
+    fn test_modpow_negative_exponent() {
+        // 3^-1 = 4 (mod 11), so 3^-2 = 16 = 5 (mod 11)
+        check_modpow(3, -1, 11, 4);
+        assert_eq!(
+            BigInt::from(-3).modpow(&BigInt::from(-2), &BigInt::from(11)),
+            BigInt::from(5)
+        );
+        check_modpow(5, -117, 19, 1);
+
+        let b = BigInt::from(7);
+        let m = BigInt::from(1000003);
+        let e = BigInt::from(-12345);
+        let x = b.modpow(&e, &m);
+        assert!(x.is_positive());
+        assert_eq!((x * b.modpow(&-e, &m)).mod_floor(&m), BigInt::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "not invertible")]
+    fn test_modpow_negative_exponent_not_invertible() {
+        BigInt::from(6).modpow(&BigInt::from(-1), &BigInt::from(9));
+    }
 }
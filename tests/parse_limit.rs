@@ -0,0 +1,35 @@
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::num_bigint::{parse_limit, set_parse_limit, BigInt, BigUint};
+
+use num_traits::Num;
+
+// The limit is global, so it is exercised in its own test binary.
+#[test]
+fn test_parse_limit() {
+    assert_eq!(parse_limit(), None);
+    let long = "9".repeat(1000);
+    assert!(BigUint::from_str_radix(&long, 10).is_ok());
+
+    set_parse_limit(Some(64));
+    assert_eq!(parse_limit(), Some(64));
+    assert!(BigUint::from_str_radix(&long, 10).is_err());
+    assert!(long.parse::<BigInt>().is_err());
+    assert!(BigUint::parse_bytes(b"18446744073709551616", 10).is_none());
+    assert_eq!(
+        BigUint::parse_bytes(b"18446744073709551615", 10),
+        Some(BigUint::from(u64::MAX))
+    );
+    assert_eq!(
+        "-18446744073709551615".parse::<BigInt>().ok(),
+        Some(-BigInt::from(u64::MAX))
+    );
+
+    // An explicit bound takes precedence over the global one.
+    assert!(BigUint::from_str_radix_bounded(&long, 10, 4000).is_ok());
+
+    set_parse_limit(None);
+    assert_eq!(parse_limit(), None);
+    assert!(BigUint::from_str_radix(&long, 10).is_ok());
+}
@@ -102,6 +102,23 @@ mod biguint {
         }
     }
 
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_os_random() {
+        let mut seen_top = false;
+        for _ in 0..100 {
+            let n = BigUint::random(100);
+            assert!(n.bits() <= 100);
+            seen_top |= n.bits() == 100;
+        }
+        assert!(seen_top);
+
+        let bound = BigUint::from(1000u32);
+        for _ in 0..100 {
+            assert!(BigUint::random_below(&bound) < bound);
+        }
+    }
+
     fn seeded_value_stability<R: SeedableRng + RandBigInt>(expected: &[&str]) {
         let mut seed = <R::Seed>::default();
         for (i, x) in seed.as_mut().iter_mut().enumerate() {
@@ -222,8 +239,8 @@ mod biguint {
 }
 
 mod bigint {
-    use crate::num_bigint::{BigInt, RandBigInt, RandomBits};
-    use num_traits::Zero;
+    use crate::num_bigint::{BigInt, RandBigInt, RandomBits, Sign};
+    use num_traits::{ToPrimitive, Zero};
     use rand::distributions::Uniform;
     use rand::{Rng, SeedableRng};
 
@@ -280,6 +297,41 @@ mod bigint {
         check(-u.clone(), -l.clone());
     }
 
+    #[test]
+    fn test_rand_range_spanning_zero() {
+        let mut rng = thread_rng();
+        let l = BigInt::from(-3);
+        let u = BigInt::from(3);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let n = rng.gen_bigint_range(&l, &u);
+            assert!(n >= l && n < u);
+            seen[(n - &l).to_usize().unwrap()] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+
+        let l = -(BigInt::from(1) << 100);
+        let u = BigInt::from(1) << 90;
+        for _ in 0..1000 {
+            let n = rng.gen_bigint_range(&l, &u);
+            assert!(n >= l && n < u);
+        }
+    }
+
+    #[test]
+    fn test_rand_nonuniform_sign() {
+        let mut rng = thread_rng();
+        let (mut pos, mut neg) = (false, false);
+        for _ in 0..100 {
+            let n = rng.gen_bigint_nonuniform_sign(70);
+            assert!(n.bits() <= 70);
+            pos |= n.sign() == Sign::Plus;
+            neg |= n.sign() == Sign::Minus;
+        }
+        assert!(pos && neg);
+        assert!(rng.gen_bigint_nonuniform_sign(0).is_zero());
+    }
+
     #[test]
     #[should_panic]
     fn test_zero_rand_range() {
@@ -441,10 +493,46 @@ mod bigint {
     }
 }
 
+mod modpow {
+    use crate::num_bigint::BigUint;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_modpow_blinded() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+
+        // RSA key with p = 1000000007 and q = 998244353.
+        let p = BigUint::from(1_000_000_007u32);
+        let q = BigUint::from(998_244_353u32);
+        let n = &p * &q;
+        let phi = (&p - 1u32) * (&q - 1u32);
+        let e = BigUint::from(65537u32);
+        let d = BigUint::from(78_519_762_354_634_753u64);
+        assert_eq!(&e * &d % &phi, BigUint::from(1u32));
+
+        for x in &[0u64, 1, 2, 1_000_000_007, 123_456_789_012_345] {
+            let x = BigUint::from(*x);
+            let expected = x.modpow(&d, &n);
+            assert_eq!(x.modpow_blinded(&mut rng, &d, &n, None, None), expected);
+            assert_eq!(x.modpow_blinded(&mut rng, &d, &n, Some(&e), None), expected);
+            assert_eq!(
+                x.modpow_blinded(&mut rng, &d, &n, None, Some(&phi)),
+                expected
+            );
+            assert_eq!(
+                x.modpow_blinded(&mut rng, &d, &n, Some(&e), Some(&phi)),
+                expected
+            );
+        }
+    }
+}
+
 #[cfg(feature = "prime")]
 mod prime {
     use num_bigint::prime::probably_prime;
-    use num_bigint::RandPrime;
+    use num_bigint::{BigUint, RandPrime};
+    use num_integer::Integer;
+    use num_traits::Zero;
     use rand::prelude::*;
 
     #[test]
@@ -464,4 +552,24 @@ mod prime {
         let p = rng.gen_prime(1024);
         assert_eq!(p.bits(), 1024);
     }
+
+    #[test]
+    fn test_gen_prime_candidate() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        for &bits in &[2, 3, 8, 9, 64, 65, 300] {
+            for &sieve in &[false, true] {
+                for _ in 0..50 {
+                    let c = rng.gen_prime_candidate(bits, sieve);
+                    assert_eq!(c.bits(), bits);
+                    assert!(c.is_odd());
+                    assert_eq!(&c >> (bits - 2), BigUint::from(3u32));
+                    if sieve && bits > 6 {
+                        for p in &[3u32, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53] {
+                            assert!(!(&c % p).is_zero(), "{} has factor {}", c, p);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
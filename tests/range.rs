@@ -0,0 +1,62 @@
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use crate::num_bigint::range::{range, range_inclusive, range_step};
+use crate::num_bigint::{BigInt, BigUint};
+
+use num_traits::One;
+
+#[test]
+fn test_range() {
+    let big = BigUint::one() << 100;
+    let v: Vec<BigUint> = range(big.clone(), &big + 3u32).collect();
+    assert_eq!(v, vec![big.clone(), &big + 1u32, &big + 2u32]);
+
+    let v: Vec<BigUint> = range(&big + 0u32, &big + 3u32).rev().collect();
+    assert_eq!(v, vec![&big + 2u32, &big + 1u32, big.clone()]);
+
+    assert_eq!(range(big.clone(), big.clone()).count(), 0);
+    assert_eq!(range(&big + 1u32, big.clone()).count(), 0);
+
+    let v: Vec<BigInt> = range(BigInt::from(-2), BigInt::from(2)).collect();
+    let expected: Vec<BigInt> = (-2..2).map(BigInt::from).collect();
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn test_range_inclusive() {
+    let v: Vec<BigUint> = range_inclusive(BigUint::from(3u32), BigUint::from(5u32)).collect();
+    let expected: Vec<BigUint> = (3u32..=5).map(BigUint::from).collect();
+    assert_eq!(v, expected);
+
+    assert_eq!(
+        range_inclusive(BigUint::from(5u32), BigUint::from(5u32)).count(),
+        1
+    );
+    assert_eq!(
+        range_inclusive(BigUint::from(6u32), BigUint::from(5u32)).count(),
+        0
+    );
+}
+
+#[test]
+fn test_range_step() {
+    let v: Vec<BigUint> = range_step(
+        BigUint::from(1u32),
+        BigUint::from(10u32),
+        BigUint::from(3u32),
+    )
+    .collect();
+    let expected: Vec<BigUint> = (1u32..10).step_by(3).map(BigUint::from).collect();
+    assert_eq!(v, expected);
+
+    let v: Vec<BigInt> = range_step(BigInt::from(5), BigInt::from(-5), BigInt::from(-4)).collect();
+    let expected: Vec<BigInt> = vec![5, 1, -3].into_iter().map(BigInt::from).collect();
+    assert_eq!(v, expected);
+}
+
+#[test]
+#[should_panic]
+fn test_range_step_zero() {
+    range_step(BigInt::from(0), BigInt::from(1), BigInt::from(0));
+}
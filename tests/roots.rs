@@ -41,6 +41,29 @@ mod biguint {
         check(120u32, 2);
     }
 
+    #[test]
+    fn test_sqrt_rem() {
+        let one = BigUint::one();
+        let mut values = vec![BigUint::zero(), one.clone(), BigUint::from(u64::MAX)];
+        for bits in (60..700).step_by(37) {
+            let ones = (&one << bits) - 1u32;
+            let three = BigUint::from(3u32).pow(bits as u32 * 5 / 8);
+            values.push(&three * &three);
+            values.push(&three * &three - 1u32);
+            values.push(&three * (&three + 2u32));
+            values.push(three);
+            values.push(&one << bits);
+            values.push(ones);
+        }
+
+        for x in &values {
+            let (root, rem) = x.sqrt_rem();
+            assert_eq!(root, x.sqrt(), "{}", x);
+            assert_eq!(&root * &root + &rem, *x, "{}", x);
+            assert!(rem <= &root << 1, "{}", x);
+        }
+    }
+
     #[test]
     fn test_cbrt() {
         check(8u32, 3);
@@ -195,3 +218,28 @@ mod bigint {
         check(-8, 3);
     }
 }
+
+mod generic {
+    use crate::num_bigint::{BigInt, BigUint};
+    use num_integer::Roots;
+
+    // Written against num-integer only, so it must work the same for
+    // primitives and big integers.
+    fn roots<T: Roots>(x: &T) -> (T, T, T) {
+        (x.sqrt(), x.cbrt(), x.nth_root(5))
+    }
+
+    #[test]
+    fn test_roots_trait() {
+        for &x in &[0u64, 1, 2, 26, 27, 1_000_000, u64::max_value()] {
+            let (s, c, n) = roots(&x);
+            assert_eq!(roots(&BigUint::from(x)), (s.into(), c.into(), n.into()));
+        }
+
+        for &x in &[0i64, 7, -8, -1_000_001, i64::min_value()] {
+            let (c, n) = (x.cbrt(), x.nth_root(5));
+            assert_eq!(Roots::cbrt(&BigInt::from(x)), BigInt::from(c));
+            assert_eq!(Roots::nth_root(&BigInt::from(x), 5), BigInt::from(n));
+        }
+    }
+}
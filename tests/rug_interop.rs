@@ -0,0 +1,59 @@
+//! Test conversions between `BigUint`/`BigInt` and `rug::Integer`.
+
+#![cfg(feature = "rug")]
+
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+extern crate rug;
+
+use crate::num_bigint::{BigInt, BigUint};
+use num_traits::{One, Pow, Zero};
+use rug::Integer;
+use std::convert::TryFrom;
+
+fn samples() -> Vec<BigInt> {
+    let big = BigInt::from(3u32).pow(200u32);
+    vec![
+        BigInt::zero(),
+        BigInt::one(),
+        -BigInt::one(),
+        BigInt::from(u64::MAX),
+        BigInt::from(i64::MIN),
+        (BigInt::one() << 64) + 1u32,
+        big.clone(),
+        -big,
+    ]
+}
+
+#[test]
+fn test_round_trip() {
+    for n in samples() {
+        let i = Integer::from(&n);
+        assert_eq!(i.to_string(), n.to_string());
+        assert_eq!(BigInt::from(&i), n);
+        assert_eq!(BigInt::from(Integer::from(n.clone())), n);
+
+        match n.to_biguint() {
+            Some(u) => {
+                assert_eq!(Integer::from(&u), i);
+                assert_eq!(BigUint::try_from(&i), Ok(u));
+            }
+            None => {
+                assert!(BigUint::try_from(&i).is_err());
+                let err = BigUint::try_from(i.clone()).unwrap_err();
+                assert_eq!(err.into_original(), i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_differential() {
+    let a = BigInt::from(7u32).pow(150u32) - 12345u32;
+    let b = -BigInt::from(11u32).pow(60u32);
+    let (ia, ib) = (Integer::from(&a), Integer::from(&b));
+
+    assert_eq!(BigInt::from(Integer::from(&ia * &ib)), &a * &b);
+    assert_eq!(BigInt::from(Integer::from(&ia / &ib)), &a / &b);
+    assert_eq!(BigInt::from(Integer::from(&ia % &ib)), &a % &b);
+}
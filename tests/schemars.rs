@@ -0,0 +1,46 @@
+//! Test the `JsonSchema` implementations.
+
+#![cfg(feature = "schemars")]
+
+extern crate num_bigint_dig as num_bigint;
+extern crate schemars;
+
+use crate::num_bigint::{BigInt, BigUint};
+use schemars::schema::{InstanceType, Schema, SingleOrVec};
+use schemars::{schema_for, JsonSchema};
+
+fn pattern(schema: Schema) -> String {
+    let object = match schema {
+        Schema::Object(object) => object,
+        Schema::Bool(_) => panic!("expected a schema object"),
+    };
+    assert_eq!(
+        object.instance_type,
+        Some(SingleOrVec::Single(Box::new(InstanceType::String)))
+    );
+    object.string.unwrap().pattern.unwrap()
+}
+
+#[test]
+fn test_schema_patterns() {
+    let mut gen = schemars::gen::SchemaGenerator::default();
+    assert_eq!(pattern(BigUint::json_schema(&mut gen)), "^(0|[1-9][0-9]*)$");
+    assert_eq!(
+        pattern(BigInt::json_schema(&mut gen)),
+        "^(0|-?[1-9][0-9]*)$"
+    );
+}
+
+#[test]
+fn test_schema_inlined() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Account {
+        balance: BigInt,
+    }
+
+    let root = schema_for!(Account);
+    assert!(root.definitions.is_empty());
+    let balance = root.schema.object.unwrap().properties["balance"].clone();
+    assert_eq!(pattern(balance), "^(0|-?[1-9][0-9]*)$");
+}
@@ -8,11 +8,14 @@
 
 extern crate num_bigint_dig as num_bigint;
 extern crate num_traits;
+extern crate serde;
 extern crate serde_test;
 
+use crate::num_bigint::{serde_hex, serde_string};
 use crate::num_bigint::{BigInt, BigUint};
 use num_traits::{One, Zero};
-use serde_test::{assert_tokens, Token};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
 
 #[test]
 fn biguint_zero() {
@@ -101,3 +104,82 @@ fn bigint_factorial_100() {
 
     assert_tokens(&n, &tokens);
 }
+
+macro_rules! with_module {
+    ($name:ident, $ty:ty, $module:ident) => {
+        #[derive(Debug, PartialEq)]
+        struct $name($ty);
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $module::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $module::deserialize(deserializer).map($name)
+            }
+        }
+    };
+}
+
+with_module!(DecimalUint, BigUint, serde_string);
+with_module!(DecimalInt, BigInt, serde_string);
+with_module!(HexUint, BigUint, serde_hex);
+with_module!(HexInt, BigInt, serde_hex);
+
+#[test]
+fn string_round_trip() {
+    let n: BigUint = (1u8..31).product();
+    let s = "265252859812191058636308480000000";
+    assert_tokens(&DecimalUint(n.clone()), &[Token::Str(s)]);
+    assert_tokens(&DecimalInt(BigInt::from(n.clone())), &[Token::Str(s)]);
+    let neg = "-265252859812191058636308480000000";
+    assert_tokens(&DecimalInt(-BigInt::from(n)), &[Token::Str(neg)]);
+    assert_tokens(&DecimalUint(BigUint::zero()), &[Token::Str("0")]);
+    assert_tokens(&DecimalInt(BigInt::zero()), &[Token::Str("0")]);
+}
+
+#[test]
+fn hex_round_trip() {
+    let n = BigUint::from(0x4d2u32);
+    assert_tokens(&HexUint(n.clone()), &[Token::Str("0x4d2")]);
+    assert_tokens(&HexInt(-BigInt::from(n.clone())), &[Token::Str("-0x4d2")]);
+    assert_tokens(&HexUint(BigUint::zero()), &[Token::Str("0x0")]);
+
+    // Uppercase digits and leading zeros are accepted.
+    assert_de_tokens(&HexUint(n), &[Token::Str("0x04D2")]);
+}
+
+#[test]
+fn string_rejects_loose_formats() {
+    let expected = "a decimal integer string without leading zeros";
+    for s in &[
+        "", "+1", "-", "-0", "01", "1_000", " 1", "1 ", "0x1", "1.0", "1e3",
+    ] {
+        let msg = format!("invalid value: string {:?}, expected {}", s, expected);
+        assert_de_tokens_error::<DecimalInt>(&[Token::Str(*s)], &msg);
+    }
+
+    let msg = format!("invalid value: string \"-1\", expected {}", expected);
+    assert_de_tokens_error::<DecimalUint>(&[Token::Str("-1")], &msg);
+
+    // The default representation is not accepted either.
+    let msg = format!("invalid type: sequence, expected {}", expected);
+    assert_de_tokens_error::<DecimalUint>(&[Token::Seq { len: Some(0) }], &msg);
+}
+
+#[test]
+fn hex_rejects_loose_formats() {
+    let expected = "a hexadecimal integer string starting with 0x";
+    for s in &[
+        "", "4d2", "0X4d2", "0x", "-0x0", "+0x1", "0x_1", "0x1g", "0x-1", "-",
+    ] {
+        let msg = format!("invalid value: string {:?}, expected {}", s, expected);
+        assert_de_tokens_error::<HexInt>(&[Token::Str(*s)], &msg);
+    }
+
+    let msg = format!("invalid value: string \"-0x1\", expected {}", expected);
+    assert_de_tokens_error::<HexUint>(&[Token::Str("-0x1")], &msg);
+}
@@ -0,0 +1,63 @@
+//! Test the per-thread operation counters.
+
+#![cfg(feature = "stats")]
+
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use std::thread;
+
+use crate::num_bigint::{stats, BigUint};
+use num_traits::One;
+
+#[test]
+fn test_stats_counts() {
+    let x = (BigUint::one() << 2048) - 1u32;
+    let y = (BigUint::one() << 1024) + 1u32;
+
+    stats::reset();
+    assert_eq!(stats::snapshot(), stats::Stats::default());
+
+    let before = stats::snapshot();
+    let product = &x * &y;
+    let used = stats::snapshot() - before;
+    assert!(used.limb_muls > 0);
+    assert_eq!(used.limb_divs, 0);
+    assert!(used.allocations > 0);
+
+    let before = stats::snapshot();
+    assert_eq!(&product / &y, x);
+    let used = stats::snapshot() - before;
+    assert!(used.limb_divs > 0);
+
+    stats::reset();
+    assert_eq!(stats::snapshot(), stats::Stats::default());
+}
+
+#[test]
+fn test_stats_mul_reuses_owned_operand() {
+    let x = (BigUint::one() << 2048) - 1u32;
+    let three = BigUint::from(3u32);
+
+    let before = stats::snapshot();
+    let _ = &x * &three;
+    assert!((stats::snapshot() - before).allocations > 0);
+
+    let before = stats::snapshot();
+    let _ = x.clone() * &three;
+    let _ = three.clone() * x;
+    assert_eq!((stats::snapshot() - before).allocations, 0);
+}
+
+#[test]
+fn test_stats_per_thread() {
+    stats::reset();
+    thread::spawn(|| {
+        let x = (BigUint::one() << 4096) - 1u32;
+        let _ = &x * &x;
+        assert!(stats::snapshot().limb_muls > 0);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(stats::snapshot().limb_muls, 0);
+}
@@ -0,0 +1,92 @@
+//! Test the algorithm trace hook.
+//!
+//! The hook is global, so this lives in its own test binary.
+
+#![cfg(feature = "trace")]
+
+extern crate num_bigint_dig as num_bigint;
+extern crate num_traits;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::num_bigint::{set_trace_hook, Algorithm, AlgorithmChoice, BigUint};
+use num_traits::One;
+
+const ALGORITHMS: [Algorithm; 7] = [
+    Algorithm::Schoolbook,
+    Algorithm::Karatsuba,
+    Algorithm::Toom3,
+    Algorithm::DivDigit,
+    Algorithm::Knuth,
+    Algorithm::Montgomery,
+    Algorithm::PlainModpow,
+];
+
+static COUNTS: [AtomicUsize; 7] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+static KNUTH_LENS: AtomicUsize = AtomicUsize::new(0);
+
+fn record(choice: &AlgorithmChoice) {
+    let i = ALGORITHMS
+        .iter()
+        .position(|&a| a == choice.algorithm)
+        .unwrap();
+    COUNTS[i].fetch_add(1, Ordering::Relaxed);
+    if choice.algorithm == Algorithm::Knuth {
+        KNUTH_LENS.store(choice.lhs_len << 16 | choice.rhs_len, Ordering::Relaxed);
+    }
+}
+
+/// Runs `f` with the hook set, returning which algorithms it reported.
+fn traced<F: FnOnce()>(f: F) -> Vec<Algorithm> {
+    for count in &COUNTS {
+        count.store(0, Ordering::Relaxed);
+    }
+    set_trace_hook(Some(record));
+    f();
+    set_trace_hook(None);
+    ALGORITHMS
+        .iter()
+        .zip(&COUNTS)
+        .filter(|(_, count)| count.load(Ordering::Relaxed) > 0)
+        .map(|(&a, _)| a)
+        .collect()
+}
+
+#[test]
+fn test_trace_hook() {
+    let small = BigUint::from(12345u32);
+    let big = (BigUint::one() << 30_000) - 1u32;
+    let divisor = (BigUint::one() << 200) + 1u32;
+
+    assert_eq!(traced(|| drop(&small * &small)), [Algorithm::Schoolbook]);
+
+    let seen = traced(|| drop(&big * &big));
+    assert!(seen.contains(&Algorithm::Toom3));
+    assert!(seen.contains(&Algorithm::Karatsuba));
+
+    assert_eq!(traced(|| drop(&big / &small)), [Algorithm::DivDigit]);
+
+    let seen = traced(|| drop(&big / &divisor));
+    assert!(seen.contains(&Algorithm::Knuth));
+    let lens = KNUTH_LENS.load(Ordering::Relaxed);
+    let (lhs_len, rhs_len) = (lens >> 16, lens & 0xffff);
+    assert!(lhs_len > rhs_len && rhs_len > 1);
+
+    let seen = traced(|| drop(small.modpow(&small, &divisor)));
+    assert!(seen.contains(&Algorithm::Montgomery));
+    let seen = traced(|| drop(small.modpow(&small, &(&divisor << 1))));
+    assert!(seen.contains(&Algorithm::PlainModpow));
+
+    // Nothing is reported once the hook is removed.
+    traced(|| {});
+    drop(&big * &big);
+    assert!(COUNTS.iter().all(|c| c.load(Ordering::Relaxed) == 0));
+}